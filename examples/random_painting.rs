@@ -1,33 +1,34 @@
-use std::env;
 use rand::Rng;
-use visualife::colors::darker;
+use visualife::colors::Colormap;
+use visualife::geometry::Degrees;
 use visualife::shapes::{Circle, Rect};
-use visualife::style::{rgb_to_hex, Style};
+use visualife::style::Style;
 use visualife::SvgDrawing;
 
 fn main() {
     let draw_width = 1000.0;
     let (n_x, n_y) = (100, 100);
     let box_width = 9.0;
-    let mut rng = rand::thread_rng();
+    let mut rng = rand::rng();
     let mut drawing = SvgDrawing::new(draw_width, draw_width);
     let max_noise: f32 = 0.5;
+    let colormap = Colormap::plasma();
     for i in 0..n_x {
         let mut max_drop = i as f32 / 100.0 + 1.0;
         max_drop = max_drop * max_drop * max_drop;
         for j in 0..n_y {
             let mut style = Style::new();
-            let fill = rgb_to_hex(255, i * 255 / n_x, j * 255 / n_y);
-            style.set_stroke(&darker(&fill, 0.3).ok().unwrap());
-            style.set_fill(&fill);
-            style.set_opacity(rng.gen_range(0.6..=1.0));
-            style.set_stroke_width(rng.gen_range(0.5..=2.0));
-            let noise_x = rng.gen_range(-max_noise..max_noise);
-            let noise_y = rng.gen_range(-max_noise..max_noise) + rng.gen_range(max_drop/2.0..max_drop);
+            let fill = colormap.sample((i + j) as f32 / (n_x + n_y - 2) as f32);
+            style.set_stroke(fill.darken(0.3));
+            style.set_fill(fill);
+            style.set_opacity(rng.random_range(0.6..=1.0));
+            style.set_stroke_width(rng.random_range(0.5..=2.0));
+            let noise_x = rng.random_range(-max_noise..max_noise);
+            let noise_y = rng.random_range(-max_noise..max_noise) + rng.random_range(max_drop/2.0..max_drop);
             let x = i as f32 * draw_width / (n_x as f32) + noise_x;
             let y = j as f32 * draw_width / (n_y as f32) + noise_y;
             let id = format!("el_{i}_{j}");
-            if rng.gen_range(0.0..1.0) < 0.1 {
+            if rng.random_range(0.0..1.0) < 0.1 {
                 let mut circ = Circle::new(&id, x, y, box_width);
                 circ.style = style;
                 drawing.add_element(Box::new(circ));
@@ -35,8 +36,8 @@ fn main() {
             } else {
                 let mut rect = Rect::new(&id, x, y, box_width, box_width);
                 rect.style = style;
-                let angle = rng.gen_range(0.0..=i as f32 * j as f32 * 60.0 / (n_x * n_y) as f32);
-                rect.angle = angle;
+                let angle = rng.random_range(0.0..=i as f32 * j as f32 * 60.0 / (n_x * n_y) as f32);
+                rect.angle = Degrees(angle);
                 drawing.add_element(Box::new(rect));
                 // separated_approach.push(Box::new(rect));
             }
@@ -0,0 +1,118 @@
+//! Tools to check that colours remain legible and distinguishable.
+// --- simulation matrices are kept with the precision they are published with
+#![allow(clippy::excessive_precision)]
+use crate::colors::{Color, LinearRgb};
+
+/// A kind of colour vision deficiency (colour blindness)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorVisionDeficiency {
+    /// missing long-wavelength (red) cones
+    Protanopia,
+    /// missing medium-wavelength (green) cones
+    Deuteranopia,
+    /// missing short-wavelength (blue) cones
+    Tritanopia,
+}
+
+impl ColorVisionDeficiency {
+    /// Simulation matrix in linear RGB, from Machado, Oliveira and Fernandes (2009), severity 1.0
+    fn matrix(&self) -> [[f32; 3]; 3] {
+        match self {
+            ColorVisionDeficiency::Protanopia => [
+                [0.152286, 1.052583, -0.204868],
+                [0.114503, 0.786281, 0.099216],
+                [-0.003882, -0.048116, 1.051998]],
+            ColorVisionDeficiency::Deuteranopia => [
+                [0.367322, 0.860646, -0.227968],
+                [0.280085, 0.672501, 0.047413],
+                [-0.011820, 0.042940, 0.968881]],
+            ColorVisionDeficiency::Tritanopia => [
+                [1.255528, -0.076749, -0.178779],
+                [-0.078411, 0.930809, 0.147602],
+                [0.004733, 0.691367, 0.303900]],
+        }
+    }
+}
+
+impl ColorVisionDeficiency {
+    /// An SVG ``<filter>`` that shows whatever it's applied to as seen with this deficiency.
+    ///
+    /// The filter multiplies colours by the same matrix as [`Color::simulate_cvd()`], in linear RGB.
+    pub(crate) fn svg_filter(&self, id: &str) -> String {
+        let mut values: Vec<String> = vec![];
+        for row in self.matrix() {
+            values.extend(row.iter().map(|v| v.to_string()));
+            values.extend(["0".to_string(), "0".to_string()]);
+        }
+        values.extend(["0", "0", "0", "1", "0"].map(str::to_string));
+        format!("<filter id=\"{}\" color-interpolation-filters=\"linearRGB\"><feColorMatrix type=\"matrix\" values=\"{}\" /></filter>",
+                id, values.join(" "))
+    }
+}
+
+impl Color {
+    /// Relative luminance as defined by WCAG: 0.0 for black, 1.0 for white
+    pub fn relative_luminance(&self) -> f32 {
+        let LinearRgb { r, g, b } = self.to_linear_rgb();
+        0.2126 * r + 0.7152 * g + 0.0722 * b
+    }
+
+    /// WCAG contrast ratio between two colours, from 1.0 (no contrast) to 21.0 (black on white).
+    ///
+    /// WCAG requires at least 4.5 for regular text and 3.0 for large text. Alpha is ignored.
+    pub fn contrast_ratio(&self, other: &Color) -> f32 {
+        let (l1, l2) = (self.relative_luminance(), other.relative_luminance());
+        (l1.max(l2) + 0.05) / (l1.min(l2) + 0.05)
+    }
+
+    /// Returns black or white, whichever has the better contrast with this colour used as a background.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::colors::Color;
+    /// assert_eq!(Color::rgb(30, 30, 120).text_color(), Color::WHITE);
+    /// assert_eq!(Color::rgb(255, 220, 0).text_color(), Color::BLACK);
+    /// ```
+    pub fn text_color(&self) -> Color {
+        if self.contrast_ratio(&Color::BLACK) >= self.contrast_ratio(&Color::WHITE) { Color::BLACK } else { Color::WHITE }
+    }
+
+    /// Returns this colour as seen by a person with the given colour vision deficiency
+    pub fn simulate_cvd(&self, deficiency: ColorVisionDeficiency) -> Color {
+        let LinearRgb { r, g, b } = self.to_linear_rgb();
+        let [m0, m1, m2] = deficiency.matrix();
+        let row = |m: [f32; 3]| m[0] * r + m[1] * g + m[2] * b;
+        Color::from(LinearRgb { r: row(m0), g: row(m1), b: row(m2) }).with_alpha(self.a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::colors::{Color, ColorVisionDeficiency};
+
+    #[test]
+    fn contrast() {
+        assert!((Color::BLACK.contrast_ratio(&Color::WHITE) - 21.0).abs() < 1e-3);
+        assert_eq!(Color::rgb(10, 200, 30).contrast_ratio(&Color::rgb(10, 200, 30)), 1.0);
+        // --- WCAG reference: #777 on white is just below the 4.5 limit
+        assert!((Color::from_u32(0x777777).contrast_ratio(&Color::WHITE) - 4.48).abs() < 0.01);
+        assert_eq!(Color::BLACK.text_color(), Color::WHITE);
+    }
+
+    #[test]
+    fn simulate_color_blindness() {
+        let (red, green) = (Color::rgb(220, 30, 30), Color::rgb(30, 160, 30));
+        for cvd in [ColorVisionDeficiency::Protanopia, ColorVisionDeficiency::Deuteranopia] {
+            // --- red and green are hard to tell apart: their hues become nearly identical
+            let (r, g) = (red.simulate_cvd(cvd).to_oklch(), green.simulate_cvd(cvd).to_oklch());
+            assert!((r.h - g.h).abs() < 30.0, "{:?}: {:?} {:?}", cvd, r, g);
+        }
+        assert_eq!(Color::WHITE.simulate_cvd(ColorVisionDeficiency::Tritanopia), Color::WHITE);
+        assert_eq!(red.with_alpha(0.5).simulate_cvd(ColorVisionDeficiency::Tritanopia).a, 0.5);
+
+        let filter = ColorVisionDeficiency::Protanopia.svg_filter("f");
+        let values = filter.split("values=\"").nth(1).unwrap().split('"').next().unwrap();
+        assert_eq!(values.split(' ').count(), 20);
+        assert!(values.starts_with("0.152286 1.052583 -0.204868 0 0 ") && values.ends_with(" 0 0 0 1 0"));
+    }
+}
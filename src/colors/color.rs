@@ -0,0 +1,285 @@
+use std::fmt;
+use std::str::FromStr;
+use crate::colors::named::named_color;
+use crate::colors::Hsl;
+
+/// Error returned when a string is not a valid CSS colour
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColorParseError {
+    /// The string was empty or contained only whitespace
+    Empty,
+    /// A word that is not one of the CSS colour keywords
+    UnknownName(String),
+    /// A ``#`` notation with a wrong number of digits or a non-hexadecimal digit
+    InvalidHex(String),
+    /// A functional notation other than ``rgb()``, ``rgba()``, ``hsl()``, ``hsla()`` or ``hwb()``
+    UnknownFunction(String),
+    /// A functional notation with a wrong number of arguments or a missing closing parenthesis
+    InvalidArguments(String),
+    /// An argument of a functional notation that is not a valid number, percentage or angle
+    InvalidComponent(String),
+}
+
+impl fmt::Display for ColorParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColorParseError::Empty => write!(f, "empty colour string"),
+            ColorParseError::UnknownName(name) => write!(f, "unknown colour name \"{}\"", name),
+            ColorParseError::InvalidHex(hex) => write!(f, "invalid hexadecimal colour \"{}\"", hex),
+            ColorParseError::UnknownFunction(name) => write!(f, "unknown colour function \"{}\"", name),
+            ColorParseError::InvalidArguments(s) => write!(f, "invalid arguments of colour function \"{}\"", s),
+            ColorParseError::InvalidComponent(c) => write!(f, "invalid colour component \"{}\"", c),
+        }
+    }
+}
+
+impl std::error::Error for ColorParseError {}
+
+/// An sRGB colour with an alpha channel.
+///
+/// A colour can be parsed from any CSS colour syntax: a colour keyword, a hexadecimal notation
+/// (``#rgb``, ``#rgba``, ``#rrggbb``, ``#rrggbbaa``) or one of the ``rgb()``, ``rgba()``,
+/// ``hsl()``, ``hsla()`` and ``hwb()`` functions. It's formatted back in the shortest
+/// hexadecimal notation.
+///
+/// # Example
+/// ```
+/// use visualife::colors::Color;
+/// let c: Color = "rgb(255 0 0 / 50%)".parse().unwrap();
+/// assert_eq!(c, Color::rgba(255, 0, 0, 0.5));
+/// assert_eq!(c.to_string(), "#FF000080");
+/// assert_eq!("hsl(120, 100%, 25%)".parse::<Color>().unwrap().to_string(), "#008000");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    /// opacity of this colour, from 0.0 (transparent) to 1.0 (opaque)
+    pub a: f32,
+}
+
+impl Color {
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+    pub const TRANSPARENT: Color = Color::rgba(0, 0, 0, 0.0);
+
+    /// Creates an opaque colour
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Self { Color { r, g, b, a: 1.0 } }
+
+    /// Creates a colour with the given opacity
+    pub const fn rgba(r: u8, g: u8, b: u8, a: f32) -> Self { Color { r, g, b, a } }
+
+    /// Creates an opaque colour from a ``0xRRGGBB`` integer
+    pub const fn from_u32(rgb: u32) -> Self {
+        Color::rgb((rgb >> 16) as u8, (rgb >> 8) as u8, rgb as u8)
+    }
+
+    /// Parses the ``#rgb``, ``#rgba``, ``#rrggbb`` or ``#rrggbbaa`` notation
+    pub fn from_hex(hex: &str) -> Result<Self, ColorParseError> {
+        let invalid = || ColorParseError::InvalidHex(hex.to_string());
+        let digits = hex.strip_prefix('#').ok_or_else(invalid)?;
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) { return Err(invalid()); }
+        let channel = |i: usize, width: usize| {
+            let v = u8::from_str_radix(&digits[i * width..(i + 1) * width], 16).unwrap();
+            if width == 1 { v * 17 } else { v }
+        };
+        let width = match digits.len() {
+            3 | 4 => 1,
+            6 | 8 => 2,
+            _ => return Err(invalid()),
+        };
+        let a = if digits.len() % 4 == 0 { channel(3, width) as f32 / 255.0 } else { 1.0 };
+
+        Ok(Color::rgba(channel(0, width), channel(1, width), channel(2, width), a))
+    }
+
+    /// Returns a copy of this colour with a new opacity
+    pub fn with_alpha(&self, a: f32) -> Self { Color { a: a.clamp(0.0, 1.0), ..*self } }
+
+    /// Returns ``true`` if this colour is fully opaque
+    pub fn is_opaque(&self) -> bool { self.alpha_u8() == 255 }
+
+    /// Formats this colour as ``#RRGGBB``, ignoring its alpha channel
+    pub fn to_hex(&self) -> String { format!("#{:02X}{:02X}{:02X}", self.r, self.g, self.b) }
+
+    fn alpha_u8(&self) -> u8 { (self.a.clamp(0.0, 1.0) * 255.0).round() as u8 }
+
+    /// Creates a colour from hue in degrees and saturation and lightness in the range [0, 1]
+    pub fn from_hsl(h: f32, s: f32, l: f32) -> Self { Hsl { h, s, l }.into() }
+
+    /// Creates a colour from hue in degrees and whiteness and blackness in the range [0, 1]
+    pub fn from_hwb(h: f32, w: f32, b: f32) -> Self {
+        let (w, b) = (w.clamp(0.0, 1.0), b.clamp(0.0, 1.0));
+        if w + b >= 1.0 {
+            let gray = to_u8(w / (w + b));
+            return Color::rgb(gray, gray, gray);
+        }
+        let hue = Color::from_hsl(h, 1.0, 0.5);
+        let f = |c: u8| to_u8(c as f32 / 255.0 * (1.0 - w - b) + w);
+        Color::rgb(f(hue.r), f(hue.g), f(hue.b))
+    }
+}
+
+impl Default for Color {
+    fn default() -> Self { Color::BLACK }
+}
+
+impl fmt::Display for Color {
+    /// Writes the shortest hexadecimal notation of this colour
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let a = self.alpha_u8();
+        let mut channels = vec![self.r, self.g, self.b];
+        if a != 255 { channels.push(a); }
+        if channels.iter().all(|c| c % 17 == 0) {
+            write!(f, "#")?;
+            for c in channels { write!(f, "{:X}", c / 17)?; }
+        } else {
+            write!(f, "#")?;
+            for c in channels { write!(f, "{:02X}", c)?; }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Color {
+    type Err = ColorParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() { return Err(ColorParseError::Empty); }
+        if s.starts_with('#') { return Color::from_hex(s); }
+        if s.contains('(') { return parse_function(s); }
+
+        let name = s.to_ascii_lowercase();
+        if name == "transparent" { return Ok(Color::TRANSPARENT); }
+        named_color(&name).map(Color::from_u32).ok_or(ColorParseError::UnknownName(s.to_string()))
+    }
+}
+
+impl From<(u8, u8, u8)> for Color {
+    fn from((r, g, b): (u8, u8, u8)) -> Self { Color::rgb(r, g, b) }
+}
+
+pub(super) fn to_u8(v: f32) -> u8 { (v.clamp(0.0, 1.0) * 255.0).round() as u8 }
+
+/// Parses ``rgb()``, ``rgba()``, ``hsl()``, ``hsla()`` or ``hwb()``, in both the legacy
+/// comma-separated and the modern space-separated syntax
+fn parse_function(s: &str) -> Result<Color, ColorParseError> {
+    let invalid = || ColorParseError::InvalidArguments(s.to_string());
+    let (name, body) = s.split_once('(').ok_or_else(invalid)?;
+    let body = body.strip_suffix(')').ok_or_else(invalid)?;
+    let name = name.trim().to_ascii_lowercase();
+
+    let (main, alpha) = match body.split_once('/') {
+        Some((main, alpha)) => (main, Some(alpha.trim())),
+        None => (body, None),
+    };
+    let mut args: Vec<&str> = main.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|a| !a.is_empty()).collect();
+    let alpha = match (alpha, args.len()) {
+        (None, 4) => args.pop(),
+        (alpha, _) => alpha,
+    };
+    if args.len() != 3 { return Err(invalid()); }
+    let a = match alpha {
+        Some(a) => parse_alpha(a)?,
+        None => 1.0,
+    };
+
+    let color = match name.as_str() {
+        "rgb" | "rgba" => Color::rgb(parse_channel(args[0])?, parse_channel(args[1])?, parse_channel(args[2])?),
+        "hsl" | "hsla" => Color::from_hsl(parse_hue(args[0])?, parse_percentage(args[1])?, parse_percentage(args[2])?),
+        "hwb" => Color::from_hwb(parse_hue(args[0])?, parse_percentage(args[1])?, parse_percentage(args[2])?),
+        _ => return Err(ColorParseError::UnknownFunction(name)),
+    };
+
+    Ok(color.with_alpha(a))
+}
+
+fn parse_number(s: &str) -> Result<f32, ColorParseError> {
+    s.parse::<f32>().map_err(|_| ColorParseError::InvalidComponent(s.to_string()))
+}
+
+/// A ``0..255`` channel value or a percentage
+fn parse_channel(s: &str) -> Result<u8, ColorParseError> {
+    let v = match s.strip_suffix('%') {
+        Some(p) => parse_number(p)? * 2.55,
+        None => parse_number(s)?,
+    };
+    Ok(v.clamp(0.0, 255.0).round() as u8)
+}
+
+/// A percentage, or a number, which CSS Color 4 allows in place of a percentage; returns a fraction
+fn parse_percentage(s: &str) -> Result<f32, ColorParseError> {
+    Ok(parse_number(s.strip_suffix('%').unwrap_or(s))? / 100.0)
+}
+
+/// An opacity given either as a number or as a percentage
+fn parse_alpha(s: &str) -> Result<f32, ColorParseError> {
+    let a = match s.strip_suffix('%') {
+        Some(p) => parse_number(p)? / 100.0,
+        None => parse_number(s)?,
+    };
+    Ok(a.clamp(0.0, 1.0))
+}
+
+/// An angle in degrees, radians, gradians or turns; returns degrees
+fn parse_hue(s: &str) -> Result<f32, ColorParseError> {
+    let units = [("deg", 1.0), ("grad", 0.9), ("rad", 180.0 / std::f32::consts::PI), ("turn", 360.0)];
+    for (unit, to_degrees) in units {
+        if let Some(v) = s.strip_suffix(unit) {
+            return Ok(parse_number(v)? * to_degrees);
+        }
+    }
+    parse_number(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::colors::{Color, ColorParseError};
+
+    fn parse(s: &str) -> Color { s.parse().unwrap() }
+
+    #[test]
+    fn parse_css_colors() {
+        assert_eq!(parse("rebeccapurple"), Color::rgb(0x66, 0x33, 0x99));
+        assert_eq!(parse(" Red "), Color::rgb(255, 0, 0));
+        assert_eq!(parse("transparent"), Color::TRANSPARENT);
+        assert_eq!(parse("#abc"), Color::rgb(0xAA, 0xBB, 0xCC));
+        assert_eq!(parse("#abcd"), Color::rgba(0xAA, 0xBB, 0xCC, 0xDD as f32 / 255.0));
+        assert_eq!(parse("#12ab34"), Color::rgb(0x12, 0xAB, 0x34));
+        assert_eq!(parse("#12ab3400"), Color::rgba(0x12, 0xAB, 0x34, 0.0));
+        assert_eq!(parse("rgb(10, 20, 30)"), Color::rgb(10, 20, 30));
+        assert_eq!(parse("rgba(10,20,30,0.5)"), Color::rgba(10, 20, 30, 0.5));
+        assert_eq!(parse("rgb(100% 0% 50% / 25%)"), Color::rgba(255, 0, 128, 0.25));
+        assert_eq!(parse("hsl(0, 100%, 50%)"), Color::rgb(255, 0, 0));
+        assert_eq!(parse("hsla(240deg 100% 50% / 1)"), Color::rgb(0, 0, 255));
+        assert_eq!(parse("hsl(0.5turn 100% 25%)"), Color::rgb(0, 128, 128));
+        assert_eq!(parse("hwb(120 0% 0%)"), Color::rgb(0, 255, 0));
+        assert_eq!(parse("hwb(0 60% 60%)"), Color::rgb(128, 128, 128));
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!("".parse::<Color>(), Err(ColorParseError::Empty));
+        assert_eq!("reddish".parse::<Color>(), Err(ColorParseError::UnknownName("reddish".to_string())));
+        assert_eq!("#12345".parse::<Color>(), Err(ColorParseError::InvalidHex("#12345".to_string())));
+        assert_eq!("#ggg".parse::<Color>(), Err(ColorParseError::InvalidHex("#ggg".to_string())));
+        assert_eq!("lab(1 2 3)".parse::<Color>(), Err(ColorParseError::UnknownFunction("lab".to_string())));
+        assert_eq!("rgb(1 2)".parse::<Color>(), Err(ColorParseError::InvalidArguments("rgb(1 2)".to_string())));
+        assert_eq!("rgb(1 2 3".parse::<Color>(), Err(ColorParseError::InvalidArguments("rgb(1 2 3".to_string())));
+        assert_eq!("rgb(1 x 3)".parse::<Color>(), Err(ColorParseError::InvalidComponent("x".to_string())));
+    }
+
+    #[test]
+    fn format_compactly() {
+        assert_eq!(Color::rgb(255, 0, 0).to_string(), "#F00");
+        assert_eq!(Color::rgb(0x12, 0xAB, 0x34).to_string(), "#12AB34");
+        assert_eq!(Color::rgba(255, 255, 255, 0.2).to_string(), "#FFF3");
+        assert_eq!(Color::rgba(0x12, 0xAB, 0x34, 0.5).to_string(), "#12AB3480");
+        for s in ["#F00", "#12AB34", "#FFF3", "#12AB3480"] {
+            assert_eq!(parse(s).to_string(), s);
+        }
+    }
+}
@@ -0,0 +1,315 @@
+//! Continuous colormaps that map a value in [0, 1] to a colour.
+//!
+//! The perceptually uniform maps of matplotlib (viridis, magma, inferno, plasma) and turbo are
+//! polynomial fits to the original tables, accurate to a few 8-bit steps; cividis, RdBu and
+//! coolwarm are interpolated between published stops.
+// --- fitted coefficients are kept with the precision they are published with
+#![allow(clippy::excessive_precision)]
+use std::fmt;
+use crate::colors::{Color, ColorSpace};
+
+/// Error returned when a [`Colormap`] can't be built from the given stops
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColormapError {
+    /// A colormap needs at least two stops
+    TooFewStops,
+    /// Stop positions must be finite and not decreasing
+    UnorderedStops,
+}
+
+impl fmt::Display for ColormapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColormapError::TooFewStops => write!(f, "a colormap needs at least two stops"),
+            ColormapError::UnorderedStops => write!(f, "colormap stop positions must not decrease"),
+        }
+    }
+}
+
+impl std::error::Error for ColormapError {}
+
+/// A continuous colour scale, defined by colour stops and interpolated linearly between them.
+///
+/// Colours between stops are mixed in sRGB unless another colour space is chosen with
+/// [`Colormap::interpolated_in()`]; a colormap therefore also serves as a multi-stop gradient.
+///
+/// # Example
+/// ```
+/// use visualife::colors::{Color, Colormap};
+/// let cmap = Colormap::viridis();
+/// let low = cmap.sample(0.0);
+/// assert_eq!(cmap.reversed().sample(1.0), low);
+/// // --- five colours for a discrete legend
+/// let legend = cmap.colors(5);
+/// assert_eq!(legend.len(), 5);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Colormap {
+    stops: Vec<(f32, Color)>,
+    space: ColorSpace,
+}
+
+/// Number of samples a built-in map defined by a polynomial is tabulated with
+const TABULATED_STOPS: usize = 64;
+
+impl Colormap {
+    /// Creates a colormap from ``(position, colour)`` stops.
+    ///
+    /// Positions must not decrease; they are rescaled so that the first stop is at 0 and the last at 1.
+    /// Two stops at the same position make a sharp transition.
+    pub fn from_stops(stops: &[(f32, Color)]) -> Result<Self, ColormapError> {
+        if stops.len() < 2 { return Err(ColormapError::TooFewStops); }
+        if stops.iter().any(|(t, _)| !t.is_finite()) || stops.windows(2).any(|w| w[1].0 < w[0].0) {
+            return Err(ColormapError::UnorderedStops);
+        }
+        let (first, last) = (stops[0].0, stops[stops.len() - 1].0);
+        if last == first { return Err(ColormapError::UnorderedStops); }
+        let stops = stops.iter().map(|&(t, c)| ((t - first) / (last - first), c)).collect();
+
+        Ok(Colormap { stops, space: ColorSpace::Srgb })
+    }
+
+    /// Creates a colormap from evenly spaced colours
+    pub fn from_colors(colors: &[Color]) -> Result<Self, ColormapError> {
+        let n = colors.len().max(2) - 1;
+        let stops: Vec<(f32, Color)> = colors.iter().enumerate().map(|(i, &c)| (i as f32 / n as f32, c)).collect();
+        Colormap::from_stops(&stops)
+    }
+
+    /// Returns a built-in colormap by its (case-insensitive) name, e.g. ``"viridis"`` or ``"RdBu"``
+    pub fn by_name(name: &str) -> Option<Colormap> {
+        match name.to_ascii_lowercase().as_str() {
+            "viridis" => Some(Colormap::viridis()),
+            "magma" => Some(Colormap::magma()),
+            "inferno" => Some(Colormap::inferno()),
+            "plasma" => Some(Colormap::plasma()),
+            "cividis" => Some(Colormap::cividis()),
+            "turbo" => Some(Colormap::turbo()),
+            "rdbu" => Some(Colormap::rdbu()),
+            "coolwarm" => Some(Colormap::coolwarm()),
+            _ => None,
+        }
+    }
+
+    /// Returns this colormap with colours between stops mixed in the given colour space
+    pub fn interpolated_in(mut self, space: ColorSpace) -> Colormap {
+        self.space = space;
+        self
+    }
+
+    /// Stops of this colormap, with positions in [0, 1]
+    pub fn stops(&self) -> &[(f32, Color)] { &self.stops }
+
+    /// Colour space colours are mixed in between stops
+    pub fn space(&self) -> ColorSpace { self.space }
+
+    /// Returns the colour at position `t`; values outside [0, 1] are clamped
+    pub fn sample(&self, t: f32) -> Color {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        let i = self.stops.partition_point(|(s, _)| *s <= t).clamp(1, self.stops.len() - 1);
+        let ((t0, c0), (t1, c1)) = (self.stops[i - 1], self.stops[i]);
+        if t1 <= t0 { return c1; }
+        Color::mix(c0, c1, (t - t0) / (t1 - t0), self.space)
+    }
+
+    /// Returns the colour for `value` on the scale from `min` to `max`.
+    ///
+    /// A scale that holds a single value, where `min == max`, gives the middle colour of this map.
+    pub fn sample_range(&self, value: f32, min: f32, max: f32) -> Color {
+        if max == min { return self.sample(0.5); }
+        self.sample((value - min) / (max - min))
+    }
+
+    /// Returns `n` evenly spaced colours of this map, from its beginning to its end
+    pub fn colors(&self, n: usize) -> Vec<Color> {
+        match n {
+            0 => vec![],
+            1 => vec![self.sample(0.5)],
+            _ => (0..n).map(|i| self.sample(i as f32 / (n - 1) as f32)).collect(),
+        }
+    }
+
+    /// Returns this colormap running in the opposite direction
+    pub fn reversed(&self) -> Colormap {
+        Colormap { stops: self.stops.iter().rev().map(|&(t, c)| (1.0 - t, c)).collect(), space: self.space }
+    }
+
+    /// Returns the part of this colormap between `start` and `end`, stretched over [0, 1].
+    ///
+    /// Reversed ranges, where `start > end`, give a reversed sub-range.
+    pub fn sub_range(&self, start: f32, end: f32) -> Colormap {
+        if start > end { return self.sub_range(end, start).reversed(); }
+        let (start, end) = (start.clamp(0.0, 1.0), end.clamp(0.0, 1.0));
+        if end - start <= f32::EPSILON {
+            let c = self.sample(start);
+            return Colormap { stops: vec![(0.0, c), (1.0, c)], space: self.space };
+        }
+        let mut stops = vec![(0.0, self.sample(start))];
+        stops.extend(self.stops.iter().filter(|(t, _)| *t > start && *t < end)
+            .map(|&(t, c)| ((t - start) / (end - start), c)));
+        stops.push((1.0, self.sample(end)));
+
+        Colormap { stops, space: self.space }
+    }
+
+    /// Returns a stepped version of this colormap that has `n` uniform colour bands.
+    ///
+    /// The colour of each band is sampled at the band's centre.
+    pub fn discretize(&self, n: usize) -> Colormap {
+        let n = n.max(1);
+        let mut stops = Vec::with_capacity(2 * n);
+        for i in 0..n {
+            let c = self.sample((i as f32 + 0.5) / n as f32);
+            stops.push((i as f32 / n as f32, c));
+            stops.push(((i + 1) as f32 / n as f32, c));
+        }
+
+        Colormap { stops, space: self.space }
+    }
+
+    /// The default colormap of matplotlib: perceptually uniform, from dark blue to yellow
+    pub fn viridis() -> Colormap {
+        Colormap::from_polynomial(&[
+            [0.2777273272234177, 0.005407344544966578, 0.3340998053353061],
+            [0.1050930431085774, 1.404613529898575, 1.384590162594685],
+            [-0.3308618287255563, 0.214847559468213, 0.09509516302823659],
+            [-4.634230498983486, -5.799100973351585, -19.33244095627987],
+            [6.228269936347081, 14.17993336680509, 56.69055260068105],
+            [4.776384997670288, -13.74514537774601, -65.35303263337234],
+            [-5.435455855934631, 4.645852612178535, 26.3124352495832]])
+    }
+
+    /// Perceptually uniform map from black through purple and orange to light yellow
+    pub fn magma() -> Colormap {
+        Colormap::from_polynomial(&[
+            [-0.002136485053939582, -0.000749655052795221, -0.005386127855323933],
+            [0.2516605407371642, 0.6775232436837668, 2.494026599312351],
+            [8.353717279216625, -3.577719514958484, 0.3144679030132573],
+            [-27.66873308576866, 14.26473078096533, -13.64921318813922],
+            [52.17613981234068, -27.94360607168351, 12.94416944238394],
+            [-50.76852536473588, 29.04658282127291, 4.23415299384598],
+            [18.65570506591883, -11.48977351997711, -5.601961508734096]])
+    }
+
+    /// Perceptually uniform map from black through red and orange to bright yellow
+    pub fn inferno() -> Colormap {
+        Colormap::from_polynomial(&[
+            [0.0002189403691192265, 0.001651004631001012, -0.01948089843709184],
+            [0.1065134194856116, 0.5639564367884091, 3.932712388889277],
+            [11.60249308247187, -3.972853965665698, -15.9423941062914],
+            [-41.70399613139459, 17.43639888205313, 44.35414519872813],
+            [77.162935699427, -33.40235894210092, -81.80730925738993],
+            [-71.31942824499214, 32.62606426397723, 73.20951985803202],
+            [25.13112622477341, -12.24266895238567, -23.07032500287172]])
+    }
+
+    /// Perceptually uniform map from blue through magenta to yellow
+    pub fn plasma() -> Colormap {
+        Colormap::from_polynomial(&[
+            [0.05873234392399702, 0.02333670892565664, 0.5433401826748754],
+            [2.176514634195958, 0.2383834171260182, 0.7539604599784036],
+            [-2.689460476458034, -7.455851135738909, 3.110799939717086],
+            [6.130348345893603, 42.3461881477227, -28.51885465332158],
+            [-11.10743619062271, -82.66631109428045, 60.13984767418263],
+            [10.02306557647065, 71.41361770095349, -54.07218655560067],
+            [-3.658713842777788, -22.93153465461149, 18.19190778539828]])
+    }
+
+    /// Blue to yellow map, optimised for viewers with colour vision deficiency
+    pub fn cividis() -> Colormap {
+        Colormap::from_hex(&[0x00204C, 0x002A66, 0x00346E, 0x273F6C, 0x3C4A6B, 0x4C556B, 0x5B5F6D,
+            0x686A70, 0x757575, 0x838178, 0x928C78, 0xA19876, 0xB0A572, 0xC0B16D, 0xD1BF66, 0xE1CC5C,
+            0xF3DB4F, 0xFFE945])
+    }
+
+    /// Rainbow-like map with smooth lightness, for data where detail matters more than ordering
+    pub fn turbo() -> Colormap {
+        let coefficients = [
+            [0.13572138, 0.09140261, 0.10667330],
+            [4.61539260, 2.19418839, 12.64194608],
+            [-42.66032258, 4.84296658, -60.58204836],
+            [132.13108234, -14.18503333, 110.36276771],
+            [-152.94239396, 4.27729857, -89.90310912],
+            [59.28637943, 2.82956604, 27.34824973]];
+        Colormap::from_polynomial(&coefficients)
+    }
+
+    /// Diverging ColorBrewer map from dark red through white to dark blue
+    pub fn rdbu() -> Colormap {
+        Colormap::from_hex(&[0x67001F, 0xB2182B, 0xD6604D, 0xF4A582, 0xFDDBC7, 0xF7F7F7,
+            0xD1E5F0, 0x92C5DE, 0x4393C3, 0x2166AC, 0x053061])
+    }
+
+    /// Moreland's diverging map from blue through light grey to red
+    pub fn coolwarm() -> Colormap {
+        Colormap::from_hex(&[0x3B4CC0, 0x5977E3, 0x7B9FF9, 0x9EBEFF, 0xC0D4F5, 0xDDDDDD,
+            0xF2CBB7, 0xF7AC8E, 0xEE8468, 0xD65244, 0xB40426])
+    }
+
+    fn from_hex(colors: &[u32]) -> Colormap {
+        let colors: Vec<Color> = colors.iter().map(|&c| Color::from_u32(c)).collect();
+        Colormap::from_colors(&colors).unwrap()
+    }
+
+    /// Tabulates a map whose red, green and blue channels are polynomials of `t`;
+    /// row `k` holds the coefficients of `t^k`
+    fn from_polynomial<const N: usize>(coefficients: &[[f64; 3]; N]) -> Colormap {
+        let channel = |t: f64, ch: usize| {
+            let v = coefficients.iter().rev().fold(0.0, |acc, row| acc * t + row[ch]);
+            (v.clamp(0.0, 1.0) * 255.0).round() as u8
+        };
+        let stops = (0..TABULATED_STOPS).map(|i| {
+            let t = i as f64 / (TABULATED_STOPS - 1) as f64;
+            (t as f32, Color::rgb(channel(t, 0), channel(t, 1), channel(t, 2)))
+        }).collect();
+
+        Colormap { stops, space: ColorSpace::Srgb }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::colors::{Color, ColorSpace, Colormap, ColormapError};
+
+    fn close(a: Color, b: Color, tolerance: i32) -> bool {
+        [(a.r, b.r), (a.g, b.g), (a.b, b.b)].iter().all(|&(x, y)| (x as i32 - y as i32).abs() <= tolerance)
+    }
+
+    #[test]
+    fn builtin_maps() {
+        let viridis = Colormap::viridis();
+        for (t, hex) in [(0.0, 0x440154), (0.25, 0x3B528B), (0.5, 0x21918C), (0.75, 0x5EC962), (1.0, 0xFDE725)] {
+            assert!(close(viridis.sample(t), Color::from_u32(hex), 5), "viridis({}) = {}", t, viridis.sample(t));
+        }
+        assert!(close(Colormap::magma().sample(0.5), Color::from_u32(0xB73779), 5));
+        assert!(close(Colormap::plasma().sample(1.0), Color::from_u32(0xF0F921), 5));
+        assert_eq!(Colormap::rdbu().sample(0.5), Color::from_u32(0xF7F7F7));
+        assert_eq!(Colormap::by_name("CoolWarm"), Some(Colormap::coolwarm()));
+        assert!(Colormap::by_name("jet").is_none());
+    }
+
+    #[test]
+    fn custom_maps() {
+        let (black, white) = (Color::BLACK, Color::WHITE);
+        assert_eq!(Colormap::from_stops(&[(0.0, black)]), Err(ColormapError::TooFewStops));
+        assert_eq!(Colormap::from_stops(&[(1.0, black), (0.0, white)]), Err(ColormapError::UnorderedStops));
+
+        let gray = Colormap::from_stops(&[(10.0, black), (20.0, white)]).unwrap();
+        assert_eq!(gray.sample(0.5), Color::rgb(128, 128, 128));
+        assert_eq!(gray.sample(-1.0), black);
+        assert_eq!(gray.sample_range(15.0, 10.0, 20.0), Color::rgb(128, 128, 128));
+        assert_eq!(gray.sample_range(3.0, 3.0, 3.0), Color::rgb(128, 128, 128));
+        assert_eq!(gray.reversed().sample(0.25), gray.sample(0.75));
+        assert_eq!(gray.sub_range(0.5, 1.0).sample(0.0), gray.sample(0.5));
+        assert_eq!(gray.sub_range(1.0, 0.5).sample(0.0), white);
+
+        let steps = gray.discretize(4);
+        assert_eq!(steps.sample(0.1), steps.sample(0.2));
+        assert_ne!(steps.sample(0.2), steps.sample(0.3));
+        assert_eq!(gray.colors(3), vec![black, Color::rgb(128, 128, 128), white]);
+
+        let linear = gray.clone().interpolated_in(ColorSpace::LinearRgb);
+        assert_eq!(linear.sample(0.5), Color::rgb(188, 188, 188));
+        assert_eq!(linear.reversed().space(), ColorSpace::LinearRgb);
+    }
+}
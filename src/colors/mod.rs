@@ -0,0 +1,60 @@
+//! Colours and colour manipulation.
+//!
+//! The [`Color`] type represents an sRGB colour with opacity; it's used by [`Style`](crate::style::Style)
+//! for fill and stroke colours. Functions of this module that operate on hexadecimal strings
+//! are kept for convenience.
+mod accessibility;
+mod color;
+mod colormap;
+mod mix;
+mod named;
+mod palette;
+mod spaces;
+
+pub use accessibility::*;
+pub use color::*;
+pub use colormap::*;
+pub use mix::*;
+pub use palette::*;
+pub use spaces::*;
+
+/// Converts RGB components to a hexadecimal color string.
+pub fn rgb_to_hex(r: u8, g: u8, b: u8) -> String {
+    format!("#{:02X}{:02X}{:02X}", r, g, b)
+}
+
+/// Converts a hexadecimal color string to RGB components.
+///
+/// Any of the ``#rgb``, ``#rgba``, ``#rrggbb`` or ``#rrggbbaa`` notations is accepted;
+/// the alpha channel is dropped.
+pub fn hex_to_rgb(hex: &str) -> Result<(u8, u8, u8), &'static str> {
+    let c = Color::from_hex(hex).map_err(|_| "Invalid hex color format")?;
+
+    Ok((c.r, c.g, c.b))
+}
+
+/// Darkens a hexadecimal color by a given fraction.
+///
+/// The colour is darkened in a perceptual colour space, see [`Color::darken()`].
+pub fn darker(color_hex: &str, fraction: f32) -> Result<String, &'static str> {
+    if !(0.0..=1.0).contains(&fraction) {
+        return Err("Fraction must be between 0.0 and 1.0");
+    }
+
+    let c = Color::from_hex(color_hex).map_err(|_| "Invalid hex color format")?;
+
+    Ok(c.darken(fraction).to_hex())
+}
+
+/// Lightens a hexadecimal color by a given fraction.
+///
+/// The colour is lightened in a perceptual colour space, see [`Color::lighten()`].
+pub fn lighter(color_hex: &str, fraction: f32) -> Result<String, &'static str> {
+    if !(0.0..=1.0).contains(&fraction) {
+        return Err("Fraction must be between 0.0 and 1.0");
+    }
+
+    let c = Color::from_hex(color_hex).map_err(|_| "Invalid hex color format")?;
+
+    Ok(c.lighten(fraction).to_hex())
+}
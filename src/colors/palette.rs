@@ -0,0 +1,233 @@
+//! Qualitative palettes: sets of colours that tell categories apart.
+use std::collections::HashSet;
+use std::fmt;
+use crate::colors::{Color, LinearRgb, Oklab};
+
+/// Error returned when a [`Palette`] can't be created
+#[derive(Debug, Clone, PartialEq)]
+pub enum PaletteError {
+    /// A palette needs at least one colour
+    Empty,
+    /// [`Palette::distinct()`] was asked for more colours than there are sRGB colours it picks from
+    TooManyColors { requested: usize, available: usize },
+}
+
+impl fmt::Display for PaletteError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PaletteError::Empty => write!(f, "a palette needs at least one colour"),
+            PaletteError::TooManyColors { requested, available } =>
+                write!(f, "{} distinct colours requested, but only {} are available", requested, available),
+        }
+    }
+}
+
+impl std::error::Error for PaletteError {}
+
+/// An ordered set of categorical colours.
+///
+/// Colours are handed out cyclically, so a palette never runs out of colours;
+/// use [`Palette::distinct()`] when more distinguishable colours are needed than a fixed palette has.
+/// Only ``Palette::distinct(0)`` gives a palette without colours.
+///
+/// # Example
+/// ```
+/// use visualife::colors::{Color, Palette};
+/// let palette = Palette::okabe_ito();
+/// assert_eq!(palette.color(0), Color::from_u32(0xE69F00));
+/// assert_eq!(palette.color(8), palette.color(0));
+/// assert_eq!(Palette::distinct(20).unwrap().len(), 20);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Palette {
+    colors: Vec<Color>,
+}
+
+impl Palette {
+    /// Creates a palette of the given colours; there must be at least one of them
+    pub fn new(colors: &[Color]) -> Result<Self, PaletteError> {
+        if colors.is_empty() { return Err(PaletteError::Empty); }
+        Ok(Palette { colors: colors.to_vec() })
+    }
+
+    /// The ten colours of Tableau
+    pub fn tableau10() -> Self {
+        Palette::from_hex(&[0x4E79A7, 0xF28E2B, 0xE15759, 0x76B7B2, 0x59A14F, 0xEDC948, 0xB07AA1,
+            0xFF9DA7, 0x9C755F, 0xBAB0AC])
+    }
+
+    /// ColorBrewer's Set1: nine strong colours
+    pub fn set1() -> Self {
+        Palette::from_hex(&[0xE41A1C, 0x377EB8, 0x4DAF4A, 0x984EA3, 0xFF7F00, 0xFFFF33, 0xA65628,
+            0xF781BF, 0x999999])
+    }
+
+    /// ColorBrewer's Set2: eight pastel colours
+    pub fn set2() -> Self {
+        Palette::from_hex(&[0x66C2A5, 0xFC8D62, 0x8DA0CB, 0xE78AC3, 0xA6D854, 0xFFD92F, 0xE5C494, 0xB3B3B3])
+    }
+
+    /// ColorBrewer's Paired: six hues, each in a light and a dark variant
+    pub fn paired() -> Self {
+        Palette::from_hex(&[0xA6CEE3, 0x1F78B4, 0xB2DF8A, 0x33A02C, 0xFB9A99, 0xE31A1C, 0xFDBF6F,
+            0xFF7F00, 0xCAB2D6, 0x6A3D9A, 0xFFFF99, 0xB15928])
+    }
+
+    /// The eight colours of Okabe and Ito, distinguishable with any kind of colour vision deficiency
+    pub fn okabe_ito() -> Self {
+        Palette::from_hex(&[0xE69F00, 0x56B4E9, 0x009E73, 0xF0E442, 0x0072B2, 0xD55E00, 0xCC79A7, 0x000000])
+    }
+
+    /// Returns a built-in palette by its (case-insensitive) name, e.g. ``"tableau10"`` or ``"Set2"``
+    pub fn by_name(name: &str) -> Option<Palette> {
+        match name.to_ascii_lowercase().replace(['-', '_'], "").as_str() {
+            "tableau10" => Some(Palette::tableau10()),
+            "set1" => Some(Palette::set1()),
+            "set2" => Some(Palette::set2()),
+            "paired" => Some(Palette::paired()),
+            "okabeito" => Some(Palette::okabe_ito()),
+            _ => None,
+        }
+    }
+
+    /// Generates `n` colours that are as far apart from each other as possible.
+    ///
+    /// Colours are picked greedily from a grid of sRGB colours of medium lightness, each time
+    /// taking the one farthest (in OKLab) from all the colours picked so far. When every colour of
+    /// the grid has been picked, the grid is refined by halving its spacing and picking goes on
+    /// from the finer grid. The result is deterministic and its first colours don't change when `n` grows.
+    ///
+    /// The first grid holds 1251 colours and every refinement about eight times as many; only
+    /// when a refinement brings no new colours, i.e. all the sRGB colours of medium lightness
+    /// have been picked, [`PaletteError::TooManyColors`] is returned.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::colors::Palette;
+    /// let many = Palette::distinct(2000).unwrap();
+    /// assert_eq!(many.len(), 2000);
+    /// assert_eq!(&many.colors()[..10], Palette::distinct(10).unwrap().colors());
+    /// assert!(Palette::distinct(0).unwrap().is_empty());
+    /// ```
+    pub fn distinct(n: usize) -> Result<Self, PaletteError> {
+        let mut colors: Vec<Color> = Vec::with_capacity(n);
+        if n == 0 { return Ok(Palette { colors }); }
+        let mut level = 0;
+        let mut candidates = distinct_candidates(level);
+        let mut min_dist = vec![f32::INFINITY; candidates.len()];
+        // --- start from the most colourful candidate
+        let mut next = (0..candidates.len())
+            .max_by(|&i, &j| chroma(&candidates[i].1).total_cmp(&chroma(&candidates[j].1))).unwrap();
+        while colors.len() < n {
+            let (color, lab) = candidates[next];
+            colors.push(color);
+            for (d, (_, other)) in min_dist.iter_mut().zip(&candidates) {
+                *d = d.min(distance(&lab, other));
+            }
+            next = (0..candidates.len()).max_by(|&i, &j| min_dist[i].total_cmp(&min_dist[j])).unwrap();
+            // ---------- every candidate has been picked: go on with a finer grid, which holds the picked colours too
+            if min_dist[next] == 0.0 && colors.len() < n {
+                level += 1;
+                let finer = distinct_candidates(level);
+                if finer.len() == candidates.len() {
+                    return Err(PaletteError::TooManyColors { requested: n, available: finer.len() });
+                }
+                candidates = finer;
+                let picked: Vec<Oklab> = colors.iter().map(|c| c.to_oklab()).collect();
+                min_dist = candidates.iter()
+                    .map(|(_, lab)| picked.iter().map(|p| distance(p, lab)).fold(f32::INFINITY, f32::min)).collect();
+                next = (0..candidates.len()).max_by(|&i, &j| min_dist[i].total_cmp(&min_dist[j])).unwrap();
+            }
+        }
+
+        Ok(Palette { colors })
+    }
+
+    /// Returns the `i`-th colour; the palette is repeated when `i` exceeds its size.
+    ///
+    /// # Panics
+    /// Panics when the palette has no colours.
+    pub fn color(&self, i: usize) -> Color { self.colors[i % self.colors.len()] }
+
+    pub fn colors(&self) -> &[Color] { &self.colors }
+
+    pub fn len(&self) -> usize { self.colors.len() }
+
+    pub fn is_empty(&self) -> bool { self.colors.is_empty() }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Color> { self.colors.iter() }
+
+    /// Replaces every colour of this palette with `f(colour)`
+    pub fn map_colors<F: Fn(Color) -> Color>(&mut self, f: F) {
+        for c in self.colors.iter_mut() { *c = f(*c); }
+    }
+
+    fn from_hex(colors: &[u32]) -> Self { Palette { colors: colors.iter().map(|&c| Color::from_u32(c)).collect() } }
+}
+
+impl From<Palette> for Vec<Color> {
+    fn from(p: Palette) -> Self { p.colors }
+}
+
+/// Colours [`Palette::distinct()`] picks from: an OKLab grid restricted to the sRGB gamut,
+/// without very dark or very light colours that would be hard to tell apart.
+///
+/// The spacing of the grid is halved at every `level`, so a grid holds the points of the coarser ones.
+fn distinct_candidates(level: u32) -> Vec<(Color, Oklab)> {
+    let k = 1 << level;
+    let (dl, dab) = (0.06 / k as f32, 0.025 / k as f32);
+    let mut seen = HashSet::new();
+    let mut out = vec![];
+    for li in 0..=8 * k {
+        for ai in -12 * k..=12 * k {
+            for bi in -12 * k..=12 * k {
+                let lab = Oklab { l: 0.4 + dl * li as f32, a: dab * ai as f32, b: dab * bi as f32 };
+                // ---------- grid points that round to the same sRGB colour are taken once
+                if LinearRgb::from(lab).in_gamut() {
+                    let color = Color::from(lab);
+                    if seen.insert((color.r, color.g, color.b)) { out.push((color, color.to_oklab())); }
+                }
+            }
+        }
+    }
+    out
+}
+
+fn chroma(c: &Oklab) -> f32 { c.a.hypot(c.b) }
+
+fn distance(x: &Oklab, y: &Oklab) -> f32 {
+    ((x.l - y.l).powi(2) + (x.a - y.a).powi(2) + (x.b - y.b).powi(2)).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::colors::{Palette, PaletteError};
+    use crate::colors::palette::distinct_candidates;
+
+    #[test]
+    fn distinct_colors() {
+        let small = Palette::distinct(5).unwrap();
+        let large = Palette::distinct(40).unwrap();
+        assert_eq!(&large.colors()[..5], small.colors());
+        for (i, a) in large.iter().enumerate() {
+            for b in large.iter().skip(i + 1) {
+                let (x, y) = (a.to_oklab(), b.to_oklab());
+                let d = ((x.l - y.l).powi(2) + (x.a - y.a).powi(2) + (x.b - y.b).powi(2)).sqrt();
+                assert!(d > 0.05, "{} and {} are too similar", a, b);
+            }
+        }
+        assert_eq!(Palette::by_name("Okabe-Ito"), Some(Palette::okabe_ito()));
+        assert_eq!(Palette::paired().len(), 12);
+
+        assert_eq!(Palette::new(&[]), Err(PaletteError::Empty));
+        assert_eq!(Palette::distinct(0), Ok(Palette { colors: vec![] }));
+        // ---------- more colours than the first grid holds come from a finer one
+        let available = distinct_candidates(0).len();
+        let all = Palette::distinct(available + 500).unwrap();
+        assert_eq!(&all.colors()[..40], large.colors());
+        let mut rgb: Vec<(u8, u8, u8)> = all.iter().map(|c| (c.r, c.g, c.b)).collect();
+        rgb.sort();
+        rgb.dedup();
+        assert_eq!(rgb.len(), available + 500);
+    }
+}
@@ -0,0 +1,366 @@
+//! Colour spaces a [`Color`] can be converted to and from.
+//!
+//! Conversions from a [`Color`] are exact up to floating point precision; conversions back
+//! round each channel to 8 bits and produce an opaque colour. CIE Lab/LCh use the D65 white point.
+// --- conversion matrices are kept with the precision they are published with
+#![allow(clippy::excessive_precision)]
+use crate::colors::Color;
+use crate::colors::color::to_u8;
+
+/// sRGB with the gamma transfer function removed; channels of in-gamut colours are in [0, 1]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LinearRgb { pub r: f32, pub g: f32, pub b: f32 }
+
+/// Hue in degrees, saturation and lightness in [0, 1]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsl { pub h: f32, pub s: f32, pub l: f32 }
+
+/// Hue in degrees, saturation and value in [0, 1]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsv { pub h: f32, pub s: f32, pub v: f32 }
+
+/// CIE L*a*b*; lightness ranges from 0 to 100
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lab { pub l: f32, pub a: f32, pub b: f32 }
+
+/// Cylindrical form of CIE L*a*b*: lightness, chroma and hue in degrees
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Lch { pub l: f32, pub c: f32, pub h: f32 }
+
+/// OKLab perceptual colour space; lightness ranges from 0 to 1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oklab { pub l: f32, pub a: f32, pub b: f32 }
+
+/// Cylindrical form of OKLab: lightness, chroma and hue in degrees
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Oklch { pub l: f32, pub c: f32, pub h: f32 }
+
+// ---------- D65 reference white
+const WHITE_X: f32 = 0.95047;
+const WHITE_Z: f32 = 1.08883;
+
+/// Chroma below which a colour is treated as a shade of grey, whose hue is undefined
+const ACHROMATIC_CHROMA: f32 = 1e-4;
+
+fn srgb_to_linear(c: f32) -> f32 {
+    if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+}
+
+fn linear_to_srgb(c: f32) -> f32 {
+    if c <= 0.0031308 { 12.92 * c } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 }
+}
+
+/// Converts rectangular ``(a, b)`` coordinates to chroma and hue in degrees
+fn to_polar(a: f32, b: f32) -> (f32, f32) {
+    let c = a.hypot(b);
+    let h = if c < ACHROMATIC_CHROMA { 0.0 } else { b.atan2(a).to_degrees().rem_euclid(360.0) };
+    (c, h)
+}
+
+fn from_polar(c: f32, h: f32) -> (f32, f32) {
+    let h = h.to_radians();
+    (c * h.cos(), c * h.sin())
+}
+
+impl LinearRgb {
+    /// Returns ``true`` if this colour can be shown on an sRGB display without clipping
+    pub fn in_gamut(&self) -> bool {
+        const EPS: f32 = 1e-4;
+        [self.r, self.g, self.b].iter().all(|c| (-EPS..=1.0 + EPS).contains(c))
+    }
+}
+
+impl From<Color> for LinearRgb {
+    fn from(c: Color) -> Self {
+        let f = |v: u8| srgb_to_linear(v as f32 / 255.0);
+        LinearRgb { r: f(c.r), g: f(c.g), b: f(c.b) }
+    }
+}
+
+impl From<LinearRgb> for Color {
+    fn from(c: LinearRgb) -> Self {
+        let f = |v: f32| to_u8(linear_to_srgb(v.clamp(0.0, 1.0)));
+        Color::rgb(f(c.r), f(c.g), f(c.b))
+    }
+}
+
+impl From<Color> for Hsl {
+    fn from(c: Color) -> Self {
+        let (r, g, b) = (c.r as f32 / 255.0, c.g as f32 / 255.0, c.b as f32 / 255.0);
+        let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+        let l = (max + min) / 2.0;
+        let d = max - min;
+        let s = if d == 0.0 { 0.0 } else { d / (1.0 - (2.0 * l - 1.0).abs()) };
+        Hsl { h: rgb_hue(r, g, b, max, d), s, l }
+    }
+}
+
+impl From<Hsl> for Color {
+    fn from(c: Hsl) -> Self {
+        let (s, l) = (c.s.clamp(0.0, 1.0), c.l.clamp(0.0, 1.0));
+        let f = |n: f32| {
+            let k = (n + c.h.rem_euclid(360.0) / 30.0) % 12.0;
+            let a = s * l.min(1.0 - l);
+            to_u8(l - a * (k - 3.0).min(9.0 - k).clamp(-1.0, 1.0))
+        };
+        Color::rgb(f(0.0), f(8.0), f(4.0))
+    }
+}
+
+impl From<Color> for Hsv {
+    fn from(c: Color) -> Self {
+        let (r, g, b) = (c.r as f32 / 255.0, c.g as f32 / 255.0, c.b as f32 / 255.0);
+        let (max, min) = (r.max(g).max(b), r.min(g).min(b));
+        let d = max - min;
+        let s = if max == 0.0 { 0.0 } else { d / max };
+        Hsv { h: rgb_hue(r, g, b, max, d), s, v: max }
+    }
+}
+
+impl From<Hsv> for Color {
+    fn from(c: Hsv) -> Self {
+        let (s, v) = (c.s.clamp(0.0, 1.0), c.v.clamp(0.0, 1.0));
+        let f = |n: f32| {
+            let k = (n + c.h.rem_euclid(360.0) / 60.0) % 6.0;
+            to_u8(v - v * s * k.min(4.0 - k).clamp(0.0, 1.0))
+        };
+        Color::rgb(f(5.0), f(3.0), f(1.0))
+    }
+}
+
+/// Hue in degrees of an RGB colour, given its largest channel and the range of its channels
+fn rgb_hue(r: f32, g: f32, b: f32, max: f32, d: f32) -> f32 {
+    if d == 0.0 { return 0.0; }
+    let h = if max == r { (g - b) / d } else if max == g { (b - r) / d + 2.0 } else { (r - g) / d + 4.0 };
+    (h * 60.0).rem_euclid(360.0)
+}
+
+impl From<Color> for Lab {
+    fn from(c: Color) -> Self {
+        let LinearRgb { r, g, b } = c.into();
+        let x = 0.4124564 * r + 0.3575761 * g + 0.1804375 * b;
+        let y = 0.2126729 * r + 0.7151522 * g + 0.0721750 * b;
+        let z = 0.0193339 * r + 0.1191920 * g + 0.9503041 * b;
+        let f = |t: f32| {
+            const DELTA: f32 = 6.0 / 29.0;
+            if t > DELTA * DELTA * DELTA { t.cbrt() } else { t / (3.0 * DELTA * DELTA) + 4.0 / 29.0 }
+        };
+        let (fx, fy, fz) = (f(x / WHITE_X), f(y), f(z / WHITE_Z));
+        Lab { l: 116.0 * fy - 16.0, a: 500.0 * (fx - fy), b: 200.0 * (fy - fz) }
+    }
+}
+
+impl From<Lab> for LinearRgb {
+    fn from(c: Lab) -> Self {
+        let fy = (c.l + 16.0) / 116.0;
+        let (fx, fz) = (fy + c.a / 500.0, fy - c.b / 200.0);
+        let f_inv = |t: f32| {
+            const DELTA: f32 = 6.0 / 29.0;
+            if t > DELTA { t * t * t } else { 3.0 * DELTA * DELTA * (t - 4.0 / 29.0) }
+        };
+        let (x, y, z) = (WHITE_X * f_inv(fx), f_inv(fy), WHITE_Z * f_inv(fz));
+        LinearRgb {
+            r: 3.2404542 * x - 1.5371385 * y - 0.4985314 * z,
+            g: -0.9692660 * x + 1.8760108 * y + 0.0415560 * z,
+            b: 0.0556434 * x - 0.2040259 * y + 1.0572252 * z,
+        }
+    }
+}
+
+impl From<Lab> for Color {
+    fn from(c: Lab) -> Self { LinearRgb::from(c).into() }
+}
+
+impl From<Lab> for Lch {
+    fn from(c: Lab) -> Self {
+        let (chroma, h) = to_polar(c.a, c.b);
+        Lch { l: c.l, c: chroma, h }
+    }
+}
+
+impl From<Lch> for Lab {
+    fn from(c: Lch) -> Self {
+        let (a, b) = from_polar(c.c, c.h);
+        Lab { l: c.l, a, b }
+    }
+}
+
+impl From<Color> for Lch {
+    fn from(c: Color) -> Self { Lab::from(c).into() }
+}
+
+impl From<Lch> for Color {
+    fn from(c: Lch) -> Self { Lab::from(c).into() }
+}
+
+impl From<Color> for Oklab {
+    fn from(c: Color) -> Self { LinearRgb::from(c).into() }
+}
+
+impl From<LinearRgb> for Oklab {
+    fn from(c: LinearRgb) -> Self {
+        let l = (0.4122214708 * c.r + 0.5363325363 * c.g + 0.0514459929 * c.b).cbrt();
+        let m = (0.2119034982 * c.r + 0.6806995451 * c.g + 0.1073969566 * c.b).cbrt();
+        let s = (0.0883024619 * c.r + 0.2817188376 * c.g + 0.6299787005 * c.b).cbrt();
+        Oklab {
+            l: 0.2104542553 * l + 0.7936177850 * m - 0.0040720468 * s,
+            a: 1.9779984951 * l - 2.4285922050 * m + 0.4505937099 * s,
+            b: 0.0259040371 * l + 0.7827717662 * m - 0.8086757660 * s,
+        }
+    }
+}
+
+impl From<Oklab> for LinearRgb {
+    fn from(c: Oklab) -> Self {
+        let l = (c.l + 0.3963377774 * c.a + 0.2158037573 * c.b).powi(3);
+        let m = (c.l - 0.1055613458 * c.a - 0.0638541728 * c.b).powi(3);
+        let s = (c.l - 0.0894841775 * c.a - 1.2914855480 * c.b).powi(3);
+        LinearRgb {
+            r: 4.0767416621 * l - 3.3077115913 * m + 0.2309699292 * s,
+            g: -1.2684380046 * l + 2.6097574011 * m - 0.3413193965 * s,
+            b: -0.0041960863 * l - 0.7034186147 * m + 1.7076147010 * s,
+        }
+    }
+}
+
+impl From<Oklab> for Color {
+    fn from(c: Oklab) -> Self { LinearRgb::from(c).into() }
+}
+
+impl From<Oklab> for Oklch {
+    fn from(c: Oklab) -> Self {
+        let (chroma, h) = to_polar(c.a, c.b);
+        Oklch { l: c.l, c: chroma, h }
+    }
+}
+
+impl From<Oklch> for Oklab {
+    fn from(c: Oklch) -> Self {
+        let (a, b) = from_polar(c.c, c.h);
+        Oklab { l: c.l, a, b }
+    }
+}
+
+impl From<Color> for Oklch {
+    fn from(c: Color) -> Self { Oklab::from(c).into() }
+}
+
+impl From<Oklch> for Color {
+    /// Converts to sRGB, reducing chroma of out-of-gamut colours so that their lightness and hue are kept
+    fn from(c: Oklch) -> Self {
+        let c = Oklch { l: c.l.clamp(0.0, 1.0), ..c };
+        if LinearRgb::from(Oklab::from(c)).in_gamut() {
+            return Oklab::from(c).into();
+        }
+        let (mut lo, mut hi) = (0.0, c.c);
+        for _ in 0..24 {
+            let mid = 0.5 * (lo + hi);
+            if LinearRgb::from(Oklab::from(Oklch { c: mid, ..c })).in_gamut() { lo = mid; } else { hi = mid; }
+        }
+        Oklab::from(Oklch { c: lo, ..c }).into()
+    }
+}
+
+impl Color {
+    pub fn to_linear_rgb(&self) -> LinearRgb { (*self).into() }
+    pub fn to_hsl(&self) -> Hsl { (*self).into() }
+    pub fn to_hsv(&self) -> Hsv { (*self).into() }
+    pub fn to_lab(&self) -> Lab { (*self).into() }
+    pub fn to_lch(&self) -> Lch { (*self).into() }
+    pub fn to_oklab(&self) -> Oklab { (*self).into() }
+    pub fn to_oklch(&self) -> Oklch { (*self).into() }
+
+    /// Returns a lighter colour.
+    ///
+    /// The OKLCH lightness is moved towards white by the given fraction of the remaining range,
+    /// keeping the hue. ``lighten(1.0)`` gives white; black gets lighter too.
+    pub fn lighten(&self, fraction: f32) -> Color {
+        self.map_oklch(|c| Oklch { l: c.l + fraction.clamp(0.0, 1.0) * (1.0 - c.l), ..c })
+    }
+
+    /// Returns a darker colour.
+    ///
+    /// The OKLCH lightness is scaled down by the given fraction, keeping the hue.
+    /// ``darken(1.0)`` gives black.
+    pub fn darken(&self, fraction: f32) -> Color {
+        self.map_oklch(|c| Oklch { l: c.l * (1.0 - fraction.clamp(0.0, 1.0)), ..c })
+    }
+
+    /// Returns a more colourful colour: the OKLCH chroma is increased by the given fraction
+    pub fn saturate(&self, fraction: f32) -> Color {
+        self.map_oklch(|c| Oklch { c: c.c * (1.0 + fraction.max(0.0)), ..c })
+    }
+
+    /// Returns a less colourful colour: the OKLCH chroma is reduced by the given fraction;
+    /// ``desaturate(1.0)`` gives a grey of the same perceived lightness
+    pub fn desaturate(&self, fraction: f32) -> Color {
+        self.map_oklch(|c| Oklch { c: c.c * (1.0 - fraction.clamp(0.0, 1.0)), ..c })
+    }
+
+    /// Rotates the OKLCH hue of this colour by the given angle in degrees
+    pub fn rotate_hue(&self, degrees: f32) -> Color {
+        self.map_oklch(|c| Oklch { h: (c.h + degrees).rem_euclid(360.0), ..c })
+    }
+
+    fn map_oklch<F: Fn(Oklch) -> Oklch>(&self, f: F) -> Color {
+        Color::from(f(self.to_oklch())).with_alpha(self.a)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::colors::{Color, Hsl, Hsv, Lab, Lch, LinearRgb, Oklab, Oklch};
+
+    fn close(a: f32, b: f32, eps: f32) -> bool { (a - b).abs() < eps }
+
+    #[test]
+    fn round_trips() {
+        let colors = [Color::rgb(0, 0, 0), Color::rgb(255, 255, 255), Color::rgb(18, 171, 52),
+            Color::rgb(200, 30, 90), Color::rgb(7, 8, 250), Color::rgb(128, 128, 128)];
+        for c in colors {
+            assert_eq!(Color::from(c.to_linear_rgb()), c);
+            assert_eq!(Color::from(c.to_hsl()), c);
+            assert_eq!(Color::from(c.to_hsv()), c);
+            assert_eq!(Color::from(c.to_lab()), c);
+            assert_eq!(Color::from(c.to_lch()), c);
+            assert_eq!(Color::from(c.to_oklab()), c);
+            assert_eq!(Color::from(c.to_oklch()), c);
+        }
+    }
+
+    #[test]
+    fn reference_values() {
+        let red = Color::rgb(255, 0, 0);
+        let Lab { l, a, b } = red.to_lab();
+        assert!(close(l, 53.24, 0.01) && close(a, 80.09, 0.01) && close(b, 67.20, 0.01));
+        let Lch { c, h, .. } = red.to_lch();
+        assert!(close(c, 104.55, 0.01) && close(h, 40.0, 0.05));
+        let Oklab { l, a, b } = red.to_oklab();
+        assert!(close(l, 0.62796, 1e-4) && close(a, 0.22486, 1e-4) && close(b, 0.12585, 1e-4));
+        let Oklch { h, .. } = red.to_oklch();
+        assert!(close(h, 29.23, 0.01));
+        assert_eq!(red.to_hsl(), Hsl { h: 0.0, s: 1.0, l: 0.5 });
+        assert_eq!(Color::rgb(0, 0, 255).to_hsv(), Hsv { h: 240.0, s: 1.0, v: 1.0 });
+        assert!(close(Color::rgb(128, 128, 128).to_linear_rgb().r, 0.21586, 1e-4));
+        assert!(!LinearRgb::from(Oklab::from(Oklch { l: 0.9, c: 0.4, h: 140.0 })).in_gamut());
+    }
+
+    #[test]
+    fn lighten_and_darken() {
+        assert_ne!(Color::BLACK.lighten(0.5), Color::BLACK);
+        assert_eq!(Color::BLACK.lighten(1.0), Color::WHITE);
+        assert_eq!(Color::WHITE.darken(1.0), Color::BLACK);
+
+        // --- hue is kept when a saturated colour gets lighter, although channels would clip in RGB
+        let orange = Color::rgb(255, 128, 0);
+        let hue = orange.to_oklch().h;
+        let lighter = orange.lighten(0.5);
+        assert!(lighter.to_oklch().l > orange.to_oklch().l);
+        assert!(close(lighter.to_oklch().h, hue, 2.0));
+
+        assert_eq!(orange.desaturate(1.0).r, orange.desaturate(1.0).b);
+        assert!(orange.saturate(0.5).to_oklch().c >= orange.to_oklch().c - 1e-3);
+        assert!(close(orange.rotate_hue(120.0).to_oklch().h, (hue + 120.0) % 360.0, 2.0));
+        assert_eq!(orange.with_alpha(0.5).darken(0.2).a, 0.5);
+    }
+}
@@ -1,13 +1,138 @@
+use std::any::Any;
+use crate::geometry::BBox;
 use crate::style::Style;
 
-pub trait ToSvg {
+/// Access to an element as [`Any`], so a `dyn ToSvg` can be downcast to its concrete type.
+///
+/// Implemented for every type; see `downcast_ref()` of `dyn ToSvg`.
+pub trait AsAny: Any {
+    fn as_any(&self) -> &dyn Any;
+
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any(&self) -> &dyn Any { self }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any { self }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> { self }
+}
+
+pub trait ToSvg: AsAny {
     fn to_svg(&self) -> String;
+
+    /// Identifier of this element, if it has one
+    fn id(&self) -> Option<&str> { None }
+
+    /// Name of the SVG element this object is written as, e.g. ``"circle"``, if it's a single element
+    fn tag(&self) -> Option<&str> { None }
+
+    /// CSS class (or space-separated classes) of this element, if it has one
+    fn class(&self) -> Option<&str> { None }
+
+    /// Style of this element, if it has one
+    fn style(&self) -> Option<&Style> { None }
+
+    /// Elements nested inside this element, e.g. the children of a group
+    fn children(&self) -> &[Box<dyn ToSvg>] { &[] }
+
+    /// Elements nested inside this element, in document order.
+    ///
+    /// Same as [`children()`](ToSvg::children), except for elements that don't keep their children boxed,
+    /// such as a drawing, whose nested elements are its layers.
+    fn nested(&self) -> Box<dyn Iterator<Item = &dyn ToSvg> + '_> {
+        Box::new(self.children().iter().map(|c| c.as_ref()))
+    }
+
+    /// Tells whether this element is drawn; hidden elements, such as hidden layers, count neither in
+    /// the bounding boxes of their parents nor in a [`SpatialIndex`](crate::SpatialIndex)
+    fn is_visible(&self) -> bool { true }
+
+    /// Bounding box of the geometry of this element, ignoring the width of its stroke.
+    ///
+    /// Elements that contain other elements cover the boxes of all their visible ones;
+    /// `None` means the element draws nothing.
+    fn bbox(&self) -> Option<BBox> {
+        self.nested().filter(|c| c.is_visible()).filter_map(|c| c.bbox()).reduce(|a, b| a.union(&b))
+    }
+
+    /// Mutable style of this element, if it has one
+    fn style_mut(&mut self) -> Option<&mut Style> { None }
+
+    /// Mutable access to the elements nested inside this element
+    fn children_mut(&mut self) -> &mut [Box<dyn ToSvg>] { &mut [] }
+
+    /// Takes the `index`-th element of [`children_mut()`](ToSvg::children_mut) out of this element.
+    ///
+    /// Returns `None` when the index is out of range, or when this element can't lose its children,
+    /// e.g. because it generates them.
+    fn remove_child(&mut self, _index: usize) -> Option<Box<dyn ToSvg>> { None }
+
+    /// Calls `f` on the style of this element and on the styles of all the elements nested in it.
+    ///
+    /// Elements that generate their content on the fly override this to expose the styles they use.
+    fn visit_styles_mut(&mut self, f: &mut dyn FnMut(&mut Style)) {
+        if let Some(style) = self.style_mut() { f(style); }
+        for child in self.children_mut() { child.visit_styles_mut(f); }
+    }
+}
+
+impl dyn ToSvg + '_ {
+    /// Tells whether this element is of type `T`
+    pub fn is<T: ToSvg>(&self) -> bool { self.as_any().is::<T>() }
+
+    /// This element as its concrete type `T`, or `None` when it's of another type.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::shapes::{Circle, Rect};
+    /// use visualife::ToSvg;
+    /// let element: Box<dyn ToSvg> = Box::new(Circle::new("c", 0.0, 0.0, 1.0));
+    /// assert_eq!(element.downcast_ref::<Circle>().unwrap().radius, 1.0);
+    /// assert!(element.downcast_ref::<Rect>().is_none());
+    /// ```
+    pub fn downcast_ref<T: ToSvg>(&self) -> Option<&T> { self.as_any().downcast_ref() }
+
+    /// Mutable access to this element as its concrete type `T`, or `None` when it's of another type
+    pub fn downcast_mut<T: ToSvg>(&mut self) -> Option<&mut T> { self.as_any_mut().downcast_mut() }
+}
+
+/// Appends the ``class`` attribute, if the element has a class
+pub(crate) fn push_class(class: &Option<String>, svg_string: &mut String) {
+    if let Some(class) = class {
+        svg_string.push_str(&format!(r#" class="{}""#, escape(class, true)));
+    }
+}
+
+/// Appends attributes kept as they are, such as those of an imported element that visualife has no field for
+pub(crate) fn push_attributes(attributes: &[(String, String)], svg_string: &mut String) {
+    for (name, value) in attributes {
+        svg_string.push_str(&format!(r#" {}="{}""#, name, escape(value, true)));
+    }
+}
+
+/// Escapes the characters that can't appear literally in XML text, or in an attribute value
+pub(crate) fn escape(s: &str, attribute: bool) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' if attribute => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+    out
 }
 
 pub(crate) fn close_element(style: &Style, svg_string: &mut String) {
 
     if !style.is_empty() {
-        svg_string.push_str(&format!(r#" style="{}""#, style.to_string()));
+        svg_string.push_str(&format!(r#" style="{}""#, escape(&style.to_string(), true)));
     }
     svg_string.push_str(r#" />"#);
 }
@@ -16,7 +141,9 @@ pub(crate) fn close_element(style: &Style, svg_string: &mut String) {
 #[cfg(test)]
 mod tests {
     use crate::draw_svg::close_element;
+    use crate::shapes::{Circle, Group, Rect};
     use crate::style::Style;
+    use crate::ToSvg;
 
     #[test]
     fn close_circle() {
@@ -27,8 +154,18 @@ mod tests {
 
         let mut svg_string = String::new();
         style.set_stroke_width(1.0);
-        style.set_stroke("#000000");
+        style.try_set_stroke("#000000").unwrap();
         close_element(&style, &mut svg_string);
-        assert_eq!(svg_string, r#" style="stroke:#000000;stroke-width:1;" />"#);
+        assert_eq!(svg_string, r#" style="stroke:#000;stroke-width:1;" />"#);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn escape_attributes() {
+        let mut circle = Circle::new("a&b", 1.0, 1.0, 1.0);
+        circle.class = Some("\"x\"".to_string());
+        assert_eq!(circle.to_svg(), r#"<circle id="a&amp;b" cx="1" cy="1" r="1" class="&quot;x&quot;" />"#);
+        let mut group = Group::new("<g>");
+        group.add_element(Box::new(Rect::new("r\"", 0.0, 0.0, 1.0, 1.0)));
+        assert_eq!(group.to_svg(), "<g id=\"&lt;g&gt;\">\n\t<rect id=\"r&quot;\" x=\"0\" y=\"0\" width=\"1\" height=\"1\" />\n</g>");
+    }
+}
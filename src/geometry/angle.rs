@@ -0,0 +1,85 @@
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// An angle measured in degrees, as used by SVG attributes.
+///
+/// # Example
+/// ```
+/// use visualife::geometry::{Degrees, Radians};
+/// let right: Radians = Degrees(90.0).into();
+/// assert!((right.0 - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+/// assert_eq!(Degrees(30.0) + Degrees(15.0), Degrees(45.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Degrees(pub f32);
+
+/// An angle measured in radians, as used by trigonometric functions
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Radians(pub f32);
+
+impl Degrees {
+    pub fn to_radians(self) -> Radians { Radians(self.0.to_radians()) }
+}
+
+impl Radians {
+    pub fn to_degrees(self) -> Degrees { Degrees(self.0.to_degrees()) }
+
+    pub fn sin(self) -> f32 { self.0.sin() }
+
+    pub fn cos(self) -> f32 { self.0.cos() }
+
+    pub fn tan(self) -> f32 { self.0.tan() }
+
+    pub fn sin_cos(self) -> (f32, f32) { self.0.sin_cos() }
+}
+
+impl From<Degrees> for Radians {
+    fn from(angle: Degrees) -> Self { angle.to_radians() }
+}
+
+impl From<Radians> for Degrees {
+    fn from(angle: Radians) -> Self { angle.to_degrees() }
+}
+
+/// Arithmetic within a single unit; mixing units requires an explicit conversion
+macro_rules! angle_ops {
+    ($t:ident) => {
+        impl Add for $t {
+            type Output = $t;
+            fn add(self, rhs: $t) -> $t { $t(self.0 + rhs.0) }
+        }
+
+        impl Sub for $t {
+            type Output = $t;
+            fn sub(self, rhs: $t) -> $t { $t(self.0 - rhs.0) }
+        }
+
+        impl Neg for $t {
+            type Output = $t;
+            fn neg(self) -> $t { $t(-self.0) }
+        }
+
+        impl Mul<f32> for $t {
+            type Output = $t;
+            fn mul(self, rhs: f32) -> $t { $t(self.0 * rhs) }
+        }
+
+        impl Mul<$t> for f32 {
+            type Output = $t;
+            fn mul(self, rhs: $t) -> $t { $t(self * rhs.0) }
+        }
+
+        impl Div<f32> for $t {
+            type Output = $t;
+            fn div(self, rhs: f32) -> $t { $t(self.0 / rhs) }
+        }
+
+        impl fmt::Display for $t {
+            /// Writes the bare number, as expected by SVG attributes
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { write!(f, "{}", self.0) }
+        }
+    };
+}
+
+angle_ops!(Degrees);
+angle_ops!(Radians);
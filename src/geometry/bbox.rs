@@ -0,0 +1,77 @@
+use crate::geometry::{Point, Vector};
+
+/// An axis-aligned bounding box.
+///
+/// # Example
+/// ```
+/// use visualife::geometry::{BBox, Point};
+/// let b = BBox::from_points([Point::new(3.0, 1.0), Point::new(-1.0, 4.0), Point::new(0.0, 0.0)]).unwrap();
+/// assert_eq!((b.width(), b.height()), (4.0, 4.0));
+/// assert!(b.contains(Point::new(2.0, 2.0)));
+/// assert_eq!(b.union(&BBox::new(Point::new(5.0, 5.0), Point::new(6.0, 6.0))).max, Point::new(6.0, 6.0));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BBox {
+    /// corner with the smallest coordinates; the top-left corner on screen
+    pub min: Point,
+    /// corner with the largest coordinates; the bottom-right corner on screen
+    pub max: Point,
+}
+
+impl BBox {
+    /// Box spanned by two opposite corners, given in any order
+    pub fn new(a: Point, b: Point) -> Self {
+        BBox { min: Point::new(a.x.min(b.x), a.y.min(b.y)), max: Point::new(a.x.max(b.x), a.y.max(b.y)) }
+    }
+
+    /// Smallest box that contains all the given points, or `None` when there are none
+    pub fn from_points(points: impl IntoIterator<Item = Point>) -> Option<BBox> {
+        let mut points = points.into_iter();
+        let first = points.next()?;
+        Some(points.fold(BBox { min: first, max: first }, |b, p| b.include(p)))
+    }
+
+    pub fn width(&self) -> f32 { self.max.x - self.min.x }
+
+    pub fn height(&self) -> f32 { self.max.y - self.min.y }
+
+    pub fn area(&self) -> f32 { self.width() * self.height() }
+
+    pub fn center(&self) -> Point { self.min.midpoint(self.max) }
+
+    /// The four corners, clockwise on screen from `min`
+    pub fn corners(&self) -> [Point; 4] {
+        [self.min, Point::new(self.max.x, self.min.y), self.max, Point::new(self.min.x, self.max.y)]
+    }
+
+    /// Tells whether a point lies inside this box or on its border
+    pub fn contains(&self, p: Point) -> bool {
+        p.x >= self.min.x && p.x <= self.max.x && p.y >= self.min.y && p.y <= self.max.y
+    }
+
+    /// Distance from `p` to the nearest point of this box; zero when the point lies inside
+    pub fn distance_to(&self, p: Point) -> f32 {
+        let dx = (self.min.x - p.x).max(p.x - self.max.x).max(0.0);
+        let dy = (self.min.y - p.y).max(p.y - self.max.y).max(0.0);
+        dx.hypot(dy)
+    }
+
+    /// Tells whether this box and `other` overlap or touch
+    pub fn intersects(&self, other: &BBox) -> bool {
+        self.min.x <= other.max.x && other.min.x <= self.max.x && self.min.y <= other.max.y && other.min.y <= self.max.y
+    }
+
+    /// Smallest box that contains this box and `other`
+    pub fn union(&self, other: &BBox) -> BBox { self.include(other.min).include(other.max) }
+
+    /// Smallest box that contains this box and the point `p`
+    pub fn include(&self, p: Point) -> BBox {
+        BBox { min: Point::new(self.min.x.min(p.x), self.min.y.min(p.y)), max: Point::new(self.max.x.max(p.x), self.max.y.max(p.y)) }
+    }
+
+    /// This box grown by `margin` on every side; a negative margin shrinks it
+    pub fn expand(&self, margin: f32) -> BBox {
+        let m = Vector::new(margin, margin);
+        BBox { min: self.min - m, max: self.max + m }
+    }
+}
@@ -0,0 +1,238 @@
+use std::collections::{BTreeSet, HashMap};
+use crate::geometry::Point;
+use crate::shapes::Path;
+
+type Pt = [f64; 2];
+
+/// Vertex at infinity shared by the ghost triangles, which lie outside the convex hull, one for each of its edges
+const GHOST: usize = usize::MAX;
+
+/// A triangulation of a set of points.
+///
+/// # Example
+/// ```
+/// use visualife::geometry::{Point, Triangulation};
+/// let square = [(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0), (5.0, 4.0)].map(Point::from);
+/// let t = Triangulation::delaunay(&square);
+/// assert_eq!(t.triangles.len(), 4);
+/// assert_eq!(t.edges().len(), 8);
+/// let mesh = t.to_path("mesh");
+/// assert_eq!(mesh.d.len(), 16);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Triangulation {
+    /// the triangulated points, as given
+    pub points: Vec<Point>,
+    /// triangles as indices of their vertices in `points`; vertices go clockwise on screen
+    pub triangles: Vec<[usize; 3]>,
+}
+
+/// Triangle of the triangulation under construction
+struct Triangle {
+    v: [usize; 3],
+    /// `adj[i]` is the triangle across the edge opposite to `v[i]`
+    adj: [Option<usize>; 3],
+    alive: bool,
+}
+
+impl Triangulation {
+    /// Delaunay triangulation: no point lies inside the circumcircle of any triangle.
+    ///
+    /// Points are inserted one by one (Bowyer–Watson algorithm); each of them is located by walking
+    /// across the triangles from the previous insertion, so the expected time is close to O(n log n)
+    /// for well-spread points. Repeated points are triangulated once; the triangulation of points lying
+    /// on a single line has no triangles.
+    pub fn delaunay(points: &[Point]) -> Triangulation {
+        let mut out = Triangulation { points: points.to_vec(), triangles: vec![] };
+        let verts: Vec<Pt> = points.iter().map(|p| [p.x as f64, p.y as f64]).collect();
+
+        // ---------- insertion in a spatially coherent order keeps the walks short
+        let mut order: Vec<usize> = (0..points.len()).collect();
+        order.sort_by(|&a, &b| verts[a][0].total_cmp(&verts[b][0]).then(verts[a][1].total_cmp(&verts[b][1])));
+        order.dedup_by(|a, b| verts[*a] == verts[*b]);
+        // ---------- the first triangle needs three points that are not on a line
+        let Some(k) = (2..order.len()).find(|&k| orient(verts[order[0]], verts[order[1]], verts[order[k]]) != 0.0) else {
+            return out;
+        };
+        let (a, mut b, mut c) = (order[0], order[1], order.remove(k));
+        if orient(verts[a], verts[b], verts[c]) < 0.0 { (b, c) = (c, b); }
+        // ---------- the real triangle, then the ghosts across its edges opposite to a, b and c
+        let mut tris = vec![
+            Triangle { v: [a, b, c], adj: [Some(1), Some(2), Some(3)], alive: true },
+            Triangle { v: [c, b, GHOST], adj: [Some(3), Some(2), Some(0)], alive: true },
+            Triangle { v: [a, c, GHOST], adj: [Some(1), Some(3), Some(0)], alive: true },
+            Triangle { v: [b, a, GHOST], adj: [Some(2), Some(1), Some(0)], alive: true },
+        ];
+
+        let mut last = 0;
+        for &i in &order[2..] {
+            let p = verts[i];
+            let start = locate(&tris, &verts, last, p);
+            // ---------- cavity: the triangles whose circumcircle contains the new point, in a deterministic order
+            let mut bad: BTreeSet<usize> = BTreeSet::from([start]);
+            let mut stack = vec![start];
+            while let Some(b) = stack.pop() {
+                for nb in tris[b].adj.into_iter().flatten() {
+                    if !bad.contains(&nb) && in_circumcircle(&verts, tris[nb].v, p) {
+                        bad.insert(nb);
+                        stack.push(nb);
+                    }
+                }
+            }
+            // ---------- the cavity is filled with triangles joining its boundary edges to the new point
+            let (mut by_start, mut by_end) = (HashMap::new(), HashMap::new());
+            let mut created = vec![];
+            for &b in &bad {
+                tris[b].alive = false;
+                for e in 0..3 {
+                    let outer = tris[b].adj[e];
+                    if outer.is_some_and(|o| bad.contains(&o)) { continue; }
+                    let (a, c) = (tris[b].v[(e + 1) % 3], tris[b].v[(e + 2) % 3]);
+                    let t = tris.len();
+                    tris.push(Triangle { v: [a, c, i], adj: [None, None, outer], alive: true });
+                    if let Some(o) = outer {
+                        for k in 0..3 { if tris[o].adj[k] == Some(b) { tris[o].adj[k] = Some(t); } }
+                    }
+                    by_start.insert(a, t);
+                    by_end.insert(c, t);
+                    created.push(t);
+                }
+            }
+            for &t in &created {
+                let [a, c, _] = tris[t].v;
+                tris[t].adj[0] = by_start.get(&c).copied();
+                tris[t].adj[1] = by_end.get(&a).copied();
+            }
+            last = created[0];
+        }
+
+        out.triangles = tris.iter().filter(|t| t.alive && !t.v.contains(&GHOST)).map(|t| t.v).collect();
+        out
+    }
+
+    /// Edges of the triangles, each given once as a pair of point indices, the smaller first
+    pub fn edges(&self) -> Vec<(usize, usize)> {
+        let mut edges: Vec<(usize, usize)> = self.triangles.iter()
+            .flat_map(|t| (0..3).map(move |k| (t[k].min(t[(k + 1) % 3]), t[k].max(t[(k + 1) % 3]))))
+            .collect();
+        edges.sort_unstable();
+        edges.dedup();
+        edges
+    }
+
+    /// Vertices of the `k`-th triangle
+    pub fn triangle(&self, k: usize) -> [Point; 3] { self.triangles[k].map(|i| self.points[i]) }
+
+    /// Every triangle as a separate closed path; the path of the `k`-th triangle has the id ``"{id}:{k}"``
+    pub fn to_paths(&self, id: &str) -> Vec<Path> {
+        (0..self.triangles.len()).map(|k| Path::polygon(&format!("{}:{}", id, k), &self.triangle(k))).collect()
+    }
+
+    /// All the triangles as sub-paths of a single path, e.g. to draw the whole mesh with one element
+    pub fn to_path(&self, id: &str) -> Path {
+        let mut p = Path::new(id);
+        for k in 0..self.triangles.len() {
+            p.d.extend(Path::polygon("", &self.triangle(k)).d);
+        }
+        p
+    }
+}
+
+/// Finds a triangle containing `p` by walking towards it from the triangle `from`.
+///
+/// A point outside the convex hull of the points inserted so far is found in a ghost triangle.
+fn locate(tris: &[Triangle], verts: &[Pt], from: usize, p: Pt) -> usize {
+    let outside = |tri: &Triangle, e: usize| orient(verts[tri.v[(e + 1) % 3]], verts[tri.v[(e + 2) % 3]], p) < 0.0;
+    let mut t = from;
+    // ---------- a walk starts from a real triangle
+    if let Some(g) = tris[t].v.iter().position(|&v| v == GHOST) { t = tris[t].adj[g].unwrap(); }
+    for _ in 0..tris.len() {
+        let tri = &tris[t];
+        if tri.v.contains(&GHOST) { return t; }
+        match (0..3).find(|&e| outside(tri, e)).and_then(|e| tri.adj[e]) {
+            Some(next) => t = next,
+            None => return t,
+        }
+    }
+    // ---------- a walk can't cycle in a Delaunay triangulation, but rounding errors might make it
+    tris.iter().position(|tri| tri.alive && !tri.v.contains(&GHOST) && (0..3).all(|e| !outside(tri, e)))
+        .or_else(|| tris.iter().position(|tri| tri.alive && in_circumcircle(verts, tri.v, p)))
+        .unwrap_or(t)
+}
+
+/// Tells whether `p` lies inside the circumcircle of a triangle.
+///
+/// The circumcircle of a ghost triangle is the open half-plane beyond its hull edge, together with the
+/// inside of that edge.
+fn in_circumcircle(verts: &[Pt], v: [usize; 3], p: Pt) -> bool {
+    match v.iter().position(|&i| i == GHOST) {
+        None => in_circle(verts[v[0]], verts[v[1]], verts[v[2]], p),
+        Some(g) => {
+            let (a, b) = (verts[v[(g + 1) % 3]], verts[v[(g + 2) % 3]]);
+            let o = orient(a, b, p);
+            o > 0.0 || (o == 0.0 && (p[0] - a[0]) * (p[0] - b[0]) + (p[1] - a[1]) * (p[1] - b[1]) < 0.0)
+        }
+    }
+}
+
+/// Twice the signed area of the triangle `abc`; positive when it turns clockwise on screen
+fn orient(a: Pt, b: Pt, c: Pt) -> f64 {
+    (b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])
+}
+
+/// Tells whether `d` lies inside the circumcircle of the triangle `abc`, given clockwise on screen
+fn in_circle(a: Pt, b: Pt, c: Pt, d: Pt) -> bool {
+    let (adx, ady) = (a[0] - d[0], a[1] - d[1]);
+    let (bdx, bdy) = (b[0] - d[0], b[1] - d[1]);
+    let (cdx, cdy) = (c[0] - d[0], c[1] - d[1]);
+    let det = (adx * adx + ady * ady) * (bdx * cdy - cdx * bdy)
+        + (bdx * bdx + bdy * bdy) * (cdx * ady - adx * cdy)
+        + (cdx * cdx + cdy * cdy) * (adx * bdy - bdx * ady);
+    det > 0.0
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::{convex_hull, scattered, Point, Triangulation};
+
+    fn check_delaunay(t: &Triangulation) {
+        for (k, tri) in t.triangles.iter().enumerate() {
+            let [a, b, c] = t.triangle(k);
+            assert!((b - a).cross(c - a) > 0.0, "triangle {:?} is not clockwise on screen", tri);
+            // ---------- empty circumcircle
+            let (d, e, f) = (b - a, c - a, 2.0 * (b - a).cross(c - a));
+            let center = a + crate::geometry::Vector::new(
+                (e.y * d.length_squared() - d.y * e.length_squared()) / f,
+                (d.x * e.length_squared() - e.x * d.length_squared()) / f);
+            let r = center.distance(a);
+            for (i, p) in t.points.iter().enumerate() {
+                assert!(tri.contains(&i) || center.distance(*p) >= r * (1.0 - 1e-4), "point {} inside triangle {:?}", i, tri);
+            }
+        }
+    }
+
+    #[test]
+    fn scattered_points() {
+        let points = scattered(300);
+        let t = Triangulation::delaunay(&points);
+        check_delaunay(&t);
+        // ---------- Euler's formula for a triangulated convex polygon
+        let h = convex_hull(&points).len();
+        assert_eq!(t.triangles.len(), 2 * points.len() - 2 - h);
+        assert_eq!(Triangulation::delaunay(&points), t);
+    }
+
+    #[test]
+    fn degenerate_points() {
+        // ---------- a grid has many points on common circles, and collinear points on its hull
+        let grid: Vec<Point> = (0..36).map(|i| Point::new((i % 6) as f32, (i / 6) as f32)).collect();
+        let t = Triangulation::delaunay(&grid);
+        assert_eq!(t.triangles.len(), 50);
+        let mut with_repeats = grid.clone();
+        with_repeats.extend_from_slice(&grid[..5]);
+        assert_eq!(Triangulation::delaunay(&with_repeats).triangles.len(), 50);
+        let line: Vec<Point> = (0..5).map(|i| Point::new(i as f32, 2.0 * i as f32)).collect();
+        assert!(Triangulation::delaunay(&line).triangles.is_empty());
+        assert!(Triangulation::delaunay(&grid[..2]).triangles.is_empty());
+    }
+}
@@ -0,0 +1,44 @@
+use crate::geometry::Point;
+
+/// Convex hull of a set of points.
+///
+/// Returns the vertices of the hull, clockwise on screen (where the y axis points down), starting from
+/// the leftmost point. Points lying on the edges of the hull are not included, nor are repeated points.
+/// Uses Andrew's monotone chain algorithm, in O(n log n).
+///
+/// # Example
+/// ```
+/// use visualife::geometry::{convex_hull, Point};
+/// use visualife::shapes::Path;
+/// let points = [(0.0, 0.0), (4.0, 0.0), (2.0, 1.0), (4.0, 4.0), (0.0, 4.0), (2.0, 0.0)].map(Point::from);
+/// let hull = convex_hull(&points);
+/// assert_eq!(hull, [(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)].map(Point::from));
+/// let outline = Path::polygon("outline", &hull);
+/// assert_eq!(outline.d.len(), 5);
+/// ```
+pub fn convex_hull(points: &[Point]) -> Vec<Point> {
+    let mut sorted: Vec<Point> = points.to_vec();
+    sorted.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    sorted.dedup();
+    if sorted.len() < 3 { return sorted; }
+
+    // ---------- chain along the top (on screen) left to right, then along the bottom right to left;
+    // ---------- a point that doesn't turn clockwise from the two before it removes the middle one
+    let turn = |o: Point, a: Point, b: Point| {
+        let (ox, oy) = (o.x as f64, o.y as f64);
+        (a.x as f64 - ox) * (b.y as f64 - oy) - (a.y as f64 - oy) * (b.x as f64 - ox)
+    };
+    let chain = |points: &mut dyn Iterator<Item = &Point>| {
+        let mut out: Vec<Point> = vec![];
+        for &p in points {
+            while out.len() >= 2 && turn(out[out.len() - 2], out[out.len() - 1], p) <= 0.0 { out.pop(); }
+            out.push(p);
+        }
+        out.pop();
+        out
+    };
+    let mut hull = chain(&mut sorted.iter());
+    hull.extend(chain(&mut sorted.iter().rev()));
+
+    hull
+}
@@ -0,0 +1,28 @@
+//! Basic 2D geometry.
+//!
+//! [`Point`]s are positions and [`Vector`]s are displacements; subtracting two points gives a vector and adding
+//! a vector to a point moves it. Angles are either [`Degrees`] or [`Radians`]; functions that take an angle accept
+//! `impl Into<Radians>` (or `impl Into<Degrees>`), so either unit may be given, but a bare number may not.
+//! [`BBox`] is an axis-aligned bounding box.
+//!
+//! Algorithms over point sets: the [`convex_hull()`], Delaunay [`Triangulation`] and [`Voronoi`] diagrams.
+//! Their results convert to [`Path`](crate::shapes::Path)s, ready to be drawn.
+mod angle;
+mod bbox;
+mod delaunay;
+mod hull;
+mod point;
+mod voronoi;
+
+pub use angle::*;
+pub use bbox::*;
+pub use delaunay::*;
+pub use hull::*;
+pub use point::*;
+pub use voronoi::*;
+
+/// Well spread points of the R2 low-discrepancy sequence, for tests of the algorithms over point sets
+#[cfg(test)]
+pub(crate) fn scattered(n: usize) -> Vec<Point> {
+    (1..=n).map(|i| Point::new((i as f32 * 0.754_877_7).fract() * 100.0, (i as f32 * 0.569_840_3).fract() * 100.0)).collect()
+}
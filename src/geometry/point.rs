@@ -0,0 +1,201 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
+use crate::geometry::Radians;
+
+/// A position on the plane.
+///
+/// # Example
+/// ```
+/// use visualife::geometry::{Degrees, Point, Vector};
+/// let a = Point::new(1.0, 1.0);
+/// let b = a + Vector::new(3.0, 4.0);
+/// assert_eq!(b - a, Vector::new(3.0, 4.0));
+/// assert_eq!(a.distance(b), 5.0);
+/// let p = Point::from_polar(a, 2.0, Degrees(90.0));
+/// assert!(p.distance(Point::new(1.0, 3.0)) < 1e-6);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+/// A displacement on the plane
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Vector {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Point {
+    pub const ORIGIN: Point = Point { x: 0.0, y: 0.0 };
+
+    pub fn new(x: f32, y: f32) -> Self { Point { x, y } }
+
+    /// Point at `radius` from `center`, in the direction given by `angle`; angles grow clockwise on screen
+    pub fn from_polar(center: Point, radius: f32, angle: impl Into<Radians>) -> Point {
+        center + Vector::from_angle(angle) * radius
+    }
+
+    /// Polar coordinates of this point around `center`: the distance and the direction
+    pub fn to_polar(self, center: Point) -> (f32, Radians) {
+        let v = self - center;
+        (v.length(), v.angle())
+    }
+
+    pub fn distance(self, other: Point) -> f32 { (other - self).length() }
+
+    /// Point that divides the segment from `self` to `other` at fraction `t`
+    pub fn lerp(self, other: Point, t: f32) -> Point { self + (other - self) * t }
+
+    pub fn midpoint(self, other: Point) -> Point { self.lerp(other, 0.5) }
+
+    /// Vector from the origin to this point
+    pub fn to_vector(self) -> Vector { Vector::new(self.x, self.y) }
+}
+
+impl Vector {
+    pub const ZERO: Vector = Vector { x: 0.0, y: 0.0 };
+
+    pub fn new(x: f32, y: f32) -> Self { Vector { x, y } }
+
+    /// Unit vector pointing in the direction given by `angle`
+    pub fn from_angle(angle: impl Into<Radians>) -> Vector {
+        let (sin, cos) = angle.into().sin_cos();
+        Vector::new(cos, sin)
+    }
+
+    pub fn length(self) -> f32 { self.x.hypot(self.y) }
+
+    pub fn length_squared(self) -> f32 { self.dot(self) }
+
+    pub fn dot(self, other: Vector) -> f32 { self.x * other.x + self.y * other.y }
+
+    /// The z component of the 3D cross product; positive when `other` turns clockwise from `self` on screen
+    pub fn cross(self, other: Vector) -> f32 { self.x * other.y - self.y * other.x }
+
+    /// Unit vector of the same direction; the zero vector is returned unchanged
+    pub fn normalize(self) -> Vector {
+        let length = self.length();
+        if length == 0.0 { self } else { self / length }
+    }
+
+    /// This vector turned by `angle`; positive angles turn clockwise on screen
+    pub fn rotate(self, angle: impl Into<Radians>) -> Vector {
+        let (sin, cos) = angle.into().sin_cos();
+        Vector::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+    }
+
+    /// This vector turned by a right angle, clockwise on screen
+    pub fn perpendicular(self) -> Vector { Vector::new(-self.y, self.x) }
+
+    /// Direction of this vector, measured from the x axis
+    pub fn angle(self) -> Radians { Radians(self.y.atan2(self.x)) }
+
+    pub fn lerp(self, other: Vector, t: f32) -> Vector { self + (other - self) * t }
+}
+
+impl From<(f32, f32)> for Point {
+    fn from(p: (f32, f32)) -> Self { Point::new(p.0, p.1) }
+}
+
+impl From<Point> for (f32, f32) {
+    fn from(p: Point) -> Self { (p.x, p.y) }
+}
+
+impl From<(f32, f32)> for Vector {
+    fn from(v: (f32, f32)) -> Self { Vector::new(v.0, v.1) }
+}
+
+impl From<Vector> for (f32, f32) {
+    fn from(v: Vector) -> Self { (v.x, v.y) }
+}
+
+impl Add<Vector> for Point {
+    type Output = Point;
+    fn add(self, v: Vector) -> Point { Point::new(self.x + v.x, self.y + v.y) }
+}
+
+impl AddAssign<Vector> for Point {
+    fn add_assign(&mut self, v: Vector) { *self = *self + v; }
+}
+
+impl Sub<Vector> for Point {
+    type Output = Point;
+    fn sub(self, v: Vector) -> Point { Point::new(self.x - v.x, self.y - v.y) }
+}
+
+impl SubAssign<Vector> for Point {
+    fn sub_assign(&mut self, v: Vector) { *self = *self - v; }
+}
+
+impl Sub for Point {
+    type Output = Vector;
+    fn sub(self, other: Point) -> Vector { Vector::new(self.x - other.x, self.y - other.y) }
+}
+
+impl Add for Vector {
+    type Output = Vector;
+    fn add(self, other: Vector) -> Vector { Vector::new(self.x + other.x, self.y + other.y) }
+}
+
+impl Sub for Vector {
+    type Output = Vector;
+    fn sub(self, other: Vector) -> Vector { Vector::new(self.x - other.x, self.y - other.y) }
+}
+
+impl Neg for Vector {
+    type Output = Vector;
+    fn neg(self) -> Vector { Vector::new(-self.x, -self.y) }
+}
+
+impl Mul<f32> for Vector {
+    type Output = Vector;
+    fn mul(self, s: f32) -> Vector { Vector::new(self.x * s, self.y * s) }
+}
+
+impl Mul<Vector> for f32 {
+    type Output = Vector;
+    fn mul(self, v: Vector) -> Vector { v * self }
+}
+
+impl Div<f32> for Vector {
+    type Output = Vector;
+    fn div(self, s: f32) -> Vector { Vector::new(self.x / s, self.y / s) }
+}
+
+impl fmt::Display for Point {
+    /// Writes the coordinates as in SVG ``points`` attributes, e.g. ``10,20``
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { write!(f, "{},{}", self.x, self.y) }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::{Degrees, Point, Radians, Vector};
+
+    #[test]
+    fn vector_algebra() {
+        let (a, b) = (Vector::new(3.0, 4.0), Vector::new(-4.0, 3.0));
+        assert_eq!(a.dot(b), 0.0);
+        assert_eq!(a.cross(b), 25.0);
+        assert_eq!(a.normalize(), Vector::new(0.6, 0.8));
+        assert_eq!(Vector::ZERO.normalize(), Vector::ZERO);
+        assert_eq!(a.perpendicular(), b);
+        let r = a.rotate(Degrees(90.0));
+        assert!((r - b).length() < 1e-5);
+        assert!((a.rotate(Radians(std::f32::consts::PI)) + a).length() < 1e-5);
+        assert_eq!(a.lerp(b, 0.5), Vector::new(-0.5, 3.5));
+        assert!((Vector::new(0.0, 2.0).angle().to_degrees().0 - 90.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn polar_round_trip() {
+        let c = Point::new(10.0, -5.0);
+        let p = Point::from_polar(c, 7.0, Degrees(-120.0));
+        let (r, theta) = p.to_polar(c);
+        assert!((r - 7.0).abs() < 1e-5);
+        assert!((Degrees::from(theta).0 + 120.0).abs() < 1e-4);
+        assert_eq!(Point::new(0.0, 0.0).midpoint(Point::new(2.0, 4.0)), Point::new(1.0, 2.0));
+        assert_eq!(Point::new(1.0, 2.0).to_string(), "1,2");
+    }
+}
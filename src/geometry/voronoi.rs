@@ -0,0 +1,128 @@
+use crate::geometry::{BBox, Point, Triangulation};
+use crate::shapes::Path;
+
+type Pt = [f64; 2];
+
+/// Voronoi diagram of a set of points, clipped to a rectangle.
+///
+/// The cell of a point (a *site*) is the region closer to it than to any other site.
+///
+/// # Example
+/// ```
+/// use visualife::geometry::{BBox, Point, Voronoi};
+/// let sites = [(25.0, 50.0), (75.0, 50.0)].map(Point::from);
+/// let v = Voronoi::new(&sites, BBox::new(Point::new(0.0, 0.0), Point::new(100.0, 100.0)));
+/// // ---------- the two cells split the box in halves
+/// assert_eq!(v.cells[0].len(), 4);
+/// assert!(v.cells[0].iter().all(|p| p.x <= 50.0) && v.cells[1].iter().all(|p| p.x >= 50.0));
+/// assert_eq!(v.to_paths("cell").len(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Voronoi {
+    pub sites: Vec<Point>,
+    /// vertices of the cell of every site, clockwise on screen; empty for a repeated site
+    /// and for a site whose cell lies outside the bounds
+    pub cells: Vec<Vec<Point>>,
+}
+
+impl Voronoi {
+    /// Computes the cells of the given sites within `bounds`.
+    ///
+    /// The neighbours of every site are found by Delaunay triangulation; the cell is the rectangle
+    /// clipped by the bisectors between the site and its neighbours.
+    pub fn new(sites: &[Point], bounds: BBox) -> Voronoi {
+        let n = sites.len();
+        let mut neighbors: Vec<Vec<usize>> = vec![vec![]; n];
+        let triangulation = Triangulation::delaunay(sites);
+        if triangulation.triangles.is_empty() {
+            // ---------- fewer than three sites, or all of them on a line: every pair is a neighbour
+            for (i, list) in neighbors.iter_mut().enumerate() { list.extend((0..n).filter(|&j| j != i)); }
+        } else {
+            for (i, j) in triangulation.edges() {
+                neighbors[i].push(j);
+                neighbors[j].push(i);
+            }
+        }
+
+        let corners: Vec<Pt> = bounds.corners().iter().map(|p| [p.x as f64, p.y as f64]).collect();
+        let cells = (0..n).map(|i| {
+            let repeated = (0..i).any(|j| sites[j] == sites[i]);
+            if repeated || (neighbors[i].is_empty() && n > 1) { return vec![]; }
+            let s = [sites[i].x as f64, sites[i].y as f64];
+            let mut cell = corners.clone();
+            for &j in &neighbors[i] {
+                if sites[j] == sites[i] { continue; }
+                cell = clip_closer(&cell, s, [sites[j].x as f64, sites[j].y as f64]);
+                if cell.is_empty() { break; }
+            }
+            cell.iter().map(|p| Point::new(p[0] as f32, p[1] as f32)).collect()
+        }).collect();
+
+        Voronoi { sites: sites.to_vec(), cells }
+    }
+
+    /// Every non-empty cell as a closed path; the path of the cell of the `i`-th site has the id ``"{id}:{i}"``
+    pub fn to_paths(&self, id: &str) -> Vec<Path> {
+        self.cells.iter().enumerate().filter(|(_, c)| !c.is_empty())
+            .map(|(i, c)| Path::polygon(&format!("{}:{}", id, i), c)).collect()
+    }
+}
+
+/// Part of a convex polygon that is closer to `s` than to `other` (Sutherland–Hodgman clipping)
+fn clip_closer(polygon: &[Pt], s: Pt, other: Pt) -> Vec<Pt> {
+    let normal = [other[0] - s[0], other[1] - s[1]];
+    let mid = [(s[0] + other[0]) / 2.0, (s[1] + other[1]) / 2.0];
+    // ---------- negative on the side of s
+    let side = |p: Pt| (p[0] - mid[0]) * normal[0] + (p[1] - mid[1]) * normal[1];
+    let mut out = Vec::with_capacity(polygon.len() + 1);
+    for k in 0..polygon.len() {
+        let (a, b) = (polygon[k], polygon[(k + 1) % polygon.len()]);
+        let (da, db) = (side(a), side(b));
+        if da <= 0.0 { out.push(a); }
+        if (da < 0.0 && db > 0.0) || (da > 0.0 && db < 0.0) {
+            let t = da / (da - db);
+            out.push([a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t]);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::{scattered, BBox, Point, Voronoi};
+    use crate::shapes::{FillRule, Path};
+    use crate::ToSvg;
+
+    fn area(cell: &[Point]) -> f32 {
+        (0..cell.len()).map(|k| cell[k].to_vector().cross(cell[(k + 1) % cell.len()].to_vector())).sum::<f32>() / 2.0
+    }
+
+    #[test]
+    fn cells_tile_the_bounds() {
+        let sites = scattered(200);
+        let bounds = BBox::new(Point::new(-10.0, -10.0), Point::new(110.0, 110.0));
+        let v = Voronoi::new(&sites, bounds);
+        let total: f32 = v.cells.iter().map(|c| area(c)).sum();
+        assert!((total - bounds.area()).abs() < 1e-2 * bounds.area(), "{} != {}", total, bounds.area());
+        for (i, cell) in v.cells.iter().enumerate() {
+            assert!(area(cell) > 0.0);
+            // ---------- every site lies in its own cell, and its cell's vertices are not closer to another site
+            assert!(Path::polygon("c", cell).contains(sites[i], FillRule::NonZero));
+            for p in cell {
+                let own = p.distance(sites[i]);
+                assert!(sites.iter().all(|s| s.distance(*p) >= own - 1e-3));
+            }
+        }
+        assert!(v.to_paths("v").iter().all(|p| bounds.expand(1e-3).contains(p.bbox().unwrap().min)));
+    }
+
+    #[test]
+    fn degenerate_sites() {
+        let bounds = BBox::new(Point::new(0.0, 0.0), Point::new(10.0, 10.0));
+        let single = Voronoi::new(&[Point::new(5.0, 5.0)], bounds);
+        assert_eq!(single.cells[0].len(), 4);
+        let line = Voronoi::new(&[(1.0, 1.0), (3.0, 3.0), (5.0, 5.0), (1.0, 1.0)].map(Point::from), bounds);
+        assert!((area(&line.cells[1]) - 24.0).abs() < 1e-4);
+        assert!(line.cells[3].is_empty());
+    }
+}
@@ -0,0 +1,681 @@
+//! Import of SVG documents into visualife elements.
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+use crate::geometry::{Degrees, Point};
+use crate::layer::{Layer, ANNOTATIONS_LAYER, BACKGROUND_LAYER, DATA_LAYER, OVERLAY_LAYER};
+use crate::shapes::{Circle, Group, Path, RawElement, RawText, Rect, Transform};
+use crate::style::{Paint, Style};
+use crate::{SvgDrawing, ToSvg};
+use xml::{XmlElement, XmlNode};
+
+mod xml;
+
+/// Error returned when an SVG document can't be imported.
+///
+/// Every variant carries the byte offset in the document where the problem was found.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SvgImportError {
+    /// The document ends inside an element, a comment or another construct
+    UnexpectedEnd { offset: usize },
+    /// A character that breaks the XML syntax
+    UnexpectedCharacter { offset: usize, found: char },
+    /// An end tag that doesn't close the element that is open
+    MismatchedEndTag { offset: usize, expected: String, found: String },
+    /// An entity reference other than the five predefined by XML, or a character reference that is not valid
+    UnknownEntity { offset: usize, name: String },
+    /// The root element of the document is not ``<svg>``
+    NotSvg { offset: usize, found: String },
+    /// An element nested deeper than visualife reads
+    TooDeep { offset: usize },
+    /// Entity references that expand to more text than visualife reads
+    TooLarge { offset: usize },
+}
+
+impl SvgImportError {
+    /// Byte offset in the document where the error was found
+    pub fn offset(&self) -> usize {
+        match self {
+            SvgImportError::UnexpectedEnd { offset } | SvgImportError::UnexpectedCharacter { offset, .. }
+            | SvgImportError::MismatchedEndTag { offset, .. } | SvgImportError::UnknownEntity { offset, .. }
+            | SvgImportError::NotSvg { offset, .. } | SvgImportError::TooDeep { offset }
+            | SvgImportError::TooLarge { offset } => *offset,
+        }
+    }
+}
+
+impl fmt::Display for SvgImportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SvgImportError::UnexpectedEnd { offset } => write!(f, "unexpected end of the document at byte {}", offset),
+            SvgImportError::UnexpectedCharacter { offset, found } => write!(f, "unexpected character '{}' at byte {}", found, offset),
+            SvgImportError::MismatchedEndTag { offset, expected, found } =>
+                write!(f, "end tag </{}> at byte {} doesn't match <{}>", found, offset, expected),
+            SvgImportError::UnknownEntity { offset, name } => write!(f, "unknown entity \"&{};\" at byte {}", name, offset),
+            SvgImportError::NotSvg { offset, found } => write!(f, "the root element <{}> at byte {} is not <svg>", found, offset),
+            SvgImportError::TooDeep { offset } =>
+                write!(f, "the element at byte {} is nested deeper than {} levels", offset, xml::MAX_DEPTH),
+            SvgImportError::TooLarge { offset } =>
+                write!(f, "the entity reference at byte {} expands the document by more than {} bytes", offset, xml::MAX_EXPANSION),
+        }
+    }
+}
+
+impl std::error::Error for SvgImportError {}
+
+/// Presentation attributes that are properties of [`Style`]
+const PRESENTATION: [&str; 6] = ["fill", "stroke", "stroke-width", "opacity", "fill-opacity", "stroke-opacity"];
+
+/// Attributes understood on every imported shape and group
+const COMMON: [&str; 4] = ["id", "class", "style", "transform"];
+
+/// Attributes of the root element that the header of a drawing writes on its own
+const ROOT_ATTRIBUTES: [&str; 5] = ["width", "height", "viewBox", "preserveAspectRatio", "xmlns"];
+
+/// Elements whose text is kept even when it's only whitespace
+const TEXT_ELEMENTS: [&str; 6] = ["text", "tspan", "textPath", "style", "title", "desc"];
+
+/// Elements that take a ``transform`` attribute
+const TRANSFORMABLE: [&str; 14] = ["a", "circle", "ellipse", "foreignObject", "g", "image", "line", "path", "polygon",
+    "polyline", "rect", "switch", "text", "use"];
+
+/// Reads an SVG document; see [`SvgDrawing::from_svg()`]
+pub(crate) fn import_svg(svg: &str) -> Result<SvgDrawing, SvgImportError> {
+    let root = xml::parse_xml(svg)?;
+    if root.name != "svg" { return Err(SvgImportError::NotSvg { offset: root.offset, found: root.name }); }
+    let (width, height, root_transform) = viewport(&root);
+    let mut drawing = SvgDrawing::new(width, height);
+    // ---------- the style and the class of the root element are kept on the drawing, so they cascade as a group's do
+    let root_style = style_of(&root, root.attribute("style").unwrap_or(""));
+    for (name, value) in &root.attributes {
+        let in_style = root_style.is_some() && (name == "style" || PRESENTATION.contains(&name.as_str()));
+        if let Some(prefix) = name.strip_prefix("xmlns:") {
+            drawing.add_namespace(prefix, value);
+        } else if name == "class" {
+            drawing.set_class(value);
+        } else if !(in_style || ROOT_ATTRIBUTES.contains(&name.as_str())) {
+            drawing.add_attribute(name, value);
+        }
+    }
+    let inherited = root_style.unwrap_or_default();
+    *drawing.style_mut().unwrap() = inherited.clone();
+
+    let mut importer = Importer { ids: HashSet::new(), generated: HashMap::new(), placements: HashMap::new() };
+    importer.collect_ids(&root);
+    let elements: Vec<&XmlElement> = root.children.iter()
+        .filter_map(|node| if let XmlNode::Element(el) = node { Some(el) } else { None }).collect();
+    // ---------- a document that has layers keeps only its own ones
+    if elements.iter().any(|el| is_layer(el)) {
+        for name in [BACKGROUND_LAYER, DATA_LAYER, ANNOTATIONS_LAYER, OVERLAY_LAYER] { drawing.remove_layer(name); }
+    }
+    // ---------- names of the layers of the document, which the layers made up for other elements must not take
+    let labels: HashSet<&str> = elements.iter().filter(|el| is_layer(el))
+        .filter_map(|el| el.attribute("inkscape:label").or(el.attribute("id"))).collect();
+    let mut data_layers = (1..).map(|n| if n == 1 { DATA_LAYER.to_string() } else { format!("{} {}", DATA_LAYER, n) })
+        .filter(|name| !labels.contains(name.as_str()));
+    let mut run: Option<String> = None;
+    for (k, el) in elements.iter().enumerate() {
+        let order = 10 * k as i32;
+        if let Some(layer) = importer.layer(el, order, root_transform, &inherited) {
+            match drawing.layer_mut(&layer.name) {
+                Some(existing) => existing.elements.extend(layer.elements),
+                None => { drawing.add_layer(layer); }
+            }
+            run = None;
+            continue;
+        }
+        // ---------- every run of elements outside of layers goes to a layer of its own, so they keep their place in the stack
+        let name = run.get_or_insert_with(|| data_layers.next().unwrap());
+        if drawing.layer(name).is_none() { drawing.add_layer(Layer::new(name, order)); }
+        let element = importer.element(el, root_transform, &inherited);
+        drawing.add_to_layer(name, element);
+    }
+    // ---------- a <use> draws its element without the transformations of the element's parents, which are now folded in
+    drawing.select_mut("use", |el| {
+        if let Some(raw) = el.downcast_mut::<RawElement>() { place_use(raw, &importer.placements); }
+    }).expect("a valid selector");
+
+    Ok(drawing)
+}
+
+struct Importer {
+    /// ids used in the document, so the generated ones are unique
+    ids: HashSet<String>,
+    /// number of ids generated so far, for every tag
+    generated: HashMap<String, usize>,
+    /// transformation folded into the coordinates of every element with an id in the document, by the id
+    placements: HashMap<String, Transform>,
+}
+
+impl Importer {
+    fn collect_ids(&mut self, el: &XmlElement) {
+        if let Some(id) = el.attribute("id") { self.ids.insert(id.to_string()); }
+        for node in &el.children {
+            if let XmlNode::Element(child) = node { self.collect_ids(child); }
+        }
+    }
+
+    /// Id of an element, or a new one for an element that has none
+    fn id(&mut self, el: &XmlElement) -> String {
+        if let Some(id) = el.attribute("id") { return id.to_string(); }
+        let n = self.generated.entry(el.name.clone()).or_insert(0);
+        loop {
+            *n += 1;
+            let id = format!("{}{}", el.name, n);
+            if self.ids.insert(id.clone()) { return id; }
+        }
+    }
+
+    /// Imports an element, placed in the coordinates given by `t`; `inherited` is the style cascaded from its parents
+    fn element(&mut self, el: &XmlElement, t: Transform, inherited: &Style) -> Box<dyn ToSvg> {
+        let element = match self.shape(el, t, inherited) {
+            Some(shape) => shape,
+            None => Box::new(self.raw(el, t, inherited)) as Box<dyn ToSvg>,
+        };
+        if let Some(id) = el.attribute("id") {
+            let untouched = element.is::<RawElement>() && !TRANSFORMABLE.contains(&el.name.as_str());
+            self.placements.entry(id.to_string()).or_insert(if untouched { Transform::IDENTITY } else { t });
+        }
+        element
+    }
+
+    /// Imports an Inkscape layer, or returns `None` when the element is not one visualife can represent
+    fn layer(&mut self, el: &XmlElement, order: i32, t: Transform, inherited: &Style) -> Option<Layer> {
+        if !is_layer(el) { return None; }
+        let known = ["id", "style", "transform", "inkscape:groupmode", "inkscape:label"];
+        let attributes: Vec<(String, String)> = el.attributes.iter()
+            .filter(|(name, _)| !known.contains(&name.as_str()) && !PRESENTATION.contains(&name.as_str())).cloned().collect();
+        // ---------- Inkscape hides and shows layers with the display property, which Style doesn't have
+        let mut visible = true;
+        let declarations: Vec<&str> = el.attribute("style").unwrap_or("").split(';').filter(|d| {
+            match d.split_once(':') {
+                Some((property, value)) if property.trim() == "display" => { visible = value.trim() != "none"; false }
+                _ => true,
+            }
+        }).collect();
+        let style = style_of(el, &declarations.join(";"))?;
+        if let Some(id) = el.attribute("id") { self.placements.entry(id.to_string()).or_insert(t); }
+        let t = own_transform(el)?.then(&t);
+        let cascaded = inherited.cascade(&style);
+        if bound_to_user_space(&t, &cascaded, &attributes) { return None; }
+        let name = match el.attribute("inkscape:label") {
+            Some(label) => label.to_string(),
+            None => self.id(el),
+        };
+        let mut layer = Layer::new(&name, order);
+        // ---------- the id of the group is kept apart from its label, so both are written back as they were
+        match el.attribute("id") {
+            Some(id) => layer.id = id.to_string(),
+            None if !self.ids.insert(layer.id.clone()) => layer.id = self.id(el),
+            None => {}
+        }
+        layer.visible = visible;
+        layer.attributes = attributes;
+        layer.elements = self.children(el, t, &cascaded);
+        layer.style = style;
+        Some(layer)
+    }
+
+    fn children(&mut self, el: &XmlElement, t: Transform, inherited: &Style) -> Vec<Box<dyn ToSvg>> {
+        el.children.iter().filter_map(|node| match node {
+            XmlNode::Element(child) => Some(self.element(child, t, inherited)),
+            XmlNode::Text(_) => None,
+        }).collect()
+    }
+
+    /// Imports a ``<circle>``, ``<rect>``, ``<path>`` or ``<g>`` element, unless its geometry can't be read
+    /// or can't be moved by `t`; attributes and style properties visualife has no field for are kept as they are
+    fn shape(&mut self, el: &XmlElement, t: Transform, inherited: &Style) -> Option<Box<dyn ToSvg>> {
+        let geometry: &[&str] = match el.name.as_str() {
+            "circle" => &["cx", "cy", "r"],
+            "rect" => &["x", "y", "width", "height", "rx", "ry"],
+            "path" => &["d"],
+            "g" => &[],
+            _ => return None,
+        };
+        let known = |name: &str| COMMON.contains(&name) || PRESENTATION.contains(&name) || geometry.contains(&name);
+        let attributes: Vec<(String, String)> = el.attributes.iter().filter(|(name, _)| !known(name)).cloned().collect();
+        let mut style = style_of(el, el.attribute("style").unwrap_or(""))?;
+        let own = own_transform(el)?;
+        let t = own.then(&t);
+        let cascaded = inherited.cascade(&style);
+        if bound_to_user_space(&t, &cascaded, &attributes) { return None; }
+
+        let class = el.attribute("class").map(str::to_string);
+        if el.name == "g" {
+            let mut group = Group::new(&self.id(el));
+            group.class = class;
+            group.attributes = attributes;
+            group.style = style;
+            group.children = self.children(el, t, &cascaded);
+            return Some(Box::new(group));
+        }
+        let stroke_width = cascaded.stroke_width.unwrap_or(1.0);
+
+        // ---------- the width of the stroke scales with the shape; a stroke that gets distorted is approximated
+        let scale = t.determinant().abs().sqrt();
+        if (scale - 1.0).abs() > 1e-6 { style.stroke_width = Some(stroke_width * scale); }
+        self.leaf(el, own, t, style, class, attributes)
+    }
+
+    /// Imports a ``<circle>``, ``<rect>`` or ``<path>`` element whose own transform is `own`, placed by `t`
+    /// into the coordinates of the drawing; kept apart from [`shape()`](Importer::shape) so that the frames
+    /// of the recursion through nested groups stay small
+    fn leaf(&mut self, el: &XmlElement, own: Transform, t: Transform, style: Style, class: Option<String>,
+            attributes: Vec<(String, String)>) -> Option<Box<dyn ToSvg>> {
+        let number = |name: &str, default: Option<f32>| el.attribute(name).map_or(default, length);
+        let scale = t.determinant().abs().sqrt();
+        let shape: Box<dyn ToSvg> = match el.name.as_str() {
+            "circle" => {
+                let (cx, cy, r) = (number("cx", Some(0.0))?, number("cy", Some(0.0))?, number("r", None)?);
+                if r < 0.0 { return None; }
+                let mut circle = Circle::new(&self.id(el), cx, cy, r);
+                circle.class = class;
+                circle.attributes = attributes;
+                circle.style = style;
+                if t == Transform::IDENTITY {
+                    Box::new(circle)
+                } else if is_conformal(&t) {
+                    let center = t.apply(circle.center());
+                    (circle.cx, circle.cy, circle.radius) = (center.x, center.y, r * scale);
+                    Box::new(circle)
+                } else {
+                    Box::new(circle_path(&circle).transform(&t))
+                }
+            }
+            "rect" => {
+                let (w, h) = (number("width", None)?, number("height", None)?);
+                if w < 0.0 || h < 0.0 { return None; }
+                let mut rect = Rect::new(&self.id(el), number("x", Some(0.0))?, number("y", Some(0.0))?, w, h);
+                rect.rx = el.attribute("rx").map(length).map_or(Some(None), |rx| rx.map(Some))?;
+                rect.ry = el.attribute("ry").map(length).map_or(Some(None), |ry| ry.map(Some))?;
+                rect.class = class;
+                rect.attributes = attributes;
+                rect.style = style;
+                if t == Transform::IDENTITY { return Some(Box::new(rect)); }
+                // ---------- the rotation a rectangle writes itself is read back exactly
+                if own == t {
+                    if let Some(angle) = el.attribute("transform").and_then(|s| rotation_around(s, rect.center())) {
+                        rect.angle = angle;
+                        return Some(Box::new(rect));
+                    }
+                }
+                if is_conformal(&t) && t.determinant() > 0.0 {
+                    let center = t.apply(rect.center());
+                    (rect.width, rect.height) = (w * scale, h * scale);
+                    (rect.x, rect.y) = (center.x - rect.width / 2.0, center.y - rect.height / 2.0);
+                    rect.rx = rect.rx.map(|rx| rx * scale);
+                    rect.ry = rect.ry.map(|ry| ry * scale);
+                    rect.angle = Degrees(t.b.atan2(t.a).to_degrees());
+                    Box::new(rect)
+                } else {
+                    Box::new(rect_path(&rect).transform(&t))
+                }
+            }
+            _ => {
+                let mut path = Path::from_str(&self.id(el), el.attribute("d").unwrap_or("")).ok()?;
+                path.class = class;
+                path.attributes = attributes;
+                path.style = style;
+                if t == Transform::IDENTITY { Box::new(path) } else { Box::new(path.transform(&t)) }
+            }
+        };
+        Some(shape)
+    }
+
+    /// Imports an element as it is; the transform `t` is prepended to its own one
+    fn raw(&mut self, el: &XmlElement, t: Transform, inherited: &Style) -> RawElement {
+        let mut raw = RawElement::new(&el.name, el.attributes.clone());
+        if t != Transform::IDENTITY && TRANSFORMABLE.contains(&el.name.as_str()) {
+            match raw.attributes.iter_mut().find(|(name, _)| name == "transform") {
+                Some((_, own)) => *own = format!("{} {}", t, own),
+                None => raw.attributes.push(("transform".to_string(), t.to_string())),
+            }
+        }
+        let mut inherited = inherited.clone();
+        if let Some(stroke_width) = raw_stroke_width(el) { inherited.stroke_width = Some(stroke_width); }
+        let keep_whitespace = TEXT_ELEMENTS.contains(&el.name.as_str());
+        for node in &el.children {
+            match node {
+                XmlNode::Element(child) => raw.children.push(self.element(child, Transform::IDENTITY, &inherited)),
+                XmlNode::Text(text) if keep_whitespace || !text.trim().is_empty() => raw.children.push(Box::new(RawText(text.clone()))),
+                XmlNode::Text(_) => {}
+            }
+        }
+        raw
+    }
+}
+
+/// Changes the transformation of a ``<use>`` element so that it draws the element it refers to where the document did,
+/// although the transformation `placements` tells of was folded into the coordinates of that element
+fn place_use(raw: &mut RawElement, placements: &HashMap<String, Transform>) {
+    let href = raw.attribute("href").or(raw.attribute("xlink:href"));
+    let Some(&placed) = href.and_then(|href| href.strip_prefix('#')).and_then(|id| placements.get(id)) else { return };
+    if placed == Transform::IDENTITY { return; }
+    let number = |name: &str| raw.attribute(name).map_or(Some(0.0), length);
+    let (Some(x), Some(y), Some(undo)) = (number("x"), number("y"), placed.inverse()) else { return };
+    let Some(own) = raw.attribute("transform").map_or(Some(Transform::IDENTITY), |t| Transform::from_str(t).ok()) else { return };
+    // ---------- the element is drawn moved by x and y, so the folded transformation is undone around that offset
+    let t = Transform::translate(-x, -y).then(&undo).then(&Transform::translate(x, y)).then(&own);
+    let size = 1.0 + placed.e.abs().max(placed.f.abs()).max(x.abs()).max(y.abs());
+    let identity = [t.a - 1.0, t.b, t.c, t.d - 1.0].iter().all(|v| v.abs() <= 1e-5) && t.e.abs().max(t.f.abs()) <= 1e-5 * size;
+    let at = raw.attributes.iter().position(|(name, _)| name == "transform");
+    match at {
+        Some(at) if identity => { raw.attributes.remove(at); }
+        Some(at) => raw.attributes[at].1 = t.to_string(),
+        None if identity => {}
+        None => raw.attributes.push(("transform".to_string(), t.to_string())),
+    }
+}
+
+fn is_layer(el: &XmlElement) -> bool { el.name == "g" && el.attribute("inkscape:groupmode") == Some("layer") }
+
+/// Style given by the presentation attributes of an element, overridden by the `declarations` of its ``style``
+fn style_of(el: &XmlElement, declarations: &str) -> Option<Style> {
+    let mut all: Vec<String> = PRESENTATION.iter()
+        .filter_map(|name| el.attribute(name).map(|value| format!("{}:{}", name, value))).collect();
+    all.push(declarations.to_string());
+    Style::parse_keeping_unknown(&all.join(";")).ok()
+}
+
+/// Tells whether moving the geometry of an element by `t` would change how it looks, because it or the style
+/// it inherits refers to its user space: paint servers, clip paths, masks and filters are placed there,
+/// and dashes don't scale along
+fn bound_to_user_space(t: &Transform, style: &Style, attributes: &[(String, String)]) -> bool {
+    if *t == Transform::IDENTITY { return false; }
+    let is_url = |value: &str| value.to_ascii_lowercase().contains("url(");
+    let paint_url = [&style.fill, &style.stroke].into_iter().flatten().any(|p| matches!(p, Paint::Other(s) if is_url(s)));
+    let scaled = (t.determinant().abs().sqrt() - 1.0).abs() > 1e-6;
+    paint_url || style.other.iter().chain(attributes).any(|(name, value)| {
+        is_url(value) || (scaled && (name == "stroke-dasharray" || name == "stroke-dashoffset"))
+    })
+}
+
+/// Stroke width set by an element whose style is not parsed as a whole
+fn raw_stroke_width(el: &XmlElement) -> Option<f32> {
+    let declared = el.attribute("style").and_then(|style| style.split(';').rev()
+        .filter_map(|d| d.split_once(':')).find(|(property, _)| property.trim() == "stroke-width").map(|(_, value)| value));
+    declared.or(el.attribute("stroke-width")).and_then(length)
+}
+
+fn own_transform(el: &XmlElement) -> Option<Transform> {
+    el.attribute("transform").map_or(Some(Transform::IDENTITY), |t| Transform::from_str(t).ok())
+}
+
+/// Tells whether a transformation keeps angles, i.e. turns circles into circles
+fn is_conformal(t: &Transform) -> bool {
+    let (n1, n2) = (t.a.hypot(t.b), t.c.hypot(t.d));
+    (n1 - n2).abs() <= 1e-5 * n1.max(n2) && (t.a * t.c + t.b * t.d).abs() <= 1e-5 * n1 * n2
+}
+
+/// The angle of a ``rotate(angle cx cy)`` transform around the given center
+fn rotation_around(transform: &str, center: Point) -> Option<Degrees> {
+    let args = transform.trim().strip_prefix("rotate(")?.strip_suffix(')')?;
+    let args: Vec<f32> = args.split(|c: char| c.is_whitespace() || c == ',').filter(|a| !a.is_empty())
+        .map(|a| a.parse().ok()).collect::<Option<_>>()?;
+    match args.as_slice() {
+        &[angle, cx, cy] if Point::new(cx, cy).distance(center) <= 1e-4 * (1.0 + center.to_vector().length()) => Some(Degrees(angle)),
+        _ => None,
+    }
+}
+
+/// A length in pixels, given as a number with an optional absolute unit
+fn length(value: &str) -> Option<f32> {
+    const UNITS: [(&str, f32); 6] = [("px", 1.0), ("pt", 4.0 / 3.0), ("pc", 16.0), ("mm", 96.0 / 25.4), ("cm", 96.0 / 2.54), ("in", 96.0)];
+    let value = value.trim();
+    let (number, factor) = UNITS.iter().find_map(|&(unit, factor)| value.strip_suffix(unit).map(|n| (n, factor)))
+        .unwrap_or((value, 1.0));
+    number.trim_end().parse::<f32>().ok().filter(|n| n.is_finite()).map(|n| n * factor)
+}
+
+/// Size of the document and the transformation from its ``viewBox`` to that size
+fn viewport(root: &XmlElement) -> (f32, f32, Transform) {
+    let (width, height) = (root.attribute("width").and_then(length), root.attribute("height").and_then(length));
+    let view_box: Option<Vec<f32>> = root.attribute("viewBox").and_then(|v| v.split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|n| !n.is_empty()).map(|n| n.parse::<f32>().ok()).collect());
+    let Some(&[x, y, w, h]) = view_box.as_deref().filter(|vb| vb.len() == 4 && vb[2] > 0.0 && vb[3] > 0.0) else {
+        // ---------- the default size of a replaced element in CSS
+        return (width.unwrap_or(300.0), height.unwrap_or(150.0), Transform::IDENTITY);
+    };
+    let (width, height) = (width.unwrap_or(w), height.unwrap_or(h));
+    let (sx, sy) = (width / w, height / h);
+    let mut words = root.attribute("preserveAspectRatio").unwrap_or("").split_whitespace().filter(|w| *w != "defer");
+    let align = words.next().unwrap_or("xMidYMid");
+    if align == "none" { return (width, height, Transform::matrix(sx, 0.0, 0.0, sy, -x * sx, -y * sy)); }
+    let s = if words.next() == Some("slice") { sx.max(sy) } else { sx.min(sy) };
+    let fx = match align.get(..4) { Some("xMin") => 0.0, Some("xMax") => 1.0, _ => 0.5 };
+    let fy = match align.get(4..) { Some("YMin") => 0.0, Some("YMax") => 1.0, _ => 0.5 };
+    (width, height, Transform::matrix(s, 0.0, 0.0, s, fx * (width - w * s) - x * s, fy * (height - h * s) - y * s))
+}
+
+/// Outline of a circle, as two half circles
+fn circle_path(c: &Circle) -> Path {
+    let mut p = Path::new(&c.id).move_to(c.cx - c.radius, c.cy)
+        .elliptical_arc_to(c.radius, c.radius, 0.0, false, true, c.cx + c.radius, c.cy)
+        .elliptical_arc_to(c.radius, c.radius, 0.0, false, true, c.cx - c.radius, c.cy).close();
+    (p.style, p.class, p.attributes) = (c.style.clone(), c.class.clone(), c.attributes.clone());
+    p
+}
+
+/// Outline of a rectangle, with its rounded corners
+fn rect_path(r: &Rect) -> Path {
+    // ---------- a missing radius takes the value of the other one; both are limited to half of the side
+    let rx = r.rx.or(r.ry).unwrap_or(0.0).clamp(0.0, r.width / 2.0);
+    let ry = r.ry.or(r.rx).unwrap_or(0.0).clamp(0.0, r.height / 2.0);
+    let (x0, y0, x1, y1) = (r.x, r.y, r.x + r.width, r.y + r.height);
+    let mut p = Path::new(&r.id).move_to(x0 + rx, y0).horizontal_to(x1 - rx);
+    let corner = |p: Path, x: f32, y: f32| if rx > 0.0 && ry > 0.0 { p.elliptical_arc_to(rx, ry, 0.0, false, true, x, y) } else { p };
+    p = corner(p, x1, y0 + ry).vertical_to(y1 - ry);
+    p = corner(p, x1 - rx, y1).horizontal_to(x0 + rx);
+    p = corner(p, x0, y1 - ry).vertical_to(y0 + ry);
+    p = corner(p, x0 + rx, y0).close();
+    (p.style, p.class, p.attributes) = (r.style.clone(), r.class.clone(), r.attributes.clone());
+    p
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use crate::import::{length, rect_path, viewport};
+    use crate::import::xml::{parse_xml, MAX_DEPTH};
+    use crate::geometry::{BBox, Degrees, Point};
+    use crate::shapes::{Circle, Group, Path, RawElement, Rect, Transform};
+    use crate::{SvgDrawing, SvgImportError, ToSvg, ANNOTATIONS_LAYER};
+
+    #[test]
+    fn lengths_and_viewports() {
+        assert_eq!(length("10"), Some(10.0));
+        assert_eq!(length(" 1.5e1px"), Some(15.0));
+        assert_eq!(length("1in"), Some(96.0));
+        assert_eq!(length("50%"), None);
+        let vp = |svg: &str| viewport(&parse_xml(svg).unwrap());
+        assert_eq!(vp("<svg/>"), (300.0, 150.0, Transform::IDENTITY));
+        assert_eq!(vp(r#"<svg viewBox="10 10 50 20"/>"#), (50.0, 20.0, Transform::translate(-10.0, -10.0)));
+        // ---------- the view box is scaled to fit and centered
+        assert_eq!(vp(r#"<svg width="100" height="100" viewBox="0 0 50 20"/>"#), (100.0, 100.0, Transform::matrix(2.0, 0.0, 0.0, 2.0, 0.0, 30.0)));
+        assert_eq!(vp(r#"<svg width="100" height="100" viewBox="0 0 50 20" preserveAspectRatio="xMaxYMax slice"/>"#).2,
+                   Transform::matrix(5.0, 0.0, 0.0, 5.0, -150.0, 0.0));
+        assert_eq!(vp(r#"<svg width="100" height="100" viewBox="0,0,50,20" preserveAspectRatio="none"/>"#).2, Transform::scale(2.0, 5.0));
+    }
+
+    #[test]
+    fn rounded_rect_outline() {
+        let mut r = Rect::new("r", 0.0, 0.0, 10.0, 4.0);
+        r.rx = Some(3.0);
+        let p = rect_path(&r);
+        assert_eq!(p.to_svg(), r#"<path id="r" d="M 3 0 H 7 A 3 2 0 0 1 10 2 V 2 A 3 2 0 0 1 7 4 H 3 A 3 2 0 0 1 0 2 V 2 A 3 2 0 0 1 3 0 Z" />"#);
+        assert_eq!(p.bbox(), Some(BBox::new(Point::new(0.0, 0.0), Point::new(10.0, 4.0))));
+    }
+
+    #[test]
+    fn read_back_own_documents() {
+        let mut drawing = SvgDrawing::new(120.0, 80.0);
+        let mut group = Group::new("g");
+        group.class = Some("nodes".to_string());
+        group.style.try_set_stroke("black").unwrap();
+        let mut rect = Rect::new("r", 10.0, 10.0, 30.0, 20.0);
+        rect.angle = Degrees(30.0);
+        rect.set_rx(2.0);
+        group.add_element(Box::new(rect));
+        group.add_element(Box::new(Circle::new("c", 60.0, 40.0, 5.5)));
+        drawing.add_element(Box::new(group));
+        let mut path = Path::from_str("p", "M 0 0 L 10 5 Q 20 0 30 5 Z").unwrap();
+        path.style.try_set_fill("none").unwrap();
+        drawing.add_to_layer(ANNOTATIONS_LAYER, Box::new(path));
+        drawing.add_to_layer("hidden", Box::new(Circle::new("h", 1.0, 1.0, 1.0)));
+        let hidden = drawing.layer_mut("hidden").unwrap();
+        hidden.visible = false;
+        hidden.style.try_set_fill("red").unwrap();
+
+        let svg = drawing.to_svg();
+        let imported = SvgDrawing::from_svg(&svg).unwrap();
+        assert_eq!(imported.to_svg(), svg);
+        assert!(imported.get_by_id("r").unwrap().is::<Rect>());
+        assert!(!imported.layer("hidden").unwrap().visible);
+    }
+
+    #[test]
+    fn inherit_root_style() {
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" width="200" height="200" viewBox="0 0 100 100"
+     class="dark" fill="red" stroke-width="3" style="stroke:blue;font-family:serif" data-x="1">
+  <circle id="c" cx="50" cy="50" r="10"/>
+  <text x="5" y="5">T</text>
+</svg>"##;
+        let drawing = SvgDrawing::from_svg(svg).unwrap();
+        assert_eq!(drawing.computed_style("c").unwrap().to_string(), "fill:#F00;stroke:#00F;stroke-width:6;font-family:serif;");
+        assert_eq!(drawing.select("svg.dark circle").unwrap().len(), 1);
+        let out = drawing.to_svg();
+        assert!(out.starts_with(r#"<svg width="200" height="200" xmlns="http://www.w3.org/2000/svg" xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape" class="dark" data-x="1" style="fill:#F00;stroke:#00F;stroke-width:3;font-family:serif;">"#));
+        assert_eq!(SvgDrawing::from_svg(&out).unwrap().to_svg(), out);
+    }
+
+    #[test]
+    fn place_used_elements() {
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" viewBox="0 0 100 100">
+  <use id="before" href="#c" x="10"/>
+  <g transform="translate(50 0)">
+    <circle id="c" cx="10" cy="10" r="5"/>
+    <use id="same" href="#c" x="10"/>
+  </g>
+  <defs><circle id="d" r="1"/></defs>
+  <g transform="translate(5 5)"><use id="defined" xlink:href="#d" transform="scale(2)"/></g>
+</svg>"##;
+        let drawing = SvgDrawing::from_svg(svg).unwrap();
+        assert_eq!(drawing.get_by_id("c").and_then(|el| el.downcast_ref::<Circle>()).unwrap().cx, 60.0);
+        // ---------- a <use> next to its element needs no transformation of its own: the circle is drawn at 60 + 10
+        let attribute = |id: &str, name: &str| drawing.get_by_id(id).and_then(|el| el.downcast_ref::<RawElement>())
+            .and_then(|raw| raw.attribute(name).map(str::to_string));
+        assert_eq!(attribute("same", "transform"), None);
+        assert_eq!(attribute("before", "transform"), Some("matrix(1 0 0 1 -50 0)".to_string()));
+        // ---------- elements that are not moved, such as those in <defs>, are used as they were
+        assert_eq!(attribute("defined", "transform"), Some("matrix(1 0 0 1 5 5) scale(2)".to_string()));
+    }
+
+    #[test]
+    fn keep_order_of_root_elements() {
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape">
+  <rect id="under" width="10" height="10"/>
+  <g id="l" inkscape:groupmode="layer" inkscape:label="data"><rect id="in" width="10" height="10"/></g>
+  <rect id="over" width="10" height="10"/>
+  <circle id="over2" r="5"/>
+  <g inkscape:groupmode="layer" inkscape:label="top"><rect id="top" width="10" height="10"/></g>
+</svg>"##;
+        let drawing = SvgDrawing::from_svg(svg).unwrap();
+        let names: Vec<&str> = drawing.layers().map(|l| l.name.as_str()).collect();
+        assert_eq!(names, vec!["data 2", "data", "data 3", "top"]);
+        let svg = drawing.to_svg();
+        let at = |id: &str| svg.find(&format!("id=\"{}\"", id)).unwrap();
+        assert!(at("under") < at("in") && at("in") < at("over") && at("over") < at("over2") && at("over2") < at("top"));
+    }
+
+    #[test]
+    fn keep_unknown_attributes() {
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg">
+  <g transform="translate(5 0)">
+    <circle id="a" r="1" data-x="1" style="stroke-linecap:round" clip-path="url(#clip)"/>
+    <path id="b" d="M 0 0 H 10" style="stroke-dasharray:2 1"/>
+  </g>
+  <g transform="scale(2)">
+    <circle id="c" r="1" data-x="1" style="stroke-linecap:round"/>
+    <path id="d" d="M 0 0 H 10" style="stroke-dasharray:2 1"/>
+  </g>
+</svg>"##;
+        let drawing = SvgDrawing::from_svg(svg).unwrap();
+        // ---------- clip paths and dashes live in user space, so they keep their elements from being moved
+        assert!(drawing.get_by_id("a").unwrap().is::<RawElement>());
+        assert!(drawing.get_by_id("b").unwrap().is::<Path>());
+        assert!(drawing.get_by_id("d").unwrap().is::<RawElement>());
+        let c = drawing.get_by_id("c").and_then(|el| el.downcast_ref::<Circle>()).unwrap();
+        assert_eq!(c.attributes, vec![("data-x".to_string(), "1".to_string())]);
+        assert_eq!(c.to_svg(), r#"<circle id="c" cx="0" cy="0" r="2" data-x="1" style="stroke-width:2;stroke-linecap:round;" />"#);
+    }
+
+    #[test]
+    fn deeply_nested_documents() {
+        let nested = |tag: &str, n: usize| {
+            format!("<svg>{}<circle r=\"1\"/>{}</svg>", format!("<{}>", tag).repeat(n), format!("</{}>", tag).repeat(n))
+        };
+        let drawing = SvgDrawing::from_svg(&nested("g", MAX_DEPTH - 2)).unwrap();
+        assert_eq!(drawing.descendants().filter(|el| el.is::<Group>()).count(), MAX_DEPTH - 2);
+        assert!(drawing.to_svg().contains("<circle id=\"circle1\""));
+        let drawing = SvgDrawing::from_svg(&nested("a", MAX_DEPTH - 2)).unwrap();
+        assert!(drawing.to_svg().contains("<circle id=\"circle1\""));
+        let Err(error) = SvgDrawing::from_svg(&nested("g", 100_000)) else { panic!() };
+        assert!(matches!(error, SvgImportError::TooDeep { .. }));
+        assert_eq!(error.to_string(), format!("the element at byte {} is nested deeper than 256 levels", error.offset()));
+    }
+
+    #[test]
+    fn illustrator_entities() {
+        let svg = r##"<?xml version="1.0" encoding="utf-8"?>
+<!-- Generator: Adobe Illustrator 16.0.0, SVG Export Plug-In . SVG Version: 6.00 Build 0)  -->
+<!DOCTYPE svg PUBLIC "-//W3C//DTD SVG 1.1//EN" "http://www.w3.org/Graphics/SVG/1.1/DTD/svg11.dtd" [
+	<!ENTITY ns_extend "http://ns.adobe.com/Extensibility/1.0/">
+	<!ENTITY ns_svg "http://www.w3.org/2000/svg">
+	<!ENTITY ns_xlink "http://www.w3.org/1999/xlink">
+]>
+<svg version="1.1" xmlns:x="&ns_extend;" xmlns="&ns_svg;" xmlns:xlink="&ns_xlink;" width="100px" height="50px" viewBox="0 0 100 50">
+<circle fill="#FF0000" cx="50" cy="25" r="20"/>
+</svg>"##;
+        let drawing = SvgDrawing::from_svg(svg).unwrap();
+        let out = drawing.to_svg();
+        assert!(out.contains(r#"xmlns:x="http://ns.adobe.com/Extensibility/1.0/""#));
+        assert!(out.contains(r##"<circle id="circle1" cx="50" cy="25" r="20" style="fill:#F00;" />"##), "{}", out);
+    }
+
+    #[test]
+    fn flatten_transforms() {
+        let svg = r##"<svg xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" width="100mm" height="50mm" viewBox="0 0 200 100">
+  <g transform="translate(10 20)" stroke-width="2">
+    <circle cx="5" cy="5" r="5"/>
+    <circle id="oval" cx="0" cy="0" r="1" transform="scale(2 1)"/>
+    <rect id="r" width="4" height="2" transform="rotate(90)"/>
+    <use xlink:href="#oval" x="3"/>
+  </g>
+</svg>"##;
+        let drawing = SvgDrawing::from_svg(svg).unwrap();
+        let scale = 96.0 / 25.4 / 2.0;
+        let circle = drawing.get_by_id("circle1").and_then(|el| el.downcast_ref::<Circle>()).unwrap();
+        assert!((circle.cx - 15.0 * scale).abs() < 1e-3 && (circle.radius - 5.0 * scale).abs() < 1e-3);
+        assert!((circle.style.stroke_width.unwrap() - 2.0 * scale).abs() < 1e-3);
+        // ---------- a circle scaled unevenly becomes a path
+        let oval = drawing.get_by_id("oval").unwrap();
+        assert!(oval.is::<Path>());
+        let bbox = oval.bbox().unwrap();
+        assert!((bbox.width() - 4.0 * scale).abs() < 1e-3 && (bbox.height() - 2.0 * scale).abs() < 1e-3);
+        let rect = drawing.get_by_id("r").and_then(|el| el.downcast_ref::<Rect>()).unwrap();
+        assert!((rect.angle.0 - 90.0).abs() < 1e-3);
+        let bbox = rect.bbox().unwrap();
+        assert!((bbox.min.x - 8.0 * scale).abs() < 1e-3 && (bbox.max.y - 24.0 * scale).abs() < 1e-3);
+        // ---------- other elements keep their attributes; a <use> draws its element where the document did
+        let used = drawing.select("g > use").unwrap()[0].downcast_ref::<RawElement>().unwrap();
+        assert_eq!(used.attribute("xlink:href"), Some("#oval"));
+        let t = Transform::translate(10.0, 20.0).then(&Transform::scale(scale, scale));
+        let drawn = Transform::from_str(used.attribute("transform").unwrap()).unwrap();
+        let (p, offset) = (Point::new(1.0, 2.0), Transform::translate(3.0, 0.0));
+        let (expected, found) = (offset.then(&t).apply(p), t.then(&offset).then(&drawn).apply(p));
+        assert!(expected.distance(found) < 1e-4);
+        assert!(drawing.to_svg().contains(r#"xmlns:xlink="http://www.w3.org/1999/xlink">"#));
+        assert_eq!(SvgDrawing::from_svg("<html/>").err(), Some(SvgImportError::NotSvg { offset: 0, found: "html".to_string() }));
+    }
+}
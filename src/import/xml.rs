@@ -0,0 +1,345 @@
+//! A small XML parser, sufficient for SVG documents.
+//!
+//! Comments, processing instructions and the document type declaration are skipped, except for the
+//! internal entities it declares, which are substituted like the predefined ones; CDATA sections
+//! become text. Namespaces are not resolved: names are kept with their prefixes.
+use crate::import::SvgImportError;
+
+/// Deepest nesting of elements accepted, so that deeply nested documents can't exhaust the stack
+pub(crate) const MAX_DEPTH: usize = 256;
+
+/// Most text, in bytes, that references to declared entities may expand to in a document
+pub(crate) const MAX_EXPANSION: usize = 1 << 20;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum XmlNode {
+    Element(XmlElement),
+    Text(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct XmlElement {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<XmlNode>,
+    /// byte offset of the ``<`` that opens this element
+    pub offset: usize,
+}
+
+impl XmlElement {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+}
+
+/// Parses an XML document and returns its root element
+pub(crate) fn parse_xml(text: &str) -> Result<XmlElement, SvgImportError> {
+    let mut parser = Parser { text, pos: 0, entities: vec![], expanded: 0 };
+    if text.starts_with('\u{feff}') { parser.pos = 3; }
+    parser.skip_misc()?;
+    if !parser.rest().starts_with('<') { return Err(parser.unexpected()); }
+    let root = parser.element(1)?;
+    parser.skip_misc()?;
+    if parser.pos < text.len() { return Err(parser.unexpected()); }
+    Ok(root)
+}
+
+struct Parser<'a> {
+    text: &'a str,
+    pos: usize,
+    /// names and replacement texts of the entities declared by the document type declaration
+    entities: Vec<(String, String)>,
+    /// bytes of text that entity references expanded to so far
+    expanded: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str { &self.text[self.pos..] }
+
+    fn unexpected(&self) -> SvgImportError {
+        match self.rest().chars().next() {
+            Some(found) => SvgImportError::UnexpectedCharacter { offset: self.pos, found },
+            None => SvgImportError::UnexpectedEnd { offset: self.pos },
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.pos = self.text.len() - self.rest().trim_start().len();
+    }
+
+    /// Moves past the next occurrence of `end`
+    fn skip_past(&mut self, end: &str) -> Result<(), SvgImportError> {
+        match self.rest().find(end) {
+            Some(at) => { self.pos += at + end.len(); Ok(()) }
+            None => Err(SvgImportError::UnexpectedEnd { offset: self.text.len() }),
+        }
+    }
+
+    /// Moves past the first of `ends` found outside quotes and returns it
+    fn skip_unquoted(&mut self, ends: &[char]) -> Result<char, SvgImportError> {
+        let mut quote = None;
+        let found = self.rest().char_indices().find(|&(_, c)| {
+            match quote {
+                Some(q) => if c == q { quote = None; },
+                None if c == '"' || c == '\'' => quote = Some(c),
+                None => return ends.contains(&c),
+            }
+            false
+        });
+        let Some((at, end)) = found else { return Err(SvgImportError::UnexpectedEnd { offset: self.text.len() }); };
+        self.pos += at + end.len_utf8();
+        Ok(end)
+    }
+
+    /// Skips whitespace, comments, processing instructions and the document type declaration
+    fn skip_misc(&mut self) -> Result<(), SvgImportError> {
+        loop {
+            self.skip_whitespace();
+            if self.rest().starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if self.rest().starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if self.rest().starts_with("<!DOCTYPE") {
+                self.doctype()?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Moves past the document type declaration, reading the entities declared in its internal subset
+    fn doctype(&mut self) -> Result<(), SvgImportError> {
+        if self.skip_unquoted(&['[', '>'])? == '>' { return Ok(()); }
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with(']') {
+                self.pos += 1;
+                self.skip_whitespace();
+                if !self.rest().starts_with('>') { return Err(self.unexpected()); }
+                self.pos += 1;
+                return Ok(());
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if rest.starts_with("<!ENTITY") {
+                self.entity_declaration()?;
+            } else if rest.starts_with("<!") {
+                self.skip_unquoted(&['>'])?;
+            } else if rest.starts_with('%') {
+                // ---------- a reference to a parameter entity, which are not read
+                self.skip_past(";")?;
+            } else {
+                return Err(self.unexpected());
+            }
+        }
+    }
+
+    /// Reads an ``<!ENTITY name "value">`` declaration. Parameter entities and external entities,
+    /// whose value is in another file, are skipped.
+    fn entity_declaration(&mut self) -> Result<(), SvgImportError> {
+        self.pos += "<!ENTITY".len();
+        self.skip_whitespace();
+        let parameter = self.rest().starts_with('%');
+        if parameter {
+            self.pos += 1;
+            self.skip_whitespace();
+        }
+        let name = self.name()?;
+        self.skip_whitespace();
+        if let Some(quote @ ('"' | '\'')) = self.rest().chars().next() {
+            self.pos += 1;
+            let Some(len) = self.rest().find(quote) else { return Err(SvgImportError::UnexpectedEnd { offset: self.text.len() }); };
+            let value = self.decode(&self.rest()[..len], self.pos)?;
+            self.pos += len + 1;
+            // ---------- the first declaration of an entity is binding
+            if !parameter && !self.entities.iter().any(|(n, _)| *n == name) {
+                self.entities.push((name, value));
+            }
+        }
+        self.skip_unquoted(&['>'])?;
+        Ok(())
+    }
+
+    fn name(&mut self) -> Result<String, SvgImportError> {
+        let len = self.rest().find(|c: char| c.is_whitespace() || "/>=<\"'".contains(c)).unwrap_or(self.rest().len());
+        if len == 0 { return Err(self.unexpected()); }
+        let name = self.rest()[..len].to_string();
+        self.pos += len;
+        Ok(name)
+    }
+
+    /// Parses an element at the given level of nesting, from its ``<`` to the end of its end tag
+    fn element(&mut self, depth: usize) -> Result<XmlElement, SvgImportError> {
+        let offset = self.pos;
+        if depth > MAX_DEPTH { return Err(SvgImportError::TooDeep { offset }); }
+        self.pos += 1;
+        let name = self.name()?;
+        let mut attributes = vec![];
+        loop {
+            let before = self.pos;
+            self.skip_whitespace();
+            if self.rest().starts_with("/>") {
+                self.pos += 2;
+                return Ok(XmlElement { name, attributes, children: vec![], offset });
+            }
+            if self.rest().starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+            // ---------- attributes must be separated from the name and from each other
+            if before == self.pos { return Err(self.unexpected()); }
+            let attr = self.name()?;
+            self.skip_whitespace();
+            if !self.rest().starts_with('=') { return Err(self.unexpected()); }
+            self.pos += 1;
+            self.skip_whitespace();
+            let quote = match self.rest().chars().next() {
+                Some(q @ ('"' | '\'')) => q,
+                _ => return Err(self.unexpected()),
+            };
+            self.pos += 1;
+            let Some(len) = self.rest().find(quote) else { return Err(SvgImportError::UnexpectedEnd { offset: self.text.len() }); };
+            let value = self.decode(&self.rest()[..len], self.pos)?;
+            self.pos += len + 1;
+            attributes.push((attr, value));
+        }
+
+        let mut children: Vec<XmlNode> = vec![];
+        let mut text = String::new();
+        loop {
+            let len = self.rest().find('<').unwrap_or(self.rest().len());
+            text.push_str(&self.decode(&self.rest()[..len], self.pos)?);
+            self.pos += len;
+            let rest = self.rest();
+            if rest.is_empty() {
+                return Err(SvgImportError::UnexpectedEnd { offset: self.pos });
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->")?;
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>")?;
+            } else if let Some(cdata) = rest.strip_prefix("<![CDATA[") {
+                let Some(end) = cdata.find("]]>") else { return Err(SvgImportError::UnexpectedEnd { offset: self.text.len() }); };
+                text.push_str(&cdata[..end]);
+                self.pos += "<![CDATA[".len() + end + "]]>".len();
+            } else if rest.starts_with("</") {
+                let end_offset = self.pos;
+                self.pos += 2;
+                let found = self.name()?;
+                self.skip_whitespace();
+                if found != name {
+                    return Err(SvgImportError::MismatchedEndTag { offset: end_offset, expected: name, found });
+                }
+                if !self.rest().starts_with('>') { return Err(self.unexpected()); }
+                self.pos += 1;
+                if !text.is_empty() { children.push(XmlNode::Text(text)); }
+                return Ok(XmlElement { name, attributes, children, offset });
+            } else {
+                if !text.is_empty() { children.push(XmlNode::Text(std::mem::take(&mut text))); }
+                children.push(XmlNode::Element(self.element(depth + 1)?));
+            }
+        }
+    }
+
+    /// Replaces character and entity references; `offset` is the position of `s` in the document
+    fn decode(&mut self, s: &str, offset: usize) -> Result<String, SvgImportError> {
+        if !s.contains('&') { return Ok(s.to_string()); }
+        let mut out = String::with_capacity(s.len());
+        let mut rest = s;
+        while let Some(at) = rest.find('&') {
+            out.push_str(&rest[..at]);
+            let reference_offset = offset + (s.len() - rest.len()) + at;
+            let Some(end) = rest[at..].find(';') else { return Err(SvgImportError::UnexpectedCharacter { offset: reference_offset, found: '&' }); };
+            let name = &rest[at + 1..at + end];
+            let c = match name {
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "amp" => Some('&'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => name.strip_prefix("#x").map(|hex| u32::from_str_radix(hex, 16))
+                    .or_else(|| name.strip_prefix('#').map(|dec| dec.parse::<u32>()))
+                    .and_then(|code| code.ok()).and_then(char::from_u32),
+            };
+            match c {
+                Some(c) => out.push(c),
+                None => {
+                    let Some((_, value)) = self.entities.iter().find(|(n, _)| n == name) else {
+                        return Err(SvgImportError::UnknownEntity { offset: reference_offset, name: name.to_string() });
+                    };
+                    // ---------- entities that expand to entities could otherwise blow up exponentially
+                    self.expanded += value.len();
+                    if self.expanded > MAX_EXPANSION { return Err(SvgImportError::TooLarge { offset: reference_offset }); }
+                    out.push_str(value);
+                }
+            }
+            rest = &rest[at + end + 1..];
+        }
+        out.push_str(rest);
+        Ok(out)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::import::xml::{parse_xml, XmlNode, MAX_DEPTH};
+    use crate::import::SvgImportError;
+
+    #[test]
+    fn parse_documents() {
+        let doc = r#"<?xml version="1.0"?>
+<!DOCTYPE svg PUBLIC "-//W3C//DTD SVG 1.1//EN" "x" [ <!ENTITY e "v"> ]>
+<!-- a comment -->
+<svg a='1' b = "x &amp; &#65;&#x42;"><g/><text>a &lt; b<![CDATA[ & <c>]]><!-- skipped --></text></svg>
+"#;
+        let root = parse_xml(doc).unwrap();
+        assert_eq!(root.name, "svg");
+        assert_eq!(root.attributes, vec![("a".to_string(), "1".to_string()), ("b".to_string(), "x & AB".to_string())]);
+        let XmlNode::Element(text) = &root.children[1] else { panic!() };
+        assert_eq!(text.children, vec![XmlNode::Text("a < b & <c>".to_string())]);
+        assert_eq!(text.offset, doc.find("<text").unwrap());
+    }
+
+    #[test]
+    fn report_errors() {
+        assert_eq!(parse_xml("<svg><g></svg>"), Err(SvgImportError::MismatchedEndTag { offset: 8, expected: "g".to_string(), found: "svg".to_string() }));
+        assert_eq!(parse_xml("<svg a=1/>"), Err(SvgImportError::UnexpectedCharacter { offset: 7, found: '1' }));
+        assert_eq!(parse_xml("<svg a='1'b='2'/>"), Err(SvgImportError::UnexpectedCharacter { offset: 10, found: 'b' }));
+        assert_eq!(parse_xml("<svg>&nbsp;</svg>"), Err(SvgImportError::UnknownEntity { offset: 5, name: "nbsp".to_string() }));
+        assert_eq!(parse_xml("<svg><g>"), Err(SvgImportError::UnexpectedEnd { offset: 8 }));
+        assert_eq!(parse_xml("<svg/><svg/>"), Err(SvgImportError::UnexpectedCharacter { offset: 6, found: '<' }));
+
+        let n = 100_000;
+        let nested = format!("<svg>{}{}</svg>", "<g>".repeat(n), "</g>".repeat(n));
+        assert_eq!(parse_xml(&nested), Err(SvgImportError::TooDeep { offset: 5 + 3 * MAX_DEPTH - 3 }));
+        let n = MAX_DEPTH - 1;
+        assert!(parse_xml(&format!("<svg>{}{}</svg>", "<g>".repeat(n), "</g>".repeat(n))).is_ok());
+    }
+
+    #[test]
+    fn declared_entities() {
+        let doc = r#"<!DOCTYPE svg [
+  <!ENTITY % local SYSTEM "local.dtd"> %local;
+  <!-- <!ENTITY skipped "x"> -->
+  <!ENTITY ns "http://www.w3.org/2000/svg">
+  <!ENTITY ext SYSTEM "ext.xml">
+  <!ATTLIST svg a CDATA "[>]">
+  <!ENTITY full '&ns;#&#65;'>
+  <!ENTITY ns "ignored">
+]>
+<svg xmlns="&ns;" a="&full;">&ns;</svg>"#;
+        let root = parse_xml(doc).unwrap();
+        assert_eq!(root.attribute("xmlns"), Some("http://www.w3.org/2000/svg"));
+        assert_eq!(root.attribute("a"), Some("http://www.w3.org/2000/svg#A"));
+        assert_eq!(root.children, vec![XmlNode::Text("http://www.w3.org/2000/svg".to_string())]);
+        assert_eq!(parse_xml(&doc.replace("&full;", "&ext;")), Err(SvgImportError::UnknownEntity {
+            offset: doc.find("&full;\">").unwrap(), name: "ext".to_string() }));
+
+        // ---------- entities defined by ten references to the previous one would expand to 10^9 copies of "lol"
+        let mut laughs = "<!DOCTYPE svg [<!ENTITY lol0 \"lol\">".to_string();
+        for k in 1..10 { laughs += &format!("<!ENTITY lol{} \"{}\">", k, format!("&lol{};", k - 1).repeat(10)); }
+        laughs += "]><svg>&lol9;</svg>";
+        assert!(matches!(parse_xml(&laughs), Err(SvgImportError::TooLarge { .. })));
+    }
+}
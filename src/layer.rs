@@ -0,0 +1,103 @@
+use crate::draw_svg::{escape, push_attributes};
+use crate::style::Style;
+use crate::ToSvg;
+
+/// Name of the bottom layer of a drawing, for backgrounds, grids and frames
+pub const BACKGROUND_LAYER: &str = "background";
+/// Name of the layer where [`SvgDrawing::add_element()`](crate::SvgDrawing::add_element) puts elements
+pub const DATA_LAYER: &str = "data";
+/// Name of the layer for labels, legends and other notes drawn over the data
+pub const ANNOTATIONS_LAYER: &str = "annotations";
+/// Name of the top layer, e.g. for highlights and selections
+pub const OVERLAY_LAYER: &str = "overlay";
+
+/// A named layer of a drawing.
+///
+/// Layers are drawn from the lowest `order` to the highest, whatever order their elements were added in.
+/// A layer is written as a group marked as an Inkscape layer, so it shows up in the layers panel of Inkscape;
+/// the name of the layer is its Inkscape label. A layer that is not visible is written as well, but hidden,
+/// and its elements are left out of the bounding box of the drawing and of a [`SpatialIndex`](crate::SpatialIndex).
+///
+/// # Example
+/// ```
+/// use visualife::shapes::Circle;
+/// use visualife::{Layer, ToSvg};
+/// let mut layer = Layer::new("data points", 5);
+/// layer.add_element(Box::new(Circle::new("c", 1.0, 1.0, 1.0)));
+/// layer.visible = false;
+/// assert_eq!(layer.to_svg(), "<g id=\"layer-data-points\" inkscape:groupmode=\"layer\" inkscape:label=\"data points\" style=\"display:none;\">\n\
+///     \t<circle id=\"c\" cx=\"1\" cy=\"1\" r=\"1\" />\n</g>");
+/// ```
+pub struct Layer {
+    pub name: String,
+    /// id of the group of this layer; by default ``layer-`` followed by the name, with characters
+    /// other than letters, digits, ``-`` and ``_`` replaced by ``-``, so it doesn't clash with the ids of elements.
+    /// [`SvgDrawing::add_layer()`](crate::SvgDrawing::add_layer) makes it unique among the layers of a drawing.
+    pub id: String,
+    pub visible: bool,
+    /// style of the group of this layer, inherited by its elements
+    pub style: Style,
+    pub elements: Vec<Box<dyn ToSvg>>,
+    /// attributes of the group of this layer that visualife has no field for, written as they are
+    pub attributes: Vec<(String, String)>,
+    order: i32,
+}
+
+impl Layer {
+    pub fn new(name: &str, order: i32) -> Self {
+        Layer { name: name.to_string(), id: layer_id(name), visible: true, style: Style::new(), elements: vec![], attributes: vec![], order }
+    }
+
+    /// Position of this layer in the stack; layers with higher orders are drawn over the lower ones.
+    ///
+    /// The order of a layer of a drawing is changed with [`SvgDrawing::set_layer_order()`](crate::SvgDrawing::set_layer_order).
+    pub fn order(&self) -> i32 { self.order }
+
+    pub(crate) fn set_order(&mut self, order: i32) { self.order = order; }
+
+    pub fn add_element(&mut self, el: Box<dyn ToSvg>) { self.elements.push(el); }
+}
+
+impl ToSvg for Layer {
+    fn id(&self) -> Option<&str> { Some(&self.id) }
+
+    fn tag(&self) -> Option<&str> { Some("g") }
+
+    fn style(&self) -> Option<&Style> { Some(&self.style) }
+
+    fn style_mut(&mut self) -> Option<&mut Style> { Some(&mut self.style) }
+
+    fn children(&self) -> &[Box<dyn ToSvg>] { &self.elements }
+
+    fn is_visible(&self) -> bool { self.visible }
+
+    fn children_mut(&mut self) -> &mut [Box<dyn ToSvg>] { &mut self.elements }
+
+    fn remove_child(&mut self, index: usize) -> Option<Box<dyn ToSvg>> {
+        (index < self.elements.len()).then(|| self.elements.remove(index))
+    }
+
+    fn to_svg(&self) -> String {
+        let mut svg_string = format!(r#"<g id="{}" inkscape:groupmode="layer" inkscape:label="{}""#,
+                                     escape(&self.id, true), escape(&self.name, true));
+        push_attributes(&self.attributes, &mut svg_string);
+        let display = if self.visible { "" } else { "display:none;" };
+        if !self.style.is_empty() || !self.visible {
+            svg_string.push_str(&format!(r#" style="{}{}""#, display, escape(&self.style.to_string(), true)));
+        }
+        svg_string.push_str(">\n");
+        for el in &self.elements {
+            svg_string.push('\t');
+            svg_string.push_str(&el.to_svg());
+            svg_string.push('\n');
+        }
+        svg_string.push_str("</g>");
+        svg_string
+    }
+}
+
+/// Default id of the group of a layer
+fn layer_id(name: &str) -> String {
+    let slug: String = name.chars().map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '-' }).collect();
+    format!("layer-{}", slug)
+}
@@ -1,9 +1,19 @@
 pub mod style;
 pub mod shapes;
+pub mod geometry;
 pub mod mindmap;
 mod draw_svg;
 mod svg_viewport;
+mod spatial_index;
+mod select;
+mod layer;
+mod import;
 pub mod colors;
+pub mod theme;
 
-pub use draw_svg::ToSvg;
-pub use svg_viewport::SvgDrawing;
\ No newline at end of file
+pub use draw_svg::{AsAny, ToSvg};
+pub use svg_viewport::SvgDrawing;
+pub use spatial_index::SpatialIndex;
+pub use select::{Descendants, Selector, SelectorParseError};
+pub use layer::*;
+pub use import::SvgImportError;
\ No newline at end of file
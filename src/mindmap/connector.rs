@@ -1,89 +1,76 @@
+use std::f32::consts::PI;
+use crate::geometry::{Point, Radians, Vector};
 use crate::mindmap::node::Node;
 use crate::mindmap::{cartesian_to_polar, FOOT_LENGTH_R_FRACTION, polar_to_cartesian};
 use crate::shapes::{Group, Path};
-use crate::ToSvg;
 
-pub(crate) fn connector(node_a: &Node, node_b: &Node, foot_angle_deg: f32, bar_width: f32) -> Group {
+pub(crate) fn connector(node_a: &Node, node_b: &Node, foot_angle: Radians, bar_width: f32) -> Group {
 
-    let foot_angle_rad = foot_angle_deg.to_radians();
-    // ---------- polar coordinates of node a as seen from node b ----------
-    let (r, theta_rad) = cartesian_to_polar(node_a.cx, node_a.cy, node_b.cx, node_b.cy);
-    let foot_a = foot_path(node_a.cx, node_a.cy, node_a.radius, theta_rad, foot_angle_rad, bar_width);
-    let foot_b = foot_path(node_b.cx, node_b.cy, node_b.radius, std::f32::consts::PI + theta_rad, foot_angle_rad, bar_width);
+    // ---------- polar coordinates of node b as seen from node a ----------
+    let (_, theta) = cartesian_to_polar(node_a.center, node_b.center);
+    let foot_a = foot_path(node_a.center, node_a.radius, theta, foot_angle, bar_width);
+    let foot_b = foot_path(node_b.center, node_b.radius, Radians(PI) + theta, foot_angle, bar_width);
 
-    let (x1a, y1a, x2a, y2a) = ankle_endpoints(node_a.cx, node_a.cy, node_a.radius, theta_rad, bar_width);
-    let (x1b, y1b, x2b, y2b) = ankle_endpoints(node_b.cx, node_b.cy, node_b.radius, std::f32::consts::PI + theta_rad, bar_width);
-    let edge= Path::new(&format!("b:{}:{}", &node_a.id, &node_b.id)).move_to(x1a, y1a).line_to(x2b, y2b).line_to(x1b, y1b).line_to(x2a, y2a).close();
+    let (p1a, p2a) = ankle_endpoints(node_a.center, node_a.radius, theta, bar_width);
+    let (p1b, p2b) = ankle_endpoints(node_b.center, node_b.radius, Radians(PI) + theta, bar_width);
+    let edge = Path::new(&format!("b:{}:{}", &node_a.id, &node_b.id))
+        .move_to(p1a.x, p1a.y).line_to(p2b.x, p2b.y).line_to(p1b.x, p1b.y).line_to(p2a.x, p2a.y).close();
 
     let mut g = Group::new(&format!("c:{}:{}", &node_a.id, &node_b.id));
     g.add_element(Box::new(foot_a));
     g.add_element(Box::new(foot_b));
     g.add_element(Box::new(edge));
 
-    return g;
+    g
 }
 
 /// computes the two points that define the top edge of a foot.
-///
-/// Returns the x, y coordinates of the two points.
-fn ankle_endpoints(cx: f32, cy: f32, r: f32, foot_direction_rad: f32, bar_width: f32) -> (f32, f32, f32, f32) {
+fn ankle_endpoints(center: Point, r: f32, foot_direction: Radians, bar_width: f32) -> (Point, Point) {
 
     // ---------- ankle midpoint
-    let (mx, my) = polar_to_cartesian(FOOT_LENGTH_R_FRACTION * r, foot_direction_rad, cx, cy);
+    let m = polar_to_cartesian(FOOT_LENGTH_R_FRACTION * r, foot_direction, center);
     // ---------- leg vector
-    let lx = foot_direction_rad.cos();
-    let ly = foot_direction_rad.sin();
+    let leg = Vector::from_angle(foot_direction);
 
     // ----------- ankle endpoints
-    return calculate_perpendicular_segment(lx, ly, mx, my, bar_width);
+    calculate_perpendicular_segment(leg, m, bar_width)
 }
 
-fn foot_path(cx: f32, cy: f32, r: f32, foot_direction_rad: f32, foot_angle_rad: f32, bar_width: f32) -> Path {
+fn foot_path(center: Point, r: f32, foot_direction: Radians, foot_angle: Radians, bar_width: f32) -> Path {
 
     // ---------- foot endpoints
-    let (a1xb, a1yb) = polar_to_cartesian(r, foot_direction_rad - 0.5 * foot_angle_rad, cx, cy);
-    let (a1xe, a1ye) = polar_to_cartesian(r, foot_direction_rad + 0.5 * foot_angle_rad, cx, cy);
+    let a1b = polar_to_cartesian(r, foot_direction - foot_angle * 0.5, center);
+    let a1e = polar_to_cartesian(r, foot_direction + foot_angle * 0.5, center);
 
-    // ---------- ankle midpoint
-    let (x1, y1, x2, y2) = ankle_endpoints(cx, cy, r, foot_direction_rad, bar_width);
+    // ---------- ankle endpoints
+    let (p1, p2) = ankle_endpoints(center, r, foot_direction, bar_width);
 
     // ----------- bezier control points
-    let pi = std::f32::consts::PI;
-    let (a1c1x, a1c1y) = polar_to_cartesian(0.15 * r, foot_direction_rad + pi / 2.5, a1xb, a1yb);
-    let (a1c2x, a1c2y) = polar_to_cartesian(0.25 * r, foot_direction_rad + pi, x1, y1);
-
-    let (a1c3x, a1c3y) = polar_to_cartesian(0.15 * r, foot_direction_rad - pi / 2.5, a1xe, a1ye);
-    let (a1c4x, a1c4y) = polar_to_cartesian(0.25 * r, foot_direction_rad + pi, x2, y2);
+    let pi = Radians(PI);
+    let c1 = polar_to_cartesian(0.15 * r, foot_direction + pi / 2.5, a1b);
+    let c2 = polar_to_cartesian(0.25 * r, foot_direction + pi, p1);
 
-    let foot_path = Path::new("").move_to(x1, y1)
-        .curve_to(a1c2x, a1c2y, a1c1x, a1c1y,  a1xb, a1yb)
-        .elliptical_arc_to(r, r, 0.0, false, true, a1xe, a1ye)
-        .curve_to(a1c3x, a1c3y, a1c4x, a1c4y, x2, y2);
+    let c3 = polar_to_cartesian(0.15 * r, foot_direction - pi / 2.5, a1e);
+    let c4 = polar_to_cartesian(0.25 * r, foot_direction + pi, p2);
 
-    return foot_path;
+    Path::new("").move_to(p1.x, p1.y)
+        .curve_to(c2.x, c2.y, c1.x, c1.y, a1b.x, a1b.y)
+        .elliptical_arc_to(r, r, 0.0, false, true, a1e.x, a1e.y)
+        .curve_to(c3.x, c3.y, c4.x, c4.y, p2.x, p2.y)
 }
 
-fn  calculate_perpendicular_segment(vx: f32, vy: f32, px: f32, py: f32, l: f32) -> (f32, f32, f32, f32) {
-    // ---------- Normalize the perpendicular vector(-vy, vx)
-    let magnitude = (vx * vx + vy * vy).sqrt();
-    let ux = -vy / magnitude;
-    let uy = vx / magnitude;
-
-    // ---------- Calculate half - length of the segment
-    let half_length = l / 2.0;
+/// Segment of length `l` centered at `p` and perpendicular to `v`
+fn calculate_perpendicular_segment(v: Vector, p: Point, l: f32) -> (Point, Point) {
+    // ---------- unit vector perpendicular to v, scaled to half the length of the segment
+    let half = v.perpendicular().normalize() * (l / 2.0);
 
-    // ---------- Calculate the endpoints of the segment
-    let x1 = px - half_length * ux;
-    let y1 = py - half_length * uy;
-    let x2 = px + half_length * ux;
-    let y2 = py + half_length * uy;
-
-    return (x1, y1, x2, y2)
+    (p - half, p + half)
 }
 
 
 #[cfg(test)]
 mod test_connector {
+    use crate::geometry::{Point, Radians};
     use crate::mindmap::connector::{ankle_endpoints, connector};
     use crate::mindmap::node::Node;
     use crate::mindmap::FOOT_LENGTH_R_FRACTION;
@@ -92,16 +79,16 @@ mod test_connector {
     #[test]
     fn test_ankle() {
 
-        let pts = ankle_endpoints(0.0, 0.0, 10.0, 0.0, 2.0);
-        assert_eq!(pts, (10.0*FOOT_LENGTH_R_FRACTION, -1.0, 10.0*FOOT_LENGTH_R_FRACTION, 1.0));
+        let pts = ankle_endpoints(Point::ORIGIN, 10.0, Radians(0.0), 2.0);
+        assert_eq!(pts, (Point::new(10.0*FOOT_LENGTH_R_FRACTION, -1.0), Point::new(10.0*FOOT_LENGTH_R_FRACTION, 1.0)));
     }
 
 
     #[test]
     fn test_connector() {
-        let na = Node::new("a", "A", 100.0, 100.0, 10.0);
-        let nb = Node::new("b", "B", 100.0, 100.0, 10.0);
-        let g = connector(&na, &nb, 0.0, 2.0);
+        let na = Node::new("a", "A", Point::new(100.0, 100.0), 10.0);
+        let nb = Node::new("b", "B", Point::new(100.0, 100.0), 10.0);
+        let g = connector(&na, &nb, Radians(0.0), 2.0);
         println!("{:?}", g.to_svg());
     }
-}
\ No newline at end of file
+}
@@ -1,15 +1,23 @@
 use std::collections::HashMap;
+use crate::geometry::{BBox, Degrees, Point, Radians};
 use crate::mindmap::connector::connector;
 use crate::mindmap::node::Node;
 use crate::mindmap::polar_to_cartesian;
 use crate::shapes::{Group};
+use crate::style::Style;
+use crate::theme::{Theme, MINDMAP_CONNECTORS_CLASS, MINDMAP_NODES_CLASS};
 use crate::ToSvg;
 
 #[derive(Debug, Clone)]
 pub struct Mindmap {
-    pub foot_angle_deg: f32,
+    /// angle at which the foot of a connector spreads over the circle of its node
+    pub foot_angle: Degrees,
     pub bar_width: f32,
     pub id: String,
+    /// style of the group of all nodes
+    pub node_style: Style,
+    /// style of the group of all connectors
+    pub connector_style: Style,
     nodes: HashMap<String, Node>,
     connections: Vec<(String,String)>,
     max_node_radius: f32,
@@ -19,24 +27,42 @@ pub struct Mindmap {
 impl Mindmap {
     pub fn new(id: &str, max_node_radius: f32) -> Self {
         Mindmap {
-            foot_angle_deg: 30.0,
+            foot_angle: Degrees(30.0),
             bar_width: max_node_radius / 5.0,
             id: id.to_string(),
+            node_style: Style::new(),
+            connector_style: Style::new(),
             nodes: HashMap::new(),
             max_node_radius,
             connections: vec![],
             node_radius_shrink_factor: 0.8 }
     }
 
-    pub fn place_node(&mut self, id: &str, label: &str, x: f32, y: f32) {
-        let el = Node::new(id, label, x, y, self.max_node_radius);
+    pub fn place_node(&mut self, id: &str, label: &str, center: Point) {
+        let el = Node::new(id, label, center, self.max_node_radius);
         self.nodes.insert(id.to_string(), el);
     }
 
-    pub fn grow_node(&mut self, id: &str, label: &str, angle_deg: f32, parent_node_id: &str) {
+    /// Places a new node next to its parent, in the direction given by `angle`, and connects them.
+    ///
+    /// The angle may be given in [`Degrees`] or in [`Radians`]; positive angles turn clockwise on screen.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::geometry::{Degrees, Point};
+    /// use visualife::mindmap::Mindmap;
+    /// use visualife::ToSvg;
+    /// let mut m = Mindmap::new("m", 10.0);
+    /// m.place_node("root", "Root", Point::new(100.0, 100.0));
+    /// m.grow_node("child", "Child", Degrees(90.0), "root");
+    /// // ---------- the child is placed right below its parent
+    /// let b = m.bbox().unwrap();
+    /// assert!((b.center().x - 100.0).abs() < 1e-3 && b.max.y > 130.0);
+    /// ```
+    pub fn grow_node(&mut self, id: &str, label: &str, angle: impl Into<Radians>, parent_node_id: &str) {
         let parent = self.nodes.get(parent_node_id).unwrap();
-        let (cx, cy) = polar_to_cartesian(parent.radius*3.0, angle_deg, parent.cx, parent.cy);
-        let el = Node::new(id, label, cx, cy, parent.radius * self.node_radius_shrink_factor);
+        let center = polar_to_cartesian(parent.radius * 3.0, angle, parent.center);
+        let el = Node::new(id, label, center, parent.radius * self.node_radius_shrink_factor);
         self.nodes.insert(id.to_string(), el);
 
         self.connect_nodes(parent_node_id, id);
@@ -45,25 +71,57 @@ impl Mindmap {
     pub fn connect_nodes(&mut self, from_id: &str, to_id: &str) {
         self.connections.push((from_id.to_string(), to_id.to_string()));
     }
+
+    /// Styles nodes and connectors of this mindmap according to a theme.
+    ///
+    /// The styles are written inline, so they take precedence over a stylesheet of a drawing.
+    /// Leave them unset when the mindmap should follow the theme(s) of the drawing it's placed in.
+    pub fn set_theme(&mut self, theme: &Theme) {
+        self.node_style = theme.node_style.clone();
+        self.connector_style = theme.connector_style.clone();
+    }
 }
 
 
 impl ToSvg for Mindmap {
-    fn to_svg(&self) -> String {
-        let mut mindmap_group = Group::new(&format!("{}", self.id));
+    fn id(&self) -> Option<&str> { Some(&self.id) }
+
+    fn tag(&self) -> Option<&str> { Some("g") }
+
+    fn visit_styles_mut(&mut self, f: &mut dyn FnMut(&mut Style)) {
+        f(&mut self.node_style);
+        f(&mut self.connector_style);
+    }
+
+    fn bbox(&self) -> Option<BBox> { self.to_group().bbox() }
+
+    fn to_svg(&self) -> String { self.to_group().to_svg() }
+}
+
+impl Mindmap {
+    /// Group of the nodes and the connectors of this mindmap, as drawn.
+    ///
+    /// Nodes are [`Circle`](crate::shapes::Circle)s identified by the ids of the nodes; add the group to a drawing
+    /// instead of the mindmap to change them afterwards, e.g. to highlight some of them.
+    pub fn to_group(&self) -> Group {
+        let mut mindmap_group = Group::new(&self.id);
         let mut node_grp = Group::new(&format!("nodes-{}", self.id));
+        node_grp.class = Some(MINDMAP_NODES_CLASS.to_string());
+        node_grp.style = self.node_style.clone();
         for node in self.nodes.values() {
-            node_grp.add_element(Box::new(node.clone()));
+            node_grp.add_element(Box::new(node.circle()));
         }
         let mut connector_grp = Group::new(&format!("connectors-{}", self.id));
+        connector_grp.class = Some(MINDMAP_CONNECTORS_CLASS.to_string());
+        connector_grp.style = self.connector_style.clone();
         for (from_id, to_id) in &self.connections {
             let from_node = self.nodes.get(from_id).unwrap();
             let to_node = self.nodes.get(to_id).unwrap();
-            connector_grp.add_element(Box::new(connector(from_node, to_node, self.foot_angle_deg, self.bar_width)));
+            connector_grp.add_element(Box::new(connector(from_node, to_node, self.foot_angle.into(), self.bar_width)));
         }
         mindmap_group.add_element(Box::new(node_grp));
         mindmap_group.add_element(Box::new(connector_grp));
 
-        return mindmap_group.to_svg();
+        mindmap_group
     }
 }
\ No newline at end of file
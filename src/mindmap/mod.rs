@@ -1,30 +1,32 @@
+#[allow(clippy::module_inception)]
 mod mindmap;
 mod connector;
 mod node;
 
 pub use mindmap::{Mindmap};
 
-const FOOT_LENGTH_R_FRACTION: f32 = 1.5;    /// foot is 1.5 times the radius
+use crate::geometry::{Point, Radians};
 
-/// Convert Cartesian coordinates of ``(px,py)`` point to polar coordinates centered at ``(cx,cy)`` point.
-///
-/// Returned angle is in radians.
-pub fn cartesian_to_polar(cx: f32, cy: f32, px: f32, py: f32) -> (f32, f32) {
-    let dx = px - cx;
-    let dy = py - cy;
-
-    // Calculate the radius
-    let r = (dx * dx + dy * dy).sqrt();
-
-    // Calculate the angle in radians
-    let theta = dy.atan2(dx);
+/// foot is 1.5 times the radius
+const FOOT_LENGTH_R_FRACTION: f32 = 1.5;
 
-    (r, theta)
-}
+/// Convert Cartesian coordinates of point `p` to polar coordinates centered at `center`.
+///
+/// Returns the distance from the center and the direction of the point.
+pub fn cartesian_to_polar(center: Point, p: Point) -> (f32, Radians) { p.to_polar(center) }
 
 /// Convert polar coordinates to cartesian coordinates.
 ///
-/// Returns the x, y coordinates of the ``(radius, angle_rad)`` radial point.
-pub fn polar_to_cartesian(radius: f32, angle_rad: f32, cx: f32, cy: f32) -> (f32, f32) {
-    (radius * angle_rad.cos() + cx, radius * angle_rad.sin() + cy)
+/// Returns the point at `radius` from `center` in the direction of `angle`, which may be given
+/// in [`Degrees`](crate::geometry::Degrees) or in [`Radians`].
+///
+/// # Example
+/// ```
+/// use visualife::geometry::{Degrees, Point};
+/// use visualife::mindmap::polar_to_cartesian;
+/// let p = polar_to_cartesian(10.0, Degrees(90.0), Point::new(5.0, 5.0));
+/// assert!(p.distance(Point::new(5.0, 15.0)) < 1e-5);
+/// ```
+pub fn polar_to_cartesian(radius: f32, angle: impl Into<Radians>, center: Point) -> Point {
+    Point::from_polar(center, radius, angle)
 }
@@ -1,36 +1,39 @@
+use crate::geometry::{BBox, Point};
 use crate::shapes::Circle;
 use crate::ToSvg;
 
 #[derive(Debug, Clone)]
 pub(crate) struct Node {
     pub id: String,
+    #[allow(dead_code)]
     pub label: String,
-    pub cx: f32,
-    pub cy: f32,
+    pub center: Point,
     pub radius: f32,
 }
 
 impl Node {
-    pub fn new(id: &str, label: &str, cx: f32, cy: f32, radius: f32) -> Self {
-        return Node { id: id.to_string(), label: label.to_string(), cx, cy, radius }
+    pub fn new(id: &str, label: &str, center: Point, radius: f32) -> Self {
+        Node { id: id.to_string(), label: label.to_string(), center, radius }
     }
+
+    pub fn circle(&self) -> Circle { Circle::new(&self.id, self.center.x, self.center.y, self.radius) }
 }
 
 impl ToSvg for Node {
-    fn to_svg(&self) -> String {
-        Circle::new(&self.id, self.cx, self.cy, self.radius).to_svg()
-    }
+    fn bbox(&self) -> Option<BBox> { self.circle().bbox() }
+
+    fn to_svg(&self) -> String { self.circle().to_svg() }
 }
 
 #[cfg(test)]
 mod test_node {
+    use crate::geometry::Point;
     use crate::mindmap::node::Node;
     use crate::ToSvg;
 
     #[test]
     fn node_to_svg() {
-        let (x1, y1, r1)  = (100.0_f32, 100.0_f32, 10.0_f32);
-        let na = Node::new("a", "A", x1, y1, r1);
+        let na = Node::new("a", "A", Point::new(100.0, 100.0), 10.0);
         assert_eq!(na.to_svg(), r#"<circle id="a" cx="100" cy="100" r="10" />"#);
     }
-}
\ No newline at end of file
+}
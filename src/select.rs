@@ -0,0 +1,327 @@
+//! Finding elements of a drawing: by id, by traversal and by CSS-like selectors.
+use std::fmt;
+use std::str::FromStr;
+use crate::ToSvg;
+
+/// Error returned when a selector doesn't follow the supported syntax.
+///
+/// Every variant carries the byte offset in the parsed string where the problem was found.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SelectorParseError {
+    /// A selector, or one of the selectors of a comma-separated list, that is empty
+    Empty { offset: usize },
+    /// A character that can't appear at that place, e.g. a pseudo-class or an attribute selector
+    UnexpectedCharacter { offset: usize, found: char },
+    /// A ``#`` or ``.`` that is not followed by a name
+    ExpectedName { offset: usize },
+}
+
+impl SelectorParseError {
+    /// Byte offset in the selector where the error was found
+    pub fn offset(&self) -> usize {
+        match self {
+            SelectorParseError::Empty { offset } | SelectorParseError::UnexpectedCharacter { offset, .. }
+            | SelectorParseError::ExpectedName { offset } => *offset,
+        }
+    }
+}
+
+impl fmt::Display for SelectorParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SelectorParseError::Empty { offset } => write!(f, "empty selector at byte {}", offset),
+            SelectorParseError::UnexpectedCharacter { offset, found } => write!(f, "unexpected character '{}' at byte {}", found, offset),
+            SelectorParseError::ExpectedName { offset } => write!(f, "expected a name at byte {}", offset),
+        }
+    }
+}
+
+impl std::error::Error for SelectorParseError {}
+
+/// A subset of CSS selectors, to find elements of a drawing.
+///
+/// Supported are:
+///  - type selectors such as ``circle`` or ``g``, and the universal selector ``*``
+///  - ``#id`` and ``.class`` selectors, which may be combined, as in ``circle.highlight``
+///  - descendant (``g circle``) and child (``g > circle``) combinators
+///  - comma-separated lists of selectors, matching elements that match any of them
+///
+/// Special characters in names, such as the ``:`` of ``#mesh:3``, are escaped with a backslash: ``#mesh\:3``.
+///
+/// # Example
+/// ```
+/// use std::str::FromStr;
+/// use visualife::Selector;
+/// assert!(Selector::from_str("g.nodes > circle#root, path").is_ok());
+/// assert_eq!(Selector::from_str("circle:hover").unwrap_err().offset(), 6);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Selector {
+    alternatives: Vec<Complex>,
+}
+
+/// Compound selectors joined by combinators; the combinator of the first one is ignored
+#[derive(Debug, Clone, PartialEq)]
+struct Complex(Vec<(Combinator, Compound)>);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Combinator { Descendant, Child }
+
+/// Conditions that must hold for a single element
+#[derive(Debug, Clone, PartialEq, Default)]
+struct Compound {
+    tag: Option<String>,
+    id: Option<String>,
+    classes: Vec<String>,
+}
+
+/// What a selector may test of an element
+#[derive(Debug, Clone)]
+pub(crate) struct ElementInfo {
+    tag: Option<String>,
+    id: Option<String>,
+    class: Option<String>,
+}
+
+impl ElementInfo {
+    pub(crate) fn of(el: &dyn ToSvg) -> ElementInfo {
+        ElementInfo { tag: el.tag().map(str::to_string), id: el.id().map(str::to_string), class: el.class().map(str::to_string) }
+    }
+}
+
+impl Compound {
+    fn matches(&self, el: &ElementInfo) -> bool {
+        (self.tag.is_none() || self.tag == el.tag) && (self.id.is_none() || self.id == el.id)
+            && self.classes.iter().all(|c| el.class.as_deref().is_some_and(|all| all.split_whitespace().any(|x| x == c)))
+    }
+}
+
+impl Complex {
+    /// Tells whether the first `k + 1` compounds match `el` and its `ancestors`, the nearest ancestor last
+    fn matches(&self, k: usize, el: &ElementInfo, ancestors: &[ElementInfo]) -> bool {
+        let (combinator, compound) = &self.0[k];
+        if !compound.matches(el) { return false; }
+        if k == 0 { return true; }
+        match combinator {
+            Combinator::Child => ancestors.split_last().is_some_and(|(parent, rest)| self.matches(k - 1, parent, rest)),
+            Combinator::Descendant => (0..ancestors.len()).rev().any(|i| self.matches(k - 1, &ancestors[i], &ancestors[..i])),
+        }
+    }
+}
+
+impl Selector {
+    /// Tells whether an element matches this selector, given its ancestors from the outermost one
+    pub(crate) fn matches(&self, el: &ElementInfo, ancestors: &[ElementInfo]) -> bool {
+        self.alternatives.iter().any(|c| c.matches(c.0.len() - 1, el, ancestors))
+    }
+}
+
+impl FromStr for Selector {
+    type Err = SelectorParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { chars: s.char_indices().peekable(), len: s.len() };
+        let mut alternatives = vec![Complex(vec![])];
+        let mut combinator = Combinator::Descendant;
+        loop {
+            parser.skip_whitespace();
+            let Some(&(offset, c)) = parser.chars.peek() else { break; };
+            let last = alternatives.last_mut().unwrap();
+            match c {
+                ',' => {
+                    if last.0.is_empty() { return Err(SelectorParseError::Empty { offset }); }
+                    if combinator == Combinator::Child { return Err(SelectorParseError::UnexpectedCharacter { offset, found: c }); }
+                    parser.chars.next();
+                    alternatives.push(Complex(vec![]));
+                    combinator = Combinator::Descendant;
+                }
+                '>' => {
+                    if last.0.is_empty() || combinator == Combinator::Child {
+                        return Err(SelectorParseError::UnexpectedCharacter { offset, found: c });
+                    }
+                    parser.chars.next();
+                    combinator = Combinator::Child;
+                }
+                _ => {
+                    last.0.push((combinator, parser.compound()?));
+                    combinator = Combinator::Descendant;
+                }
+            }
+        }
+        // ---------- a selector can't end with a comma or a combinator
+        let last = alternatives.last().unwrap();
+        if last.0.is_empty() || combinator == Combinator::Child {
+            return Err(SelectorParseError::Empty { offset: s.len() });
+        }
+        Ok(Selector { alternatives })
+    }
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+    len: usize,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    fn compound(&mut self) -> Result<Compound, SelectorParseError> {
+        let mut compound = Compound::default();
+        if self.chars.next_if(|&(_, c)| c == '*').is_none() {
+            compound.tag = self.name()?;
+        }
+        while let Some(&(offset, c)) = self.chars.peek() {
+            match c {
+                '#' | '.' => {
+                    self.chars.next();
+                    let name = self.name()?.ok_or(SelectorParseError::ExpectedName { offset: self.offset() })?;
+                    if c == '#' { compound.id = Some(name); } else { compound.classes.push(name); }
+                }
+                c if c.is_whitespace() || c == ',' || c == '>' => break,
+                _ => return Err(SelectorParseError::UnexpectedCharacter { offset, found: c }),
+            }
+        }
+        Ok(compound)
+    }
+
+    /// A name made of letters, digits, ``-`` and ``_``, and of any characters escaped with a backslash;
+    /// a backslash that ends the selector escapes nothing
+    fn name(&mut self) -> Result<Option<String>, SelectorParseError> {
+        let mut name = String::new();
+        while let Some((_, c)) = self.chars.next_if(|&(_, c)| c.is_alphanumeric() || c == '-' || c == '_' || c == '\\') {
+            if c == '\\' {
+                name.push(self.chars.next().ok_or(SelectorParseError::ExpectedName { offset: self.len })?.1);
+            } else {
+                name.push(c);
+            }
+        }
+        Ok((!name.is_empty()).then_some(name))
+    }
+
+    fn offset(&mut self) -> usize { self.chars.peek().map_or(self.len, |&(offset, _)| offset) }
+}
+
+/// Iterator over the elements nested in a drawing or a group, depth first, every element before its children
+pub struct Descendants<'a> {
+    stack: Vec<Box<dyn Iterator<Item = &'a dyn ToSvg> + 'a>>,
+}
+
+impl<'a> Descendants<'a> {
+    pub(crate) fn new(root: &'a dyn ToSvg) -> Self { Descendants { stack: vec![root.nested()] } }
+}
+
+impl<'a> Iterator for Descendants<'a> {
+    type Item = &'a dyn ToSvg;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(top) = self.stack.last_mut() {
+            match top.next() {
+                Some(el) => {
+                    self.stack.push(el.nested());
+                    return Some(el);
+                }
+                None => { self.stack.pop(); }
+            }
+        }
+        None
+    }
+}
+
+/// The box holding the first element with the given id, searched depth first
+pub(crate) fn find_slot<'a>(elements: &'a mut [Box<dyn ToSvg>], id: &str) -> Option<&'a mut Box<dyn ToSvg>> {
+    for el in elements.iter_mut() {
+        if el.id() == Some(id) { return Some(el); }
+        if let Some(found) = find_slot(el.children_mut(), id) { return Some(found); }
+    }
+    None
+}
+
+/// Removes the first element with the given id, searched depth first, from the list or the element that holds it
+pub(crate) fn remove(elements: &mut Vec<Box<dyn ToSvg>>, id: &str) -> Option<Box<dyn ToSvg>> {
+    for i in 0..elements.len() {
+        if elements[i].id() == Some(id) { return Some(elements.remove(i)); }
+        if let Some(removed) = remove_nested(elements[i].as_mut(), id) { return Some(removed); }
+    }
+    None
+}
+
+/// Removes the first element with the given id from among the elements nested in `parent`
+fn remove_nested(parent: &mut dyn ToSvg, id: &str) -> Option<Box<dyn ToSvg>> {
+    for i in 0..parent.children_mut().len() {
+        if parent.children_mut()[i].id() == Some(id) { return parent.remove_child(i); }
+        if let Some(removed) = remove_nested(parent.children_mut()[i].as_mut(), id) { return Some(removed); }
+    }
+    None
+}
+
+/// Elements nested in `parent` that match a selector, in document order
+pub(crate) fn select<'a>(parent: &'a dyn ToSvg, selector: &Selector, ancestors: &mut Vec<ElementInfo>,
+                         out: &mut Vec<&'a dyn ToSvg>) {
+    for el in parent.nested() {
+        let info = ElementInfo::of(el);
+        if selector.matches(&info, ancestors) { out.push(el); }
+        ancestors.push(info);
+        select(el, selector, ancestors, out);
+        ancestors.pop();
+    }
+}
+
+/// Calls `f` on `el` and on every element nested in it that match a selector, in document order;
+/// returns how many were matched
+pub(crate) fn select_mut(el: &mut dyn ToSvg, selector: &Selector, ancestors: &mut Vec<ElementInfo>,
+                         f: &mut dyn FnMut(&mut dyn ToSvg)) -> usize {
+    let info = ElementInfo::of(el);
+    let mut count = 0;
+    if selector.matches(&info, ancestors) {
+        f(el);
+        count += 1;
+    }
+    ancestors.push(info);
+    for child in el.children_mut() {
+        count += select_mut(child.as_mut(), selector, ancestors, f);
+    }
+    ancestors.pop();
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use crate::select::{ElementInfo, Selector, SelectorParseError};
+
+    fn info(tag: &str, id: &str, class: Option<&str>) -> ElementInfo {
+        ElementInfo { tag: Some(tag.to_string()), id: Some(id.to_string()), class: class.map(str::to_string) }
+    }
+
+    #[test]
+    fn match_selectors() {
+        let ancestors = [info("svg", "", None), info("g", "map", Some("nodes big")), info("g", "inner", None)];
+        let el = info("circle", "mesh:3", Some("highlight"));
+        let matches = |s: &str| Selector::from_str(s).unwrap().matches(&el, &ancestors);
+        for s in ["circle", "*", "#mesh\\:3", ".highlight", "circle.highlight", "g circle", "g.nodes circle", "svg g > circle",
+                  "g.big.nodes > * > circle", "rect, .highlight", "svg *"] {
+            assert!(matches(s), "{}", s);
+        }
+        for s in ["rect", "g.nodes > circle", "circle.other", "#mesh", "path circle", "svg > circle", "circle g"] {
+            assert!(!matches(s), "{}", s);
+        }
+    }
+
+    #[test]
+    fn parse_errors() {
+        let err = |s: &str| Selector::from_str(s).unwrap_err();
+        assert_eq!(err(""), SelectorParseError::Empty { offset: 0 });
+        assert_eq!(err("g,,circle"), SelectorParseError::Empty { offset: 2 });
+        assert_eq!(err("g >"), SelectorParseError::Empty { offset: 3 });
+        assert_eq!(err("> g"), SelectorParseError::UnexpectedCharacter { offset: 0, found: '>' });
+        assert_eq!(err("circle[r]"), SelectorParseError::UnexpectedCharacter { offset: 6, found: '[' });
+        assert_eq!(err("g.#x"), SelectorParseError::ExpectedName { offset: 2 });
+        assert_eq!(err("g."), SelectorParseError::ExpectedName { offset: 2 });
+        assert_eq!(err("g > , a"), SelectorParseError::UnexpectedCharacter { offset: 4, found: ',' });
+        assert_eq!(err("g > > a"), SelectorParseError::UnexpectedCharacter { offset: 4, found: '>' });
+        assert_eq!(err("\\"), SelectorParseError::ExpectedName { offset: 1 });
+        assert_eq!(err("g#a\\"), SelectorParseError::ExpectedName { offset: 4 });
+    }
+}
@@ -1,4 +1,5 @@
-use crate::draw_svg::{close_element, ToSvg};
+use crate::draw_svg::{close_element, escape, push_attributes, push_class, ToSvg};
+use crate::geometry::{BBox, Point, Vector};
 use crate::style::Style;
 
 /// A ``<circle>`` element
@@ -9,21 +10,44 @@ pub struct Circle {
     pub cy: f32,
     pub radius: f32,
     pub style: Style,
+    /// CSS class (or space-separated classes) of this circle
+    pub class: Option<String>,
+    /// attributes visualife has no field for, such as ``data-*`` ones, written as they are
+    pub attributes: Vec<(String, String)>,
 }
 
 impl Circle {
     pub fn new(id: &str, cx: f32, cy: f32, radius: f32) -> Self {
-        Circle { id: id.to_string(), cx, cy, radius, style: Style::new(), }
+        Circle { id: id.to_string(), cx, cy, radius, style: Style::new(), class: None, attributes: vec![] }
     }
+
+    pub fn center(&self) -> Point { Point::new(self.cx, self.cy) }
 }
 
 
 impl ToSvg for Circle {
+    fn id(&self) -> Option<&str> { Some(&self.id) }
+
+    fn tag(&self) -> Option<&str> { Some("circle") }
+
+    fn class(&self) -> Option<&str> { self.class.as_deref() }
+
+    fn style(&self) -> Option<&Style> { Some(&self.style) }
+
+    fn style_mut(&mut self) -> Option<&mut Style> { Some(&mut self.style) }
+
+    fn bbox(&self) -> Option<BBox> {
+        let r = Vector::new(self.radius, self.radius);
+        Some(BBox::new(self.center() - r, self.center() + r))
+    }
+
     fn to_svg(&self) -> String {
         let mut svg_string = format!(
             r#"<circle id="{}" cx="{}" cy="{}" r="{}""#,
-            self.id, self.cx, self.cy, self.radius
+            escape(&self.id, true), self.cx, self.cy, self.radius
         );
+        push_class(&self.class, &mut svg_string);
+        push_attributes(&self.attributes, &mut svg_string);
         close_element(&self.style, &mut svg_string);
 
         svg_string
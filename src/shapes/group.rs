@@ -1,4 +1,7 @@
-use crate::style::Style;
+use std::str::FromStr;
+use crate::select::{self, Descendants, ElementInfo, Selector, SelectorParseError};
+use crate::draw_svg::{escape, push_attributes, push_class};
+use crate::style::{computed_style, Style};
 use crate::ToSvg;
 
 /// A group of SVG elements.
@@ -18,30 +21,104 @@ pub struct Group {
     pub id: String,
     pub children: Vec<Box<dyn ToSvg>>,
     pub style: Style,
+    /// CSS class (or space-separated classes) of this group
+    pub class: Option<String>,
+    /// attributes visualife has no field for, such as ``data-*`` ones, written as they are
+    pub attributes: Vec<(String, String)>,
 }
 
 impl Group {
     pub fn new(id: &str) -> Self {
-        Group { id: id.to_string(), children: vec![], style: Style::new() }
+        Group { id: id.to_string(), children: vec![], style: Style::new(), class: None, attributes: vec![] }
     }
 
     pub fn add_element(&mut self, child: Box<dyn ToSvg>) { self.children.push(child); }
+
+    /// Effective style of the element identified by `id`, as seen inside this group.
+    ///
+    /// Styles of all the groups between this group and the element are cascaded on the way down,
+    /// so the returned style reports what the element actually looks like.
+    /// Returns `None` if this group doesn't contain an element with that id.
+    pub fn computed_style(&self, id: &str) -> Option<Style> {
+        if self.id == id {
+            return Some(self.style.clone());
+        }
+        computed_style(&self.children, &self.style, id)
+    }
+
+    /// The first element with the given id, searched depth first.
+    ///
+    /// Use `downcast_ref()` of `dyn ToSvg` to get at its concrete type.
+    pub fn get_by_id(&self, id: &str) -> Option<&dyn ToSvg> { self.descendants().find(|el| el.id() == Some(id)) }
+
+    /// Mutable access to the first element with the given id, searched depth first
+    pub fn get_by_id_mut(&mut self, id: &str) -> Option<&mut dyn ToSvg> {
+        select::find_slot(&mut self.children, id).map(|el| el.as_mut())
+    }
+
+    /// Removes the first element with the given id, searched depth first, from the element that holds it
+    pub fn remove_by_id(&mut self, id: &str) -> Option<Box<dyn ToSvg>> { select::remove(&mut self.children, id) }
+
+    /// Puts `element` in place of the first element with the given id and returns the replaced one.
+    ///
+    /// When no element has that id, nothing changes and `element` is dropped.
+    pub fn replace(&mut self, id: &str, element: Box<dyn ToSvg>) -> Option<Box<dyn ToSvg>> {
+        select::find_slot(&mut self.children, id).map(|slot| std::mem::replace(slot, element))
+    }
+
+    /// All the elements nested in this group, depth first, every element before its children
+    pub fn descendants(&self) -> Descendants<'_> { Descendants::new(self) }
+
+    /// Elements nested in this group that match a CSS-like [`Selector`], in document order
+    pub fn select(&self, selector: &str) -> Result<Vec<&dyn ToSvg>, SelectorParseError> {
+        let selector = Selector::from_str(selector)?;
+        let mut found = vec![];
+        select::select(self, &selector, &mut vec![ElementInfo::of(self)], &mut found);
+        Ok(found)
+    }
+
+    /// Calls `f` on every element nested in this group that matches a CSS-like [`Selector`]; returns how many matched
+    pub fn select_mut(&mut self, selector: &str, mut f: impl FnMut(&mut dyn ToSvg)) -> Result<usize, SelectorParseError> {
+        let selector = Selector::from_str(selector)?;
+        let mut ancestors = vec![ElementInfo::of(self)];
+        Ok(self.children.iter_mut().map(|el| select::select_mut(el.as_mut(), &selector, &mut ancestors, &mut f)).sum())
+    }
 }
 
 impl ToSvg for Group {
+    fn id(&self) -> Option<&str> { Some(&self.id) }
+
+    fn tag(&self) -> Option<&str> { Some("g") }
+
+    fn class(&self) -> Option<&str> { self.class.as_deref() }
+
+    fn style(&self) -> Option<&Style> { Some(&self.style) }
+
+    fn style_mut(&mut self) -> Option<&mut Style> { Some(&mut self.style) }
+
+    fn children(&self) -> &[Box<dyn ToSvg>] { &self.children }
+
+    fn children_mut(&mut self) -> &mut [Box<dyn ToSvg>] { &mut self.children }
+
+    fn remove_child(&mut self, index: usize) -> Option<Box<dyn ToSvg>> {
+        (index < self.children.len()).then(|| self.children.remove(index))
+    }
+
     fn to_svg(&self) -> String {
-        let mut svg_string = format!(r#"<g id="{}""#, self.id);
+        let mut svg_string = format!(r#"<g id="{}""#, escape(&self.id, true));
+        push_class(&self.class, &mut svg_string);
+        push_attributes(&self.attributes, &mut svg_string);
         if !self.style.is_empty() {
-            svg_string.push_str(&format!(r#" style="{}">\n"#, self.style.to_string()));
+            svg_string.push_str(&format!(r#" style="{}">\n"#, escape(&self.style.to_string(), true)));
         } else {
             svg_string.push_str(">\n");
         }
         for child in &self.children {
-            svg_string.push_str(&"\t");
+            svg_string.push('\t');
             svg_string.push_str(&child.to_svg());
-            svg_string.push_str(&"\n");
+            svg_string.push('\n');
         }
         svg_string.push_str("</g>");
-        return svg_string;
+        svg_string
     }
 }
\ No newline at end of file
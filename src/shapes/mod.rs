@@ -2,8 +2,12 @@ mod rect;
 mod circle;
 mod path;
 mod group;
+mod transform;
+mod raw;
 
 pub use rect::*;
 pub use group::*;
 pub use path::*;
-pub use circle::*;
\ No newline at end of file
+pub use circle::*;
+pub use transform::*;
+pub use raw::*;
@@ -0,0 +1,404 @@
+//! Boolean operations on the areas enclosed by paths.
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use crate::shapes::path::segments::{from_point, Pt};
+use crate::shapes::{Path, PathCommand};
+
+/// Flattening tolerance used by [`Path::union()`] and its siblings
+const BOOLEAN_TOLERANCE: f32 = 0.01;
+
+/// Rule deciding which points are inside a path, as the SVG ``fill-rule`` property
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FillRule {
+    /// a point is inside when the path winds around it a non-zero number of times
+    #[default]
+    NonZero,
+    /// a point is inside when a ray from it crosses the path an odd number of times
+    EvenOdd,
+}
+
+impl FillRule {
+    /// Tells whether a point with the given winding number is inside
+    pub fn is_inside(&self, winding: i32) -> bool {
+        match self {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0,
+        }
+    }
+}
+
+impl fmt::Display for FillRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FillRule::NonZero => write!(f, "nonzero"),
+            FillRule::EvenOdd => write!(f, "evenodd"),
+        }
+    }
+}
+
+/// Boolean operation combining the areas of two paths
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BooleanOp {
+    Union,
+    Intersection,
+    /// area of the first path that is not covered by the second one
+    Difference,
+    /// area covered by exactly one of the two paths
+    Xor,
+}
+
+impl BooleanOp {
+    fn apply(&self, in_a: bool, in_b: bool) -> bool {
+        match self {
+            BooleanOp::Union => in_a || in_b,
+            BooleanOp::Intersection => in_a && in_b,
+            BooleanOp::Difference => in_a && !in_b,
+            BooleanOp::Xor => in_a != in_b,
+        }
+    }
+}
+
+impl Path {
+    /// Area covered by this path or by `other`; see [`Path::boolean()`]
+    pub fn union(&self, other: &Path) -> Path {
+        self.boolean(other, BooleanOp::Union, FillRule::NonZero, BOOLEAN_TOLERANCE)
+    }
+
+    /// Area covered by both this path and `other`; see [`Path::boolean()`]
+    pub fn intersection(&self, other: &Path) -> Path {
+        self.boolean(other, BooleanOp::Intersection, FillRule::NonZero, BOOLEAN_TOLERANCE)
+    }
+
+    /// Area of this path not covered by `other`; see [`Path::boolean()`]
+    pub fn difference(&self, other: &Path) -> Path {
+        self.boolean(other, BooleanOp::Difference, FillRule::NonZero, BOOLEAN_TOLERANCE)
+    }
+
+    /// Area covered by exactly one of this path and `other`; see [`Path::boolean()`]
+    pub fn xor(&self, other: &Path) -> Path {
+        self.boolean(other, BooleanOp::Xor, FillRule::NonZero, BOOLEAN_TOLERANCE)
+    }
+
+    /// Combines the areas enclosed by this path and by `other`.
+    ///
+    /// Both paths are flattened with the given `tolerance` (see [`Path::flatten()`]); open sub-paths
+    /// are closed, as they are when filled. Points inside each operand are decided with `fill_rule`.
+    /// The result is made of closed polygons, each with the covered area on its left-hand side
+    /// (clockwise on screen, where the y axis points down), so it renders the same with either fill rule.
+    /// It keeps the id and the style of this path.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::shapes::{BooleanOp, FillRule, Path, PathCommand};
+    /// let a = Path::from_str("a", "M 0 0 H 10 V 10 H 0 Z").unwrap();
+    /// let b = Path::from_str("b", "M 5 5 H 15 V 15 H 5 Z").unwrap();
+    /// let i = a.boolean(&b, BooleanOp::Intersection, FillRule::NonZero, 0.1);
+    /// assert_eq!(i.d.len(), 5);
+    /// assert!(i.d.contains(&PathCommand::LineTo(10.0, 10.0)));
+    /// ```
+    pub fn boolean(&self, other: &Path, op: BooleanOp, fill_rule: FillRule, tolerance: f32) -> Path {
+        let rings: Vec<(usize, Vec<Pt>)> = [self, other].iter().enumerate()
+            .flat_map(|(operand, path)| path.flatten(tolerance).into_iter()
+                .map(move |pl| (operand, pl.points.iter().copied().map(from_point).collect())))
+            .collect();
+
+        self.with_rings(boolean_rings(&rings, op, fill_rule))
+    }
+
+    /// Copy of this path, keeping its id and style, with the geometry replaced by closed polygons
+    pub(crate) fn with_rings(&self, rings: Vec<Vec<Pt>>) -> Path {
+        let mut d = vec![];
+        for ring in rings {
+            d.push(PathCommand::MoveTo(ring[0][0] as f32, ring[0][1] as f32));
+            for p in &ring[1..] { d.push(PathCommand::LineTo(p[0] as f32, p[1] as f32)); }
+            d.push(PathCommand::Close);
+        }
+
+        self.with_commands(d)
+    }
+}
+
+fn cross(a: Pt, b: Pt) -> f64 { a[0] * b[1] - a[1] * b[0] }
+
+/// A directed edge of one of the two operands
+struct Edge { a: Pt, b: Pt, operand: usize }
+
+/// Combines closed polygons, each tagged with the operand (0 or 1) it belongs to.
+///
+/// Every edge is split at its intersections with all the other edges. Then, for every distinct piece,
+/// the winding numbers of both operands are evaluated on its two sides; a piece is kept when the
+/// result of the operation differs between the sides, oriented so that the covered area is on its left.
+/// The kept pieces are finally linked into loops.
+pub(crate) fn boolean_rings(rings: &[(usize, Vec<Pt>)], op: BooleanOp, fill_rule: FillRule) -> Vec<Vec<Pt>> {
+    let mut edges = vec![];
+    let (mut min, mut max) = ([f64::MAX; 2], [f64::MIN; 2]);
+    for (operand, ring) in rings {
+        for i in 0..ring.len() {
+            let (a, b) = (ring[i], ring[(i + 1) % ring.len()]);
+            for k in 0..2 { min[k] = min[k].min(a[k]); max[k] = max[k].max(a[k]); }
+            if a != b { edges.push(Edge { a, b, operand: *operand }); }
+        }
+    }
+    if edges.is_empty() { return vec![]; }
+    let scale = (max[0] - min[0]).max(max[1] - min[1]).max(1e-9);
+    let eps = scale * 1e-9;
+
+    // ---------- split points of every edge, as (parameter, point)
+    let mut splits: Vec<Vec<(f64, Pt)>> = edges.iter().map(|e| vec![(0.0, e.a), (1.0, e.b)]).collect();
+    let bounds: Vec<[f64; 4]> = edges.iter()
+        .map(|e| [e.a[0].min(e.b[0]) - eps, e.a[1].min(e.b[1]) - eps, e.a[0].max(e.b[0]) + eps, e.a[1].max(e.b[1]) + eps])
+        .collect();
+    // ---------- sweep along x: only edges whose x ranges overlap are tested against each other
+    let mut by_x: Vec<usize> = (0..edges.len()).collect();
+    by_x.sort_by(|&i, &j| bounds[i][0].total_cmp(&bounds[j][0]));
+    for (k, &first) in by_x.iter().enumerate() {
+        for &second in by_x[k + 1..].iter().take_while(|&&j| bounds[j][0] <= bounds[first][2]) {
+            let (i, j) = (first.min(second), first.max(second));
+            let (bi, bj) = (bounds[i], bounds[j]);
+            if bi[1] > bj[3] || bj[1] > bi[3] { continue; }
+            intersect(&edges[i], &edges[j], eps, |ti, tj, p| {
+                if ti > 0.0 && ti < 1.0 { splits[i].push((ti, p)); }
+                if tj > 0.0 && tj < 1.0 { splits[j].push((tj, p)); }
+            });
+        }
+    }
+
+    // ---------- vertices snapped to a fine grid, and pieces as pairs of vertex indexes
+    let mut vertex_ids: HashMap<(i64, i64), usize> = HashMap::new();
+    let mut vertices: Vec<Pt> = vec![];
+    let mut vertex = |p: Pt| -> usize {
+        let key = (((p[0] - min[0]) / eps).round() as i64, ((p[1] - min[1]) / eps).round() as i64);
+        *vertex_ids.entry(key).or_insert_with(|| { vertices.push(p); vertices.len() - 1 })
+    };
+    let mut pieces: Vec<(usize, usize, usize)> = vec![];
+    for (edge, s) in edges.iter().zip(splits.iter_mut()) {
+        s.sort_by(|x, y| x.0.total_cmp(&y.0));
+        let ids: Vec<usize> = s.iter().map(|&(_, p)| vertex(p)).collect();
+        for w in ids.windows(2) {
+            if w[0] != w[1] { pieces.push((w[0], w[1], edge.operand)); }
+        }
+    }
+
+    // ---------- net multiplicity of every undirected piece in each operand, oriented from the lower vertex index
+    let mut coincident: BTreeMap<(usize, usize), [i32; 2]> = BTreeMap::new();
+    for &(a, b, operand) in &pieces {
+        let (key, sign) = if a < b { ((a, b), 1) } else { ((b, a), -1) };
+        coincident.entry(key).or_insert([0, 0])[operand] += sign;
+    }
+
+    // ---------- horizontal bands of pieces, so a ray meets only the pieces of the band it runs in
+    let bands = Bands::new(&vertices, &pieces, min[1], max[1]);
+    let mut kept: Vec<(usize, usize)> = vec![];
+    for (&(u, v), multiplicity) in &coincident {
+        let (pu, pv) = (vertices[u], vertices[v]);
+        let m = [(pu[0] + pv[0]) / 2.0, (pu[1] + pv[1]) / 2.0];
+        let d = [pv[0] - pu[0], pv[1] - pu[1]];
+        // ---------- winding numbers at the midpoint, ignoring the piece itself
+        let mut w0 = [0, 0];
+        for &k in bands.at(m[1]) {
+            let (a, b, operand) = pieces[k];
+            if (a.min(b), a.max(b)) == (u, v) { continue; }
+            w0[operand] += crossing(vertices[a], vertices[b], m);
+        }
+        // ---------- the ray towards +x doesn't cross the piece itself, so w0 is the winding on its +x side
+        // ---------- (+y side when horizontal); crossing the piece from its right to its left adds its multiplicity
+        let left_is_w0_side = if d[1] != 0.0 { d[1] < 0.0 } else { d[0] > 0.0 };
+        let (mut left, mut right) = ([false; 2], [false; 2]);
+        for k in 0..2 {
+            let (wl, wr) = if left_is_w0_side { (w0[k], w0[k] - multiplicity[k]) } else { (w0[k] + multiplicity[k], w0[k]) };
+            left[k] = fill_rule.is_inside(wl);
+            right[k] = fill_rule.is_inside(wr);
+        }
+        let (in_left, in_right) = (op.apply(left[0], left[1]), op.apply(right[0], right[1]));
+        if in_left && !in_right { kept.push((u, v)); }
+        if in_right && !in_left { kept.push((v, u)); }
+    }
+
+    link_loops(&vertices, kept)
+}
+
+/// Pieces sorted into horizontal bands of equal height by the range of y they span
+struct Bands {
+    /// indexes of the pieces that reach into every band
+    bands: Vec<Vec<usize>>,
+    y0: f64,
+    height: f64,
+}
+
+impl Bands {
+    fn new(vertices: &[Pt], pieces: &[(usize, usize, usize)], y0: f64, y1: f64) -> Self {
+        let n = ((pieces.len() as f64).sqrt() as usize).max(1);
+        let height = ((y1 - y0) / n as f64).max(f64::MIN_POSITIVE);
+        let mut out = Bands { bands: vec![vec![]; n], y0, height };
+        for (k, &(a, b, _)) in pieces.iter().enumerate() {
+            let (ya, yb) = (vertices[a][1], vertices[b][1]);
+            for band in out.band(ya.min(yb))..=out.band(ya.max(yb)) { out.bands[band].push(k); }
+        }
+        out
+    }
+
+    fn band(&self, y: f64) -> usize { (((y - self.y0) / self.height).max(0.0) as usize).min(self.bands.len() - 1) }
+
+    /// Pieces that may cross the horizontal line at `y`
+    fn at(&self, y: f64) -> &[usize] { &self.bands[self.band(y)] }
+}
+
+/// Contribution of the edge `ab` to the winding number at `p`, counted along a ray towards +x
+fn crossing(a: Pt, b: Pt, p: Pt) -> i32 {
+    let upward = a[1] <= p[1] && p[1] < b[1];
+    let downward = b[1] <= p[1] && p[1] < a[1];
+    if !upward && !downward { return 0; }
+    let x = a[0] + (p[1] - a[1]) / (b[1] - a[1]) * (b[0] - a[0]);
+    if x <= p[0] { 0 } else if upward { 1 } else { -1 }
+}
+
+/// Calls `found(ti, tj, point)` for every point shared by two edges.
+///
+/// Crossings report a single point; collinear overlaps report the end points of each edge lying on the other one.
+fn intersect<F: FnMut(f64, f64, Pt)>(ei: &Edge, ej: &Edge, eps: f64, mut found: F) {
+    let r = [ei.b[0] - ei.a[0], ei.b[1] - ei.a[1]];
+    let s = [ej.b[0] - ej.a[0], ej.b[1] - ej.a[1]];
+    let qp = [ej.a[0] - ei.a[0], ej.a[1] - ei.a[1]];
+    let (len_r, len_s) = (r[0].hypot(r[1]), s[0].hypot(s[1]));
+    let denom = cross(r, s);
+    if denom.abs() > 1e-12 * len_r * len_s {
+        let ti = cross(qp, s) / denom;
+        let tj = cross(qp, r) / denom;
+        let (tol_i, tol_j) = (eps / len_r, eps / len_s);
+        if ti < -tol_i || ti > 1.0 + tol_i || tj < -tol_j || tj > 1.0 + tol_j { return; }
+        // ---------- reuse an end point when the crossing is at one, so that T-junctions meet exactly
+        let p = if tj.abs() <= tol_j { ej.a } else if (tj - 1.0).abs() <= tol_j { ej.b }
+            else if ti.abs() <= tol_i { ei.a } else if (ti - 1.0).abs() <= tol_i { ei.b }
+            else { [ei.a[0] + ti * r[0], ei.a[1] + ti * r[1]] };
+        found(ti.clamp(0.0, 1.0), tj.clamp(0.0, 1.0), p);
+    } else if cross(qp, r).abs() <= eps * len_r {
+        // ---------- collinear: project the end points of each edge onto the other one
+        let project = |p: Pt, o: Pt, dir: Pt, len2: f64| ((p[0] - o[0]) * dir[0] + (p[1] - o[1]) * dir[1]) / len2;
+        for p in [ej.a, ej.b] {
+            let t = project(p, ei.a, r, len_r * len_r);
+            if t > 0.0 && t < 1.0 { found(t, -1.0, p); }
+        }
+        for p in [ei.a, ei.b] {
+            let t = project(p, ej.a, s, len_s * len_s);
+            if t > 0.0 && t < 1.0 { found(-1.0, t, p); }
+        }
+    }
+}
+
+/// Links directed edges into closed loops, dropping vertices where the outline goes straight on.
+///
+/// Where several edges leave a vertex, the one turning most to the left is taken, so that regions
+/// touching at a single point stay separate loops.
+fn link_loops(vertices: &[Pt], edges: Vec<(usize, usize)>) -> Vec<Vec<Pt>> {
+    let mut outgoing: HashMap<usize, Vec<usize>> = HashMap::new();
+    for (i, &(a, _)) in edges.iter().enumerate() { outgoing.entry(a).or_default().push(i); }
+    let mut used = vec![false; edges.len()];
+    let mut loops = vec![];
+    for first in 0..edges.len() {
+        if used[first] { continue; }
+        let mut ring = vec![edges[first].0];
+        let mut current = first;
+        used[first] = true;
+        loop {
+            let (a, b) = edges[current];
+            if b == ring[0] { break; }
+            ring.push(b);
+            let incoming = [vertices[b][0] - vertices[a][0], vertices[b][1] - vertices[a][1]];
+            let next = outgoing.get(&b).and_then(|candidates| candidates.iter().copied()
+                .filter(|&e| !used[e])
+                .max_by(|&x, &y| turn(incoming, vertices, edges[x]).total_cmp(&turn(incoming, vertices, edges[y]))));
+            match next {
+                Some(e) => { used[e] = true; current = e; }
+                None => break,
+            }
+        }
+        let ring = remove_collinear(ring.iter().map(|&i| vertices[i]).collect());
+        if ring.len() >= 3 { loops.push(ring); }
+    }
+
+    loops
+}
+
+/// Signed angle from `incoming` to the direction of `edge`; positive for a left turn
+fn turn(incoming: Pt, vertices: &[Pt], edge: (usize, usize)) -> f64 {
+    let (a, b) = (vertices[edge.0], vertices[edge.1]);
+    let out = [b[0] - a[0], b[1] - a[1]];
+    cross(incoming, out).atan2(incoming[0] * out[0] + incoming[1] * out[1])
+}
+
+fn remove_collinear(mut ring: Vec<Pt>) -> Vec<Pt> {
+    let mut i = 0;
+    while ring.len() >= 3 && i < ring.len() {
+        let n = ring.len();
+        let (prev, p, next) = (ring[(i + n - 1) % n], ring[i], ring[(i + 1) % n]);
+        let (u, v) = ([p[0] - prev[0], p[1] - prev[1]], [next[0] - p[0], next[1] - p[1]]);
+        let straight = cross(u, v).abs() <= 1e-9 * u[0].hypot(u[1]) * v[0].hypot(v[1]) && u[0] * v[0] + u[1] * v[1] > 0.0;
+        if straight { ring.remove(i); i = i.saturating_sub(1); } else { i += 1; }
+    }
+
+    ring
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shapes::{BooleanOp, FillRule, Path};
+
+    /// Area enclosed by a path made of straight lines only, with the sign of its orientation
+    fn area(p: &Path) -> f32 {
+        p.flatten(0.01).iter().map(|pl| {
+            let n = pl.points.len();
+            (0..n).map(|i| {
+                let (a, b) = (pl.points[i], pl.points[(i + 1) % n]);
+                a.to_vector().cross(b.to_vector())
+            }).sum::<f32>() / 2.0
+        }).sum()
+    }
+
+    fn count(p: &Path, command: char) -> usize {
+        p.d.iter().filter(|c| c.to_string().starts_with(command)).count()
+    }
+
+    #[test]
+    fn overlapping_squares() {
+        let a = Path::from_str("a", "M 0 0 H 10 V 10 H 0 Z").unwrap();
+        let b = Path::from_str("b", "M 5 5 H 15 V 15 H 5 Z").unwrap();
+        let expected = [(BooleanOp::Union, 175.0), (BooleanOp::Intersection, 25.0),
+            (BooleanOp::Difference, 75.0), (BooleanOp::Xor, 150.0)];
+        for (op, a_expected) in expected {
+            let r = a.boolean(&b, op, FillRule::NonZero, 0.01);
+            assert!((area(&r) - a_expected).abs() < 1e-3, "{:?}: {}", op, area(&r));
+        }
+        assert_eq!(a.union(&b).d.len(), 9);
+    }
+
+    #[test]
+    fn shared_edges_and_holes() {
+        // ---------- squares sharing an edge merge into a single rectangle
+        let a = Path::from_str("a", "M 0 0 H 10 V 10 H 0 Z").unwrap();
+        let b = Path::from_str("b", "M 10 0 H 20 V 10 H 10 Z").unwrap();
+        let u = a.union(&b);
+        assert_eq!(count(&u, 'L'), 3);
+        assert!((area(&u) - 200.0).abs() < 1e-3);
+        // ---------- a cut-out leaves a ring
+        let hole = Path::from_str("h", "M 3 3 H 7 V 7 H 3 Z").unwrap();
+        let ring = a.difference(&hole);
+        assert_eq!(count(&ring, 'M'), 2);
+        assert!((area(&ring) - 84.0).abs() < 1e-3);
+        // ---------- the same hole drawn inside a single path, decided by the fill rule
+        let donut = Path::from_str("d", "M 0 0 H 10 V 10 H 0 Z M 3 3 H 7 V 7 H 3 Z").unwrap();
+        let even_odd = donut.boolean(&Path::new("empty"), BooleanOp::Union, FillRule::EvenOdd, 0.01);
+        assert!((area(&even_odd) - 84.0).abs() < 1e-3);
+        let non_zero = donut.boolean(&Path::new("empty"), BooleanOp::Union, FillRule::NonZero, 0.01);
+        assert!((area(&non_zero) - 100.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn curved_operands() {
+        let circle = |id: &str, cx: f32| Path::from_str(id, &format!("M {} 0 a 10 10 0 0 0 20 0 a 10 10 0 0 0 -20 0 Z", cx - 10.0)).unwrap();
+        let (a, b) = (circle("a", 0.0), circle("b", 10.0));
+        // ---------- the lens of two circles of radius r at distance r apart has the area (2pi/3 - sqrt(3)/2) r^2
+        let lens = (2.0 * std::f32::consts::PI / 3.0 - 3f32.sqrt() / 2.0) * 100.0;
+        assert!((area(&a.intersection(&b)) - lens).abs() < 0.5);
+        assert!((area(&a.union(&b)) - (200.0 * std::f32::consts::PI - lens)).abs() < 1.0);
+    }
+}
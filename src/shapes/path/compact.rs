@@ -0,0 +1,224 @@
+//! Shortest textual encoding of path data.
+use std::str::FromStr;
+use crate::shapes::{Path, PathCommand};
+
+type P = (f32, f32);
+
+/// Argument of a command, as written in path data
+#[derive(Clone)]
+enum Token {
+    /// the formatted number
+    Number(String),
+    Flag(bool),
+}
+
+/// What was written last, deciding whether a separator is needed before the next token
+#[derive(Clone, Copy, PartialEq)]
+enum Last {
+    Letter,
+    /// a number; true when it contains a ``.`` or an exponent, so that a following ``.5`` needs no space
+    Number(bool),
+    Flag,
+}
+
+/// An encoding of a command, together with the geometry a parser would read back from it
+struct Candidate {
+    letter: char,
+    tokens: Vec<Token>,
+    end: P,
+    /// second control point of a cubic, or the control point of a quadratic, as read back
+    control: Option<P>,
+}
+
+impl Path {
+    /// Path data of this path in its shortest form.
+    ///
+    /// Every command is written either absolute or relative, whichever is shorter; ``H``, ``V``, ``S`` and ``T``
+    /// shorthands are used where they apply and repeated command letters are dropped. Numbers lose leading zeros
+    /// and separators that a parser doesn't need. When `decimals` is given, numbers are rounded to that many
+    /// decimal places; the current point is tracked as a parser would read it back, so rounding errors of
+    /// relative commands don't accumulate. Set [`compact`](Path::compact) to write a path in this form
+    /// with [`to_svg()`](crate::ToSvg::to_svg).
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::shapes::Path;
+    /// let p = Path::from_str("p", "M 10 10 L 20 10 L 20 20 L 10 20 Z M 100 100 L 101 101").unwrap();
+    /// assert_eq!(p.to_compact_d(None), "M10 10H20V20H10zm90 90 1 1");
+    /// let q = Path::from_str("q", "M 0.5 0.25 L -0.5 0.754").unwrap();
+    /// assert_eq!(q.to_compact_d(Some(2)), "M.5.25-.5.75");
+    /// ```
+    pub fn to_compact_d(&self, decimals: Option<u32>) -> String {
+        let mut out = String::new();
+        let mut last = Last::Letter;
+        let mut implicit: Option<char> = None;
+        let (mut cur, mut start): (P, P) = ((0.0, 0.0), (0.0, 0.0));
+        let mut last_cubic: Option<P> = None;
+        let mut last_quad: Option<P> = None;
+        let num = |v: f32| format_number(v, decimals);
+        // ---------- coordinates: absolute, or relative to the current point as read back by a parser
+        let abs = |p: P| { let (x, y) = (num(p.0), num(p.1)); ((x.1, y.1), vec![Token::Number(x.0), Token::Number(y.0)]) };
+        let rel = |p: P, cur: P| {
+            let (x, y) = (num(p.0 - cur.0), num(p.1 - cur.1));
+            ((cur.0 + x.1, cur.1 + y.1), vec![Token::Number(x.0), Token::Number(y.0)])
+        };
+        let same = |a: f32, b: f32| num(a).0 == num(b).0;
+
+        for command in self.expand_shorthands().d {
+            let mut candidates: Vec<Candidate> = vec![];
+            match command {
+                PathCommand::MoveTo(x, y) => {
+                    let (a, ta) = abs((x, y));
+                    let (r, tr) = rel((x, y), cur);
+                    candidates.push(Candidate { letter: 'M', tokens: ta, end: a, control: None });
+                    candidates.push(Candidate { letter: 'm', tokens: tr, end: r, control: None });
+                }
+                PathCommand::LineTo(x, y) => {
+                    let (a, ta) = abs((x, y));
+                    let (r, tr) = rel((x, y), cur);
+                    candidates.push(Candidate { letter: 'L', tokens: ta, end: a, control: None });
+                    candidates.push(Candidate { letter: 'l', tokens: tr, end: r, control: None });
+                    if same(y, cur.1) {
+                        let (h, dh) = (num(x), num(x - cur.0));
+                        candidates.push(Candidate { letter: 'H', tokens: vec![Token::Number(h.0)], end: (h.1, cur.1), control: None });
+                        candidates.push(Candidate { letter: 'h', tokens: vec![Token::Number(dh.0)], end: (cur.0 + dh.1, cur.1), control: None });
+                    }
+                    if same(x, cur.0) {
+                        let (v, dv) = (num(y), num(y - cur.1));
+                        candidates.push(Candidate { letter: 'V', tokens: vec![Token::Number(v.0)], end: (cur.0, v.1), control: None });
+                        candidates.push(Candidate { letter: 'v', tokens: vec![Token::Number(dv.0)], end: (cur.0, cur.1 + dv.1), control: None });
+                    }
+                }
+                PathCommand::CurveTo(x1, y1, x2, y2, x, y) => {
+                    let ((_, t1a), (c2a, t2a), (ea, tea)) = (abs((x1, y1)), abs((x2, y2)), abs((x, y)));
+                    let ((_, t1r), (c2r, t2r), (er, ter)) = (rel((x1, y1), cur), rel((x2, y2), cur), rel((x, y), cur));
+                    let reflected = last_cubic.map_or(cur, |c| (2.0 * cur.0 - c.0, 2.0 * cur.1 - c.1));
+                    if same(x1, reflected.0) && same(y1, reflected.1) {
+                        candidates.push(Candidate { letter: 'S', tokens: [t2a.clone(), tea.clone()].concat(), end: ea, control: Some(c2a) });
+                        candidates.push(Candidate { letter: 's', tokens: [t2r.clone(), ter.clone()].concat(), end: er, control: Some(c2r) });
+                    }
+                    candidates.push(Candidate { letter: 'C', tokens: [t1a, t2a, tea].concat(), end: ea, control: Some(c2a) });
+                    candidates.push(Candidate { letter: 'c', tokens: [t1r, t2r, ter].concat(), end: er, control: Some(c2r) });
+                }
+                PathCommand::QuadraticBezierCurveTo(x1, y1, x, y) => {
+                    let ((c1a, t1a), (ea, tea)) = (abs((x1, y1)), abs((x, y)));
+                    let ((c1r, t1r), (er, ter)) = (rel((x1, y1), cur), rel((x, y), cur));
+                    let reflected = last_quad.map_or(cur, |c| (2.0 * cur.0 - c.0, 2.0 * cur.1 - c.1));
+                    if same(x1, reflected.0) && same(y1, reflected.1) {
+                        candidates.push(Candidate { letter: 'T', tokens: tea.clone(), end: ea, control: Some(reflected) });
+                        candidates.push(Candidate { letter: 't', tokens: ter.clone(), end: er, control: Some(reflected) });
+                    }
+                    candidates.push(Candidate { letter: 'Q', tokens: [t1a, tea].concat(), end: ea, control: Some(c1a) });
+                    candidates.push(Candidate { letter: 'q', tokens: [t1r, ter].concat(), end: er, control: Some(c1r) });
+                }
+                PathCommand::EllipticalArcTo(rx, ry, rot, large, sweep, x, y) => {
+                    let shape = vec![Token::Number(num(rx).0), Token::Number(num(ry).0), Token::Number(num(rot).0),
+                                     Token::Flag(large), Token::Flag(sweep)];
+                    let (a, ta) = abs((x, y));
+                    let (r, tr) = rel((x, y), cur);
+                    candidates.push(Candidate { letter: 'A', tokens: [shape.clone(), ta].concat(), end: a, control: None });
+                    candidates.push(Candidate { letter: 'a', tokens: [shape, tr].concat(), end: r, control: None });
+                }
+                PathCommand::Close => candidates.push(Candidate { letter: 'z', tokens: vec![], end: start, control: None }),
+                _ => unreachable!("expand_shorthands() leaves only absolute M, L, C, Q, A and Z commands"),
+            }
+
+            let best = candidates.into_iter()
+                .map(|c| { let text = encode(&c, last, implicit); (c, text) })
+                .min_by_key(|(_, (text, _))| text.len()).unwrap();
+            let (chosen, (text, new_last)) = best;
+            out.push_str(&text);
+            last = new_last;
+            implicit = match chosen.letter { 'M' => Some('L'), 'm' => Some('l'), 'z' => None, c => Some(c) };
+            cur = chosen.end;
+            if chosen.letter.eq_ignore_ascii_case(&'M') { start = cur; }
+            last_cubic = if "CcSs".contains(chosen.letter) { chosen.control } else { None };
+            last_quad = if "QqTt".contains(chosen.letter) { chosen.control } else { None };
+        }
+
+        out
+    }
+}
+
+/// Text appended for a command, and what ends it
+fn encode(c: &Candidate, mut last: Last, implicit: Option<char>) -> (String, Last) {
+    let mut text = String::new();
+    if implicit != Some(c.letter) || c.tokens.is_empty() {
+        text.push(c.letter);
+        last = Last::Letter;
+    }
+    for token in &c.tokens {
+        match token {
+            Token::Number(s) => {
+                let glued = match last {
+                    Last::Letter | Last::Flag => true,
+                    Last::Number(has_dot) => s.starts_with('-') || (s.starts_with('.') && has_dot),
+                };
+                if !glued { text.push(' '); }
+                text.push_str(s);
+                last = Last::Number(s.contains(['.', 'e']));
+            }
+            Token::Flag(f) => {
+                if matches!(last, Last::Number(_)) { text.push(' '); }
+                text.push(if *f { '1' } else { '0' });
+                last = Last::Flag;
+            }
+        }
+    }
+
+    (text, last)
+}
+
+/// Shortest text of a number, optionally rounded, and the value a parser reads back from it
+fn format_number(v: f32, decimals: Option<u32>) -> (String, f32) {
+    let v = match decimals {
+        Some(d) => { let scale = 10f64.powi(d as i32); ((v as f64 * scale).round() / scale) as f32 }
+        None => v,
+    };
+    let v = if v == 0.0 { 0.0 } else { v };
+    let mut plain = format!("{}", v);
+    if let Some(rest) = plain.strip_prefix("0.") { plain = format!(".{}", rest); }
+    if let Some(rest) = plain.strip_prefix("-0.") { plain = format!("-.{}", rest); }
+    let scientific = format!("{:e}", v);
+    let text = if scientific.len() < plain.len() { scientific } else { plain };
+    let read_back = f32::from_str(&text).unwrap_or(v);
+
+    (text, read_back)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shapes::Path;
+    use crate::{SvgDrawing, ToSvg};
+
+    #[test]
+    fn shorthands_and_round_trip() {
+        let p = Path::from_str("p", "M 0 0 C 0 10 10 10 10 0 C 10 -10 20 -10 20 0 Q 25 5 30 0 Q 35 -5 40 0 \
+            A 5 5 0 0 1 50 0 L 1000000 0.0001 Z").unwrap();
+        let compact = p.to_compact_d(None);
+        assert_eq!(compact, "M0 0C0 10 10 10 10 0S20-10 20 0q5 5 10 0T40 0A5 5 0 0150 0L1e6 1e-4z");
+        // ---------- the compact data describes the same geometry
+        let back = Path::from_str("b", &compact).unwrap();
+        assert_eq!(back.normalize().d, p.normalize().d);
+    }
+
+    #[test]
+    fn rounding_does_not_drift() {
+        let mut p = Path::new("p").move_to(0.0, 0.0);
+        for i in 1..200 { p = p.line_to(i as f32 * 1.0049, (i % 2) as f32 * 0.3333); }
+        let compact = p.to_compact_d(Some(1));
+        let back = Path::from_str("b", &compact).unwrap().to_absolute();
+        let last = back.d.last().unwrap().to_string();
+        assert_eq!(last, "L 200 0.3");
+        assert!(compact.len() < p.to_svg().len() / 2);
+    }
+
+    #[test]
+    fn compact_documents() {
+        let mut drawing = SvgDrawing::new(100.0, 100.0);
+        drawing.add_element(Box::new(Path::from_str("p", "M 0.5 0.5 L 10.25 0.5 L 10.25 10").unwrap()));
+        assert!(drawing.to_svg().contains(r#"<path id="p" d="M 0.5 0.5 L 10.25 0.5 L 10.25 10" />"#));
+        drawing.select_mut("path", |el| el.downcast_mut::<Path>().unwrap().compact = Some(1)).unwrap();
+        assert!(drawing.to_svg().contains(r#"<path id="p" d="M.5.5h9.8V10" />"#));
+    }
+}
@@ -0,0 +1,285 @@
+//! Editing of paths: reversing, cutting into parts and dashing.
+use crate::shapes::path::normalize::end_point;
+use crate::shapes::path::segments::{subpaths, Segment, SubPath};
+use crate::shapes::{Path, PathCommand};
+
+/// A run of segments to be written as one sub-path
+struct Piece {
+    segments: Vec<Segment>,
+    closed: bool,
+}
+
+impl Path {
+    /// Returns this path traversed in the opposite direction.
+    ///
+    /// The result is normalized (see [`Path::normalize()`]); its sub-paths come in reverse order,
+    /// and closed sub-paths stay closed and start at the same point.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::shapes::{Path, PathCommand};
+    /// let p = Path::from_str("p", "M 0 0 L 10 0 L 10 10").unwrap();
+    /// assert_eq!(p.reverse().d, vec![PathCommand::MoveTo(10.0, 10.0), PathCommand::LineTo(10.0, 0.0),
+    ///     PathCommand::LineTo(0.0, 0.0)]);
+    /// ```
+    pub fn reverse(&self) -> Path {
+        let mut d = vec![];
+        for sp in subpaths(self).into_iter().rev() {
+            if sp.segments.is_empty() {
+                d.push(PathCommand::MoveTo(sp.start[0] as f32, sp.start[1] as f32));
+                continue;
+            }
+            let segments = sp.segments.iter().rev().map(Segment::reversed).collect();
+            write_piece(&Piece { segments, closed: sp.closed }, &mut d);
+        }
+
+        self.with_commands(d)
+    }
+
+    /// Splits this path in two at the distance `s` along it.
+    ///
+    /// `s` is clamped to ``[0, length()]``. Both parts are normalized; a closed sub-path that is cut becomes open.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::geometry::Point;
+    /// use visualife::shapes::Path;
+    /// let p = Path::from_str("p", "M 0 0 H 10 V 10").unwrap();
+    /// let (a, b) = p.split_at_length(15.0);
+    /// assert_eq!((a.length(), b.length()), (15.0, 5.0));
+    /// assert_eq!(b.point_at_length(0.0), Some(Point::new(10.0, 5.0)));
+    /// ```
+    pub fn split_at_length(&self, s: f32) -> (Path, Path) {
+        let subs = subpaths(self);
+        let total = total_length(&subs);
+        let s = (s as f64).clamp(0.0, total);
+        (self.with_pieces(extract(&subs, 0.0, s)), self.with_pieces(extract(&subs, s, total)))
+    }
+
+    /// Sub-paths of this path, each started by a move command.
+    ///
+    /// Commands are kept as they are, except that a relative move starting a sub-path is made absolute,
+    /// and a sub-path following a ``Z`` without a move gets an explicit one. Sub-paths keep the id and
+    /// the style of this path.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::shapes::Path;
+    /// let p = Path::from_str("p", "M 0 0 h 5 z m 10 10 v 5").unwrap();
+    /// let parts: Vec<String> = p.subpaths().map(|s| s.d.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(" ")).collect();
+    /// assert_eq!(parts, vec!["M 0 0 h 5 Z", "M 10 10 v 5"]);
+    /// ```
+    pub fn subpaths(&self) -> impl Iterator<Item = Path> + '_ {
+        let absolute = self.to_absolute().d;
+        let mut parts: Vec<Vec<PathCommand>> = vec![];
+        let (mut current, mut start) = ((0.0, 0.0), (0.0, 0.0));
+        let mut after_close = false;
+        for (command, abs) in self.d.iter().zip(absolute.iter()) {
+            match abs {
+                PathCommand::MoveTo(x, y) => {
+                    parts.push(vec![PathCommand::MoveTo(*x, *y)]);
+                    (current, start) = ((*x, *y), (*x, *y));
+                }
+                _ => {
+                    if parts.is_empty() || after_close {
+                        parts.push(vec![PathCommand::MoveTo(current.0, current.1)]);
+                    }
+                    parts.last_mut().unwrap().push(command.clone());
+                    match abs {
+                        PathCommand::Close => current = start,
+                        PathCommand::HorizontalTo(x) => current.0 = *x,
+                        PathCommand::VerticalTo(y) => current.1 = *y,
+                        other => if let Some(p) = end_point(other) { current = p },
+                    }
+                }
+            }
+            after_close = matches!(abs, PathCommand::Close);
+        }
+
+        parts.into_iter().map(move |d| self.with_commands(d))
+    }
+
+    /// Keeps only the part of this path between the fractions `start` and `end` of its length.
+    ///
+    /// Both fractions are clamped to ``[0, 1]``; the result is empty when `start` isn't less than `end`.
+    /// Trimming is meant to animate drawing a line, or to draw a part of a shape.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::shapes::Path;
+    /// let circle = Path::from_str("c", "M 10 0 A 10 10 0 0 1 -10 0 A 10 10 0 0 1 10 0 Z").unwrap();
+    /// let quarter = circle.trim(0.0, 0.25);
+    /// assert!((quarter.length() - 5.0 * std::f32::consts::PI).abs() < 0.01);
+    /// ```
+    pub fn trim(&self, start: f32, end: f32) -> Path {
+        let (start, end) = (start.clamp(0.0, 1.0) as f64, end.clamp(0.0, 1.0) as f64);
+        if start >= end { return self.with_commands(vec![]); }
+        let subs = subpaths(self);
+        let total = total_length(&subs);
+
+        self.with_pieces(extract(&subs, start * total, end * total))
+    }
+
+    /// Cuts this path into dashes, each becoming a separate sub-path.
+    ///
+    /// The pattern lists alternating lengths of dashes and gaps, as the SVG ``stroke-dasharray``
+    /// property: a pattern with an odd number of entries is repeated twice, and the pattern restarts
+    /// at every sub-path. Curves stay curves. An empty, negative or all-zero pattern leaves the path solid.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::shapes::{Path, PathCommand};
+    /// let p = Path::from_str("p", "M 0 0 H 10").unwrap();
+    /// let dashed = p.dash(&[3.0, 1.0]);
+    /// assert_eq!(dashed.d, vec![PathCommand::MoveTo(0.0, 0.0), PathCommand::LineTo(3.0, 0.0),
+    ///     PathCommand::MoveTo(4.0, 0.0), PathCommand::LineTo(7.0, 0.0),
+    ///     PathCommand::MoveTo(8.0, 0.0), PathCommand::LineTo(10.0, 0.0)]);
+    /// ```
+    pub fn dash(&self, pattern: &[f32]) -> Path {
+        if pattern.iter().any(|&v| v < 0.0) || pattern.iter().all(|&v| v == 0.0) { return self.clone(); }
+        let mut pattern: Vec<f64> = pattern.iter().map(|&v| v as f64).collect();
+        if pattern.len() % 2 == 1 { pattern.extend(pattern.clone()); }
+        let period: f64 = pattern.iter().sum();
+
+        let mut pieces = vec![];
+        for sp in subpaths(self) {
+            let length: f64 = sp.segments.iter().map(Segment::length).sum();
+            let single = [sp];
+            let (mut s, mut i) = (0.0, 0);
+            while s < length {
+                let next = s + pattern[i];
+                if i % 2 == 0 && pattern[i] > 0.0 {
+                    pieces.extend(extract(&single, s, next.min(length)).into_iter().map(|mut p| { p.closed = false; p }));
+                }
+                s = next;
+                i = (i + 1) % pattern.len();
+                // ---------- guard against patterns too short to make progress in floating point
+                if i == 0 && s + period == s { break; }
+            }
+        }
+
+        self.with_pieces(pieces)
+    }
+
+    fn with_pieces(&self, pieces: Vec<Piece>) -> Path {
+        let mut d = vec![];
+        for piece in pieces.iter().filter(|p| !p.segments.is_empty()) { write_piece(piece, &mut d); }
+
+        self.with_commands(d)
+    }
+}
+
+impl Segment {
+    /// The same segment traversed from its end to its start
+    pub fn reversed(&self) -> Segment {
+        match *self {
+            Segment::Line(a, b) => Segment::Line(b, a),
+            Segment::Cubic(a, b, c, d) => Segment::Cubic(d, c, b, a),
+        }
+    }
+
+    /// Part of this segment between the arc lengths `s0` and `s1` from its start
+    fn portion(&self, s0: f64, s1: f64) -> Segment {
+        let (t0, t1) = (self.t_at_length(s0), self.t_at_length(s1));
+        let head = if t1 >= 1.0 { *self } else { self.split(t1).0 };
+        if t0 <= 0.0 || t1 <= 0.0 { head } else { head.split((t0 / t1).min(1.0)).1 }
+    }
+}
+
+fn total_length(subs: &[SubPath]) -> f64 {
+    subs.iter().flat_map(|sp| sp.segments.iter()).map(Segment::length).sum()
+}
+
+/// Parts of the sub-paths found between the distances `s0` and `s1` along all of them
+fn extract(subs: &[SubPath], s0: f64, s1: f64) -> Vec<Piece> {
+    let mut pieces = vec![];
+    let mut offset = 0.0;
+    for sp in subs {
+        let mut piece = Piece { segments: vec![], closed: false };
+        let mut complete = true;
+        for seg in &sp.segments {
+            let length = seg.length();
+            let (a, b) = (offset, offset + length);
+            offset = b;
+            if b <= s0 || a >= s1 {
+                complete = false;
+                continue;
+            }
+            if a >= s0 && b <= s1 {
+                piece.segments.push(*seg);
+            } else {
+                complete = false;
+                let (from, to) = ((s0 - a).max(0.0), (s1 - a).min(length));
+                if to > from { piece.segments.push(seg.portion(from, to)); }
+            }
+        }
+        piece.closed = sp.closed && complete;
+        if !piece.segments.is_empty() { pieces.push(piece); }
+    }
+
+    pieces
+}
+
+fn write_piece(piece: &Piece, d: &mut Vec<PathCommand>) {
+    let f = |v: f64| v as f32;
+    let start = piece.segments[0].start();
+    d.push(PathCommand::MoveTo(f(start[0]), f(start[1])));
+    let mut segments = piece.segments.as_slice();
+    // ---------- a closing line is implied by the ``Z`` command
+    if piece.closed {
+        if let Some(Segment::Line(_, end)) = segments.last() {
+            if *end == start { segments = &segments[..segments.len() - 1]; }
+        }
+    }
+    for seg in segments {
+        d.push(match *seg {
+            Segment::Line(_, b) => PathCommand::LineTo(f(b[0]), f(b[1])),
+            Segment::Cubic(_, c1, c2, e) => PathCommand::CurveTo(f(c1[0]), f(c1[1]), f(c2[0]), f(c2[1]), f(e[0]), f(e[1])),
+        });
+    }
+    if piece.closed { d.push(PathCommand::Close); }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shapes::{Path, PathCommand};
+
+    #[test]
+    fn reverse_closed_and_curved() {
+        let p = Path::from_str("p", "M 0 0 C 0 10 10 10 10 0 Z M 20 0 L 30 0").unwrap();
+        assert_eq!(p.reverse().d, vec![PathCommand::MoveTo(30.0, 0.0), PathCommand::LineTo(20.0, 0.0),
+            PathCommand::MoveTo(0.0, 0.0), PathCommand::LineTo(10.0, 0.0),
+            PathCommand::CurveTo(10.0, 10.0, 0.0, 10.0, 0.0, 0.0), PathCommand::Close]);
+        assert_eq!(p.reverse().reverse().length(), p.length());
+    }
+
+    #[test]
+    fn trim_and_split_preserve_length() {
+        let p = Path::from_str("p", "M 0 0 Q 50 80 100 0 T 200 0 M 0 100 A 30 20 0 1 0 60 100").unwrap();
+        let total = p.length();
+        for s in [0.0, 10.0, total / 3.0, total / 2.0, total - 1.0, total] {
+            let (a, b) = p.split_at_length(s);
+            assert!((a.length() - s).abs() < 1e-2 && (b.length() - (total - s)).abs() < 1e-2, "split at {}", s);
+        }
+        let middle = p.trim(0.25, 0.75);
+        assert!((middle.length() - total / 2.0).abs() < 1e-2);
+        let start = p.point_at_length(total * 0.25).unwrap();
+        let got = middle.point_at_length(0.0).unwrap();
+        assert!(start.distance(got) < 1e-3);
+        assert!(p.trim(0.6, 0.4).d.is_empty());
+        assert_eq!(p.trim(0.0, 1.0).d.iter().filter(|c| matches!(c, PathCommand::MoveTo(..))).count(), 2);
+    }
+
+    #[test]
+    fn dashes_and_subpaths() {
+        let square = Path::from_str("s", "M 0 0 h 10 v 10 h -10 z").unwrap();
+        let dashed = square.dash(&[5.0]);
+        assert_eq!(dashed.subpaths().count(), 4);
+        assert!((dashed.length() - 20.0).abs() < 1e-4);
+        assert_eq!(square.dash(&[0.0, 0.0]).d, square.d);
+        assert_eq!(square.subpaths().count(), 1);
+        let p = Path::from_str("p", "m 5 5 h 5 z l 1 1").unwrap();
+        let subs: Vec<Path> = p.subpaths().collect();
+        assert_eq!(subs[1].d, vec![PathCommand::MoveTo(5.0, 5.0), PathCommand::LineBy(1.0, 1.0)]);
+    }
+}
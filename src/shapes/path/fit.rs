@@ -0,0 +1,290 @@
+//! Smooth cubic curves through or near given points.
+use crate::geometry::Point;
+use crate::shapes::path::segments::{dist, from_point, lerp, sub, Pt, Segment};
+use crate::shapes::Path;
+
+/// Maximum number of Newton reparameterization rounds in [`Path::fit_cubic()`]
+const MAX_REPARAMETERIZATIONS: usize = 4;
+
+impl Path {
+    /// Cardinal spline through the given points, made of cubic Béziers.
+    ///
+    /// `tension` of 0 gives a Catmull-Rom spline and 1 gives straight segments; values in between
+    /// tighten the curve. A closed spline also joins the last point back to the first one smoothly.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::geometry::Point;
+    /// use visualife::shapes::{Path, PathCommand};
+    /// let points = [(0.0, 0.0), (6.0, 6.0), (12.0, 0.0)].map(Point::from);
+    /// let p = Path::catmull_rom("s", &points, 0.0, false);
+    /// assert_eq!(p.d.len(), 3);
+    /// assert_eq!(p.d[1], PathCommand::CurveTo(1.0, 1.0, 4.0, 6.0, 6.0, 6.0));
+    /// ```
+    pub fn catmull_rom(id: &str, points: &[Point], tension: f32, closed: bool) -> Path {
+        let mut p = Path::new(id);
+        let n = points.len();
+        if n == 0 { return p; }
+        p = p.move_to(points[0].x, points[0].y);
+        let k = (1.0 - tension) / 6.0;
+        let at = |i: isize| -> Point {
+            if closed { points[i.rem_euclid(n as isize) as usize] } else { points[i.clamp(0, n as isize - 1) as usize] }
+        };
+        let segments = if closed { n } else { n - 1 };
+        for i in 0..segments as isize {
+            let (p0, p1, p2, p3) = (at(i - 1), at(i), at(i + 1), at(i + 2));
+            let (c1, c2) = (p1 + (p2 - p0) * k, p2 - (p3 - p1) * k);
+            p = p.curve_to(c1.x, c1.y, c2.x, c2.y, p2.x, p2.y);
+        }
+        if closed { p = p.close(); }
+
+        p
+    }
+
+    /// Monotone cubic interpolation of the function sampled at the given points.
+    ///
+    /// Points are sorted by their x coordinate, and points repeating an x are skipped. The curve
+    /// (Fritsch–Carlson) passes through every point and never overshoots: it's monotone wherever the data
+    /// is, which makes it the right choice for plotted data series.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::geometry::Point;
+    /// use visualife::shapes::Path;
+    /// let p = Path::monotone_cubic("f", &[(0.0, 0.0), (1.0, 1.0), (2.0, 1.0), (3.0, 5.0)].map(Point::from));
+    /// // ---------- flat between x = 1 and x = 2, as the data
+    /// let ys: Vec<f32> = p.sample(31).iter().filter(|p| p.x > 1.0 && p.x < 2.0).map(|p| p.y).collect();
+    /// assert!(ys.iter().all(|&y| (y - 1.0).abs() < 1e-4));
+    /// ```
+    pub fn monotone_cubic(id: &str, points: &[Point]) -> Path {
+        let mut pts: Vec<(f64, f64)> = points.iter().map(|p| (p.x as f64, p.y as f64)).collect();
+        pts.sort_by(|a, b| a.0.total_cmp(&b.0));
+        pts.dedup_by(|a, b| a.0 == b.0);
+        let n = pts.len();
+        let mut p = Path::new(id);
+        if n == 0 { return p; }
+        p = p.move_to(pts[0].0 as f32, pts[0].1 as f32);
+        if n == 1 { return p; }
+
+        let h: Vec<f64> = (0..n - 1).map(|i| pts[i + 1].0 - pts[i].0).collect();
+        let delta: Vec<f64> = (0..n - 1).map(|i| (pts[i + 1].1 - pts[i].1) / h[i]).collect();
+        // ---------- initial tangents, then limited so that no segment overshoots
+        let mut m = vec![0.0; n];
+        m[0] = delta[0];
+        m[n - 1] = delta[n - 2];
+        for i in 1..n - 1 {
+            m[i] = if delta[i - 1] * delta[i] <= 0.0 { 0.0 } else { (delta[i - 1] + delta[i]) / 2.0 };
+        }
+        for i in 0..n - 1 {
+            if delta[i] == 0.0 {
+                m[i] = 0.0;
+                m[i + 1] = 0.0;
+                continue;
+            }
+            let (a, b) = (m[i] / delta[i], m[i + 1] / delta[i]);
+            let s = a * a + b * b;
+            if s > 9.0 {
+                let t = 3.0 / s.sqrt();
+                m[i] = t * a * delta[i];
+                m[i + 1] = t * b * delta[i];
+            }
+        }
+        for i in 0..n - 1 {
+            let ((x0, y0), (x1, y1)) = (pts[i], pts[i + 1]);
+            let third = h[i] / 3.0;
+            p = p.curve_to((x0 + third) as f32, (y0 + m[i] * third) as f32,
+                           (x1 - third) as f32, (y1 - m[i + 1] * third) as f32, x1 as f32, y1 as f32);
+        }
+
+        p
+    }
+
+    /// Smooth cubic Bézier path approximating the given points by least squares.
+    ///
+    /// Implements the algorithm of Philip J. Schneider (Graphics Gems, 1990): the points are
+    /// parametrized by chord length and fitted with a single cubic, which is split at the worst
+    /// point until no point is farther than `max_error` from the curve. The curve starts at the first
+    /// point and ends at the last one; consecutive Béziers meet with continuous tangents.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::geometry::{Point, Radians};
+    /// use visualife::shapes::Path;
+    /// let arc: Vec<Point> = (0..=100)
+    ///     .map(|i| Point::from_polar(Point::ORIGIN, 100.0, Radians(i as f32 * std::f32::consts::PI / 100.0)))
+    ///     .collect();
+    /// let p = Path::fit_cubic("arc", &arc, 0.5);
+    /// assert!(p.d.len() <= 4);
+    /// ```
+    pub fn fit_cubic(id: &str, points: &[Point], max_error: f32) -> Path {
+        let mut pts: Vec<Pt> = points.iter().copied().map(from_point).collect();
+        pts.dedup();
+        let mut p = Path::new(id);
+        if pts.is_empty() { return p; }
+        p = p.move_to(pts[0][0] as f32, pts[0][1] as f32);
+        if pts.len() == 1 { return p; }
+
+        let n = pts.len();
+        let start_tangent = normalized(sub(pts[1], pts[0]));
+        let end_tangent = normalized(sub(pts[n - 2], pts[n - 1]));
+        let mut curves = vec![];
+        fit_range(&pts, start_tangent, end_tangent, (max_error as f64).max(1e-6), &mut curves);
+        for c in curves {
+            if let Segment::Cubic(_, c1, c2, e) = c {
+                p = p.curve_to(c1[0] as f32, c1[1] as f32, c2[0] as f32, c2[1] as f32, e[0] as f32, e[1] as f32);
+            }
+        }
+
+        p
+    }
+}
+
+fn normalized(v: Pt) -> Pt {
+    let len = v[0].hypot(v[1]);
+    if len == 0.0 { v } else { [v[0] / len, v[1] / len] }
+}
+
+fn dot(a: Pt, b: Pt) -> f64 { a[0] * b[0] + a[1] * b[1] }
+
+fn scaled(p: Pt, v: Pt, s: f64) -> Pt { [p[0] + v[0] * s, p[1] + v[1] * s] }
+
+/// Fits `pts` with cubics leaving the first point along `t1` and arriving at the last one from `t2`
+fn fit_range(pts: &[Pt], t1: Pt, t2: Pt, max_error: f64, out: &mut Vec<Segment>) {
+    let n = pts.len();
+    if n == 2 {
+        let d = dist(pts[0], pts[1]) / 3.0;
+        out.push(Segment::Cubic(pts[0], scaled(pts[0], t1, d), scaled(pts[1], t2, d), pts[1]));
+        return;
+    }
+
+    let mut u = chord_length_parameters(pts);
+    let mut curve = generate_bezier(pts, &u, t1, t2);
+    let (mut error, mut split) = max_deviation(pts, &curve, &u);
+    if error < max_error {
+        out.push(curve);
+        return;
+    }
+    // ---------- a moderate error is often fixed by a better parametrization
+    if error < 4.0 * max_error {
+        for _ in 0..MAX_REPARAMETERIZATIONS {
+            u = reparameterize(pts, &curve, &u);
+            curve = generate_bezier(pts, &u, t1, t2);
+            (error, split) = max_deviation(pts, &curve, &u);
+            if error < max_error {
+                out.push(curve);
+                return;
+            }
+        }
+    }
+
+    let center = normalized(sub(pts[split - 1], pts[split + 1]));
+    fit_range(&pts[..=split], t1, center, max_error, out);
+    fit_range(&pts[split..], [-center[0], -center[1]], t2, max_error, out);
+}
+
+fn chord_length_parameters(pts: &[Pt]) -> Vec<f64> {
+    let mut u = vec![0.0; pts.len()];
+    for i in 1..pts.len() { u[i] = u[i - 1] + dist(pts[i], pts[i - 1]); }
+    let total = u[pts.len() - 1];
+    u.iter().map(|v| v / total).collect()
+}
+
+/// Least-squares cubic with fixed end points and end tangent directions
+fn generate_bezier(pts: &[Pt], u: &[f64], t1: Pt, t2: Pt) -> Segment {
+    let (first, last) = (pts[0], pts[pts.len() - 1]);
+    let (mut c, mut x) = ([[0.0; 2]; 2], [0.0; 2]);
+    for (p, &t) in pts.iter().zip(u) {
+        let s = 1.0 - t;
+        let (a1, a2) = (scaled([0.0, 0.0], t1, 3.0 * s * s * t), scaled([0.0, 0.0], t2, 3.0 * s * t * t));
+        c[0][0] += dot(a1, a1);
+        c[0][1] += dot(a1, a2);
+        c[1][1] += dot(a2, a2);
+        let on_chord = lerp(first, last, t * t * (3.0 - 2.0 * t));
+        let tmp = sub(*p, on_chord);
+        x[0] += dot(a1, tmp);
+        x[1] += dot(a2, tmp);
+    }
+    c[1][0] = c[0][1];
+    let det = c[0][0] * c[1][1] - c[0][1] * c[1][0];
+    let (mut alpha1, mut alpha2) = if det.abs() > 1e-12 {
+        ((x[0] * c[1][1] - x[1] * c[0][1]) / det, (c[0][0] * x[1] - c[1][0] * x[0]) / det)
+    } else { (0.0, 0.0) };
+    // ---------- fall back to the Wu/Barsky heuristic when the solution is degenerate
+    let seg_length = dist(first, last);
+    if alpha1 < 1e-6 * seg_length || alpha2 < 1e-6 * seg_length {
+        alpha1 = seg_length / 3.0;
+        alpha2 = alpha1;
+    }
+
+    Segment::Cubic(first, scaled(first, t1, alpha1), scaled(last, t2, alpha2), last)
+}
+
+/// Largest distance between a point and its counterpart on the curve, and the index of that point
+fn max_deviation(pts: &[Pt], curve: &Segment, u: &[f64]) -> (f64, usize) {
+    let mut worst = (0.0, pts.len() / 2);
+    for i in 1..pts.len() - 1 {
+        let d = dist(curve.point(u[i]), pts[i]);
+        if d > worst.0 { worst = (d, i); }
+    }
+
+    worst
+}
+
+/// Improves the parameters with one Newton step towards the nearest point of the curve
+fn reparameterize(pts: &[Pt], curve: &Segment, u: &[f64]) -> Vec<f64> {
+    let Segment::Cubic(p0, p1, p2, p3) = *curve else { return u.to_vec(); };
+    // ---------- control points of the second derivative
+    let second = |t: f64| -> Pt {
+        let a = [6.0 * (p2[0] - 2.0 * p1[0] + p0[0]), 6.0 * (p2[1] - 2.0 * p1[1] + p0[1])];
+        let b = [6.0 * (p3[0] - 2.0 * p2[0] + p1[0]), 6.0 * (p3[1] - 2.0 * p2[1] + p1[1])];
+        lerp(a, b, t)
+    };
+    pts.iter().zip(u).map(|(p, &t)| {
+        let diff = sub(curve.point(t), *p);
+        let (d1, d2) = (curve.derivative(t), second(t));
+        let denominator = dot(d1, d1) + dot(diff, d2);
+        if denominator.abs() < 1e-12 { t } else { (t - dot(diff, d1) / denominator).clamp(0.0, 1.0) }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::Point;
+    use crate::shapes::path::flatten::distance_to_segment;
+    use crate::shapes::path::segments::from_point;
+    use crate::shapes::Path;
+
+    #[test]
+    fn splines_pass_through_points() {
+        let pts = [(0.0, 0.0), (10.0, 5.0), (20.0, -5.0), (30.0, 0.0)].map(Point::from);
+        for tension in [0.0, 0.5, 1.0] {
+            let p = Path::catmull_rom("s", &pts, tension, true);
+            assert_eq!(p.d.len(), 6);
+            assert!((p.length() > 0.0));
+        }
+        let mono = Path::monotone_cubic("m", &[(3.0, 2.0), (0.0, 0.0), (1.0, 1.0), (2.0, 1.5)].map(Point::from));
+        let samples = mono.sample(50);
+        assert!(samples.windows(2).all(|w| w[1].y >= w[0].y - 1e-5), "not monotone");
+        assert_eq!(samples.last(), Some(&Point::new(3.0, 2.0)));
+    }
+
+    #[test]
+    fn least_squares_fit() {
+        let wave: Vec<Point> = (0..=200).map(|i| {
+            let x = i as f32 * 0.05;
+            Point::new(x * 10.0, 20.0 * x.sin())
+        }).collect();
+        for max_error in [1.0, 0.1] {
+            let p = Path::fit_cubic("w", &wave, max_error);
+            // ---------- every input point lies close to the fitted curve
+            let curve = p.flatten(0.01);
+            let pts: Vec<[f64; 2]> = curve[0].points.iter().copied().map(from_point).collect();
+            for &p in wave.iter().step_by(7) {
+                let d = pts.windows(2).map(|w| distance_to_segment(from_point(p), w[0], w[1]) as f32)
+                    .fold(f32::MAX, f32::min);
+                assert!(d < max_error + 0.05, "{:?} is {} away", p, d);
+            }
+        }
+        assert!(Path::fit_cubic("w", &wave, 1.0).d.len() < Path::fit_cubic("w", &wave, 0.1).d.len());
+    }
+}
@@ -0,0 +1,165 @@
+//! Approximation of paths by polylines.
+use crate::shapes::path::normalize::arc_center;
+use crate::geometry::Point;
+use crate::shapes::path::segments::{dist, sub, to_point, Pt, Segment};
+use crate::shapes::{Path, PathCommand};
+
+/// Smallest tolerance accepted by [`Path::flatten()`]
+const MIN_TOLERANCE: f32 = 1e-4;
+
+/// Maximum depth of the subdivision of a single curve
+const MAX_DEPTH: u32 = 24;
+
+/// A sequence of points connected with straight lines
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polyline {
+    pub points: Vec<Point>,
+    /// true if the last point connects back to the first one; the first point is not repeated at the end
+    pub closed: bool,
+}
+
+impl Path {
+    /// Approximates this path by polylines, one for every sub-path.
+    ///
+    /// No point of the path is farther than `tolerance` from the returned polylines, and all the
+    /// returned points lie on the path. Curves are subdivided adaptively, so flat parts of a curve
+    /// produce few points. Elliptical arcs are flattened directly rather than through their
+    /// approximation by Béziers. Tolerances smaller than 0.0001 are raised to that value.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::geometry::Point;
+    /// use visualife::shapes::Path;
+    /// let p = Path::from_str("p", "M 0 0 L 10 0 Q 20 0 20 10 Z M 30 30 h 5").unwrap();
+    /// let polylines = p.flatten(0.1);
+    /// assert_eq!(polylines.len(), 2);
+    /// assert!(polylines[0].closed && !polylines[1].closed);
+    /// assert_eq!(polylines[1].points, vec![Point::new(30.0, 30.0), Point::new(35.0, 30.0)]);
+    /// ```
+    pub fn flatten(&self, tolerance: f32) -> Vec<Polyline> {
+        let tol = tolerance.max(MIN_TOLERANCE) as f64;
+        let mut out: Vec<Polyline> = vec![];
+        let mut current: Pt = [0.0, 0.0];
+        let mut start: Pt = [0.0, 0.0];
+        let mut points: Vec<Pt> = vec![];
+        let finish = |points: &mut Vec<Pt>, closed: bool, out: &mut Vec<Polyline>| {
+            if closed && points.len() > 1 && points.first() == points.last() { points.pop(); }
+            if !points.is_empty() {
+                out.push(Polyline { points: points.iter().copied().map(to_point).collect(), closed });
+            }
+            points.clear();
+        };
+
+        for command in self.expand_shorthands().d {
+            let to_pt = |x: f32, y: f32| [x as f64, y as f64];
+            if points.is_empty() && !matches!(command, PathCommand::MoveTo(..) | PathCommand::Close) {
+                points.push(current);
+            }
+            match command {
+                PathCommand::MoveTo(x, y) => {
+                    finish(&mut points, false, &mut out);
+                    current = to_pt(x, y);
+                    start = current;
+                    points.push(current);
+                    continue;
+                }
+                PathCommand::LineTo(x, y) => points.push(to_pt(x, y)),
+                PathCommand::CurveTo(x1, y1, x2, y2, x, y) => {
+                    let s = Segment::Cubic(current, to_pt(x1, y1), to_pt(x2, y2), to_pt(x, y));
+                    flatten_cubic(&s, tol, 0, &mut points);
+                }
+                PathCommand::QuadraticBezierCurveTo(qx, qy, x, y) => {
+                    let (q, p) = (to_pt(qx, qy), to_pt(x, y));
+                    let c1 = [current[0] + 2.0 / 3.0 * (q[0] - current[0]), current[1] + 2.0 / 3.0 * (q[1] - current[1])];
+                    let c2 = [p[0] + 2.0 / 3.0 * (q[0] - p[0]), p[1] + 2.0 / 3.0 * (q[1] - p[1])];
+                    flatten_cubic(&Segment::Cubic(current, c1, c2, p), tol, 0, &mut points);
+                }
+                PathCommand::EllipticalArcTo(rx, ry, rot, large, sweep, x, y) => {
+                    match arc_center(current[0] as f32, current[1] as f32, rx, ry, rot, large, sweep, x, y) {
+                        Some(arc) => {
+                            // ---------- a chord spanning angle a deviates from a circle of radius r by r(1 - cos(a/2));
+                            // ---------- an ellipse deviates less than the circle of its larger radius
+                            let r = arc.rx.max(arc.ry);
+                            let max_step = 2.0 * (1.0 - tol / r).clamp(-1.0, 1.0).acos();
+                            let n = (arc.delta.abs() / max_step.max(1e-6)).ceil().clamp(1.0, 1e6) as usize;
+                            for i in 1..n {
+                                let (px, py) = arc.point(arc.theta + arc.delta * i as f64 / n as f64);
+                                points.push([px, py]);
+                            }
+                            points.push(to_pt(x, y));
+                        }
+                        None => points.push(to_pt(x, y)),
+                    }
+                }
+                PathCommand::Close => {
+                    finish(&mut points, true, &mut out);
+                    current = start;
+                    continue;
+                }
+                _ => unreachable!("expand_shorthands() leaves only absolute M, L, C, Q, A and Z commands"),
+            }
+            current = *points.last().unwrap();
+        }
+        finish(&mut points, false, &mut out);
+
+        out
+    }
+}
+
+/// Distance from point `p` to the line segment `ab`
+pub(crate) fn distance_to_segment(p: Pt, a: Pt, b: Pt) -> f64 {
+    let ab = sub(b, a);
+    let len2 = ab[0] * ab[0] + ab[1] * ab[1];
+    if len2 == 0.0 { return dist(p, a); }
+    let ap = sub(p, a);
+    let t = ((ap[0] * ab[0] + ap[1] * ab[1]) / len2).clamp(0.0, 1.0);
+    dist(p, [a[0] + t * ab[0], a[1] + t * ab[1]])
+}
+
+/// Appends the end points of the chords approximating a cubic, without its start point.
+///
+/// The curve lies within the convex hull of its control points, so it's closer to the chord than
+/// the farther of its two inner control points; a curve is subdivided until that distance is small enough.
+fn flatten_cubic(s: &Segment, tol: f64, depth: u32, out: &mut Vec<Pt>) {
+    if let Segment::Cubic(p0, p1, p2, p3) = *s {
+        let flatness = distance_to_segment(p1, p0, p3).max(distance_to_segment(p2, p0, p3));
+        if flatness <= tol || depth >= MAX_DEPTH {
+            out.push(p3);
+        } else {
+            let (a, b) = s.split(0.5);
+            flatten_cubic(&a, tol, depth + 1, out);
+            flatten_cubic(&b, tol, depth + 1, out);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::Point;
+    use crate::shapes::Path;
+
+    #[test]
+    fn flatten_within_tolerance() {
+        let circle = Path::from_str("c", "M 0 -100 A 100 100 0 1 1 0 100 A 100 100 0 1 1 0 -100 Z").unwrap();
+        for tol in [1.0, 0.1, 0.01] {
+            let polylines = circle.flatten(tol);
+            assert_eq!(polylines.len(), 1);
+            let pts = &polylines[0].points;
+            assert!(polylines[0].closed);
+            for (i, &Point { x, y }) in pts.iter().enumerate() {
+                // --- vertices lie on the circle and chord midpoints are within tolerance
+                assert!(((x * x + y * y).sqrt() - 100.0).abs() < 1e-3);
+                let Point { x: nx, y: ny } = pts[(i + 1) % pts.len()];
+                let mid = (((x + nx) / 2.0).powi(2) + ((y + ny) / 2.0).powi(2)).sqrt();
+                assert!(100.0 - mid <= tol + 1e-3, "deviation {} > {}", 100.0 - mid, tol);
+            }
+        }
+
+        let curve = Path::from_str("q", "M 0 0 C 0 100 100 100 100 0").unwrap();
+        let coarse = curve.flatten(1.0);
+        let fine = curve.flatten(0.01);
+        assert!(coarse[0].points.len() < fine[0].points.len());
+        assert_eq!(coarse[0].points.first(), Some(&Point::new(0.0, 0.0)));
+        assert_eq!(coarse[0].points.last(), Some(&Point::new(100.0, 0.0)));
+    }
+}
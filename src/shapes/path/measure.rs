@@ -0,0 +1,149 @@
+//! Arc-length parametrization of paths.
+use crate::geometry::{Point, Vector};
+use crate::shapes::path::segments::{subpaths, to_point, Segment};
+use crate::shapes::Path;
+
+/// Arc-length parametrization of a path, for repeated queries by distance along it.
+///
+/// Lengths of all the segments are computed once, when the measure is created; the methods of [`Path`]
+/// that take a distance build a new measure on every call, so use this one to query a path many times.
+///
+/// # Example
+/// ```
+/// use visualife::geometry::Point;
+/// use visualife::shapes::Path;
+/// let p = Path::from_str("p", "M 0 0 C 0 10 10 10 10 0").unwrap();
+/// let measure = p.measure();
+/// let points: Vec<Point> = (0..=10).filter_map(|i| measure.point_at_length(i as f32)).collect();
+/// assert_eq!(points[0], Point::new(0.0, 0.0));
+/// assert_eq!(measure.point_at_length(5.0), p.point_at_length(5.0));
+/// ```
+pub struct PathMeasure {
+    /// segments of all sub-paths, with the distance along the path at which each of them ends
+    segments: Vec<(Segment, f64)>,
+    /// length of every segment
+    lengths: Vec<f64>,
+}
+
+impl PathMeasure {
+    pub fn new(path: &Path) -> Self {
+        let mut total = 0.0;
+        let (mut segments, mut lengths) = (vec![], vec![]);
+        for sp in subpaths(path) {
+            for s in sp.segments {
+                let length = s.length();
+                total += length;
+                segments.push((s, total));
+                lengths.push(length);
+            }
+        }
+        PathMeasure { segments, lengths }
+    }
+
+    /// Total length of the measured path
+    pub fn length(&self) -> f64 { self.segments.last().map_or(0.0, |s| s.1) }
+
+    /// Point at distance `s` along the path, see [`Path::point_at_length()`]
+    pub fn point_at_length(&self, s: f32) -> Option<Point> {
+        self.locate(s as f64).map(|(seg, t)| to_point(seg.point(t)))
+    }
+
+    /// Unit tangent at distance `s` along the path, see [`Path::tangent_at_length()`]
+    pub fn tangent_at_length(&self, s: f32) -> Option<Vector> {
+        self.locate(s as f64).map(|(seg, t)| to_point(seg.tangent(t)).to_vector())
+    }
+
+    /// Unit normal at distance `s` along the path, see [`Path::normal_at_length()`]
+    pub fn normal_at_length(&self, s: f32) -> Option<Vector> {
+        self.tangent_at_length(s).map(Vector::perpendicular)
+    }
+
+    /// `n` points evenly spaced along the path, see [`Path::sample()`]
+    pub fn sample(&self, n: usize) -> Vec<Point> {
+        let step = if n > 1 { self.length() / (n - 1) as f64 } else { 0.0 };
+        (0..n).filter_map(|i| self.locate(step * i as f64).map(|(seg, t)| to_point(seg.point(t)))).collect()
+    }
+
+    /// All segments, each with the distance along the path at which it ends
+    pub(crate) fn segments(&self) -> &[(Segment, f64)] { &self.segments }
+
+    /// Distance along the path at which the `i`-th segment starts
+    pub(crate) fn start_of(&self, i: usize) -> f64 { self.segments[i].1 - self.lengths[i] }
+
+    /// Segment and its parameter found at distance `s` along the path; `s` is clamped to the path length
+    pub(crate) fn locate(&self, s: f64) -> Option<(Segment, f64)> {
+        let s = s.clamp(0.0, self.length());
+        let i = self.segments.partition_point(|(_, end)| *end < s).min(self.segments.len().checked_sub(1)?);
+        let segment = self.segments[i].0;
+        Some((segment, segment.t_at_length_within(s - self.start_of(i), self.lengths[i])))
+    }
+}
+
+impl Path {
+    /// Arc-length parametrization of this path, for many queries by distance along it
+    pub fn measure(&self) -> PathMeasure { PathMeasure::new(self) }
+
+    /// Total length of this path; gaps between sub-paths are not counted.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::shapes::Path;
+    /// let circle = Path::from_str("c", "M 0 10 A 10 10 0 1 1 0 -10 A 10 10 0 1 1 0 10").unwrap();
+    /// assert!((circle.length() - 20.0 * std::f32::consts::PI).abs() < 0.01);
+    /// ```
+    pub fn length(&self) -> f32 { self.measure().length() as f32 }
+
+    /// Point at distance `s` along this path.
+    ///
+    /// `s` is clamped to ``[0, length()]``; returns `None` for a path that has no segments.
+    pub fn point_at_length(&self, s: f32) -> Option<Point> { self.measure().point_at_length(s) }
+
+    /// Unit vector tangent to this path at distance `s`, pointing in the direction of the path
+    pub fn tangent_at_length(&self, s: f32) -> Option<Vector> { self.measure().tangent_at_length(s) }
+
+    /// Unit vector normal to this path at distance `s`.
+    ///
+    /// The normal is the tangent rotated by 90 degrees clockwise on the screen (where the y axis
+    /// points down), i.e. it points to the right-hand side of the direction of the path.
+    pub fn normal_at_length(&self, s: f32) -> Option<Vector> { self.measure().normal_at_length(s) }
+
+    /// Returns `n` points evenly spaced along this path, including both its ends.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::geometry::Point;
+    /// use visualife::shapes::Path;
+    /// let p = Path::new("p").move_to(0.0, 0.0).line_to(10.0, 0.0).line_to(10.0, 10.0);
+    /// let expected = [(0.0, 0.0), (5.0, 0.0), (10.0, 0.0), (10.0, 5.0), (10.0, 10.0)].map(Point::from);
+    /// assert_eq!(p.sample(5), expected);
+    /// ```
+    pub fn sample(&self, n: usize) -> Vec<Point> { self.measure().sample(n) }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::{Point, Vector};
+    use crate::shapes::Path;
+
+    #[test]
+    fn measure_path() {
+        let p = Path::new("p").move_to(0.0, 0.0).line_to(10.0, 0.0)
+            .move_to(0.0, 10.0).curve_to(0.0, 10.0, 10.0, 10.0, 10.0, 10.0);
+        assert!((p.length() - 20.0).abs() < 1e-4);
+        assert_eq!(p.point_at_length(-1.0), Some(Point::new(0.0, 0.0)));
+        assert_eq!(p.point_at_length(100.0), Some(Point::new(10.0, 10.0)));
+        let Point { x, y } = p.point_at_length(15.0).unwrap();
+        assert!((x - 5.0).abs() < 1e-4 && y == 10.0);
+        assert_eq!(p.tangent_at_length(5.0), Some(Vector::new(1.0, 0.0)));
+        assert_eq!(p.normal_at_length(5.0), Some(Vector::new(-0.0, 1.0)));
+        // --- the tangent is defined at the ends of a cubic whose control points coincide with its ends
+        let Vector { x: tx, y: ty } = p.tangent_at_length(10.0 + 1e-3).unwrap();
+        assert!((tx - 1.0).abs() < 1e-4 && ty.abs() < 1e-4);
+
+        let closed = Path::from_str("sq", "M 0 0 h 10 v 10 h -10 z").unwrap();
+        assert_eq!(closed.length(), 40.0);
+        assert_eq!(closed.point_at_length(35.0), Some(Point::new(0.0, 5.0)));
+        assert_eq!(Path::new("empty").point_at_length(1.0), None);
+        assert!(Path::new("empty").sample(3).is_empty());
+    }
+}
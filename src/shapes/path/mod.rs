@@ -0,0 +1,210 @@
+use std::fmt::{Display, Formatter};
+use crate::draw_svg::{close_element, escape, push_attributes, push_class, ToSvg};
+use crate::geometry::{BBox, Point};
+use crate::style::Style;
+use parse::parse_path_data;
+
+mod boolean;
+mod compact;
+mod edit;
+mod fit;
+mod flatten;
+mod measure;
+mod normalize;
+mod parse;
+mod query;
+mod segments;
+mod simplify;
+mod stroke;
+mod transform;
+
+pub use boolean::{BooleanOp, FillRule};
+pub use flatten::Polyline;
+pub use measure::PathMeasure;
+pub use parse::PathParseError;
+pub use query::{Intersection, PathLocation};
+pub use stroke::{LineCap, LineJoin};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathCommand {
+    MoveTo(f32, f32),
+    LineTo(f32, f32),
+    HorizontalTo(f32),
+    VerticalTo(f32),
+    CurveTo(f32, f32, f32, f32, f32, f32),
+    SmoothCurveTo(f32, f32, f32, f32),
+    QuadraticBezierCurveTo(f32, f32, f32, f32),
+    SmoothQuadraticBezierCurveTo(f32, f32),
+    EllipticalArcTo(f32, f32, f32, bool, bool, f32, f32),
+    MoveBy(f32, f32),
+    LineBy(f32, f32),
+    HorizontalBy(f32),
+    VerticalBy(f32),
+    CurveBy(f32, f32, f32, f32, f32, f32),
+    SmoothCurveBy(f32, f32, f32, f32),
+    QuadraticBezierCurveBy(f32, f32, f32, f32),
+    SmoothQuadraticBezierCurveBy(f32, f32),
+    EllipticalArcBy(f32, f32, f32, bool, bool, f32, f32),
+    Close,
+}
+
+impl Display for PathCommand {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathCommand::MoveTo(x, y) => write!(f, "M {} {}", x, y),
+            PathCommand::LineTo(x, y) => write!(f, "L {} {}", x, y),
+            PathCommand::HorizontalTo(x) => write!(f, "H {}", x),
+            PathCommand::VerticalTo(y) => write!(f, "V {}", y),
+            PathCommand::CurveTo(x1, y1, x2, y2, x, y) => write!(f, "C {} {} {} {} {} {}", x1, y1, x2, y2, x, y),
+            PathCommand::SmoothCurveTo(x2, y2, x, y) => write!(f, "S {} {} {} {}", x2, y2, x, y),
+            PathCommand::QuadraticBezierCurveTo(x1, y1, x, y) => write!(f, "Q {} {} {} {}", x1, y1, x, y),
+            PathCommand::SmoothQuadraticBezierCurveTo(x, y) => write!(f, "T {} {}", x, y),
+            PathCommand::EllipticalArcTo(rx, ry, x_axis_rotation, large_arc_flag , sweep_flag, x, y)
+                => write!(f, "A {} {} {} {} {} {} {}", rx, ry, x_axis_rotation, (*large_arc_flag as i32), (*sweep_flag as i32), x, y),
+            PathCommand::Close => { write!(f, "Z") },
+            PathCommand::MoveBy(x, y) => write!(f, "m {} {}", x, y),
+            PathCommand::LineBy(x, y) => write!(f, "l {} {}", x, y),
+            PathCommand::HorizontalBy(x) => write!(f, "h {}", x),
+            PathCommand::VerticalBy(y) => write!(f, "v {}", y),
+            PathCommand::CurveBy(x1, y1, x2, y2, x, y) => write!(f, "c {} {} {} {} {} {}", x1, y1, x2, y2, x, y),
+            PathCommand::SmoothCurveBy(x2, y2, x, y) => write!(f, "s {} {} {} {}", x2, y2, x, y),
+            PathCommand::QuadraticBezierCurveBy(x1, y1, x, y) => write!(f, "q {} {} {} {}", x1, y1, x, y),
+            PathCommand::SmoothQuadraticBezierCurveBy(x, y) => write!(f, "t {} {}", x, y),
+            PathCommand::EllipticalArcBy(rx, ry, x_axis_rotation, large_arc_flag, sweep_flag, x, y)
+                => write!(f, "a {} {} {} {} {} {} {}", rx, ry, x_axis_rotation, (*large_arc_flag as i32), (*sweep_flag as i32), x, y),
+        }
+    }
+}
+
+/// A ``<path>`` element
+///
+/// Paths are a series of commands that describe how to draw a shape.
+///
+/// # Example
+/// ```rust
+/// use visualife::shapes::{Path};
+/// use visualife::ToSvg;
+///
+/// let mut p = Path::new("a_path")
+///     .move_to(50.0, 50.0)
+///     .line_to(10.0, 10.0);
+/// assert_eq!(p.to_svg(), r#"<path id="a_path" d="M 50 50 L 10 10" />"#);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Path {
+    pub id: String,
+    pub d: Vec<PathCommand>,
+    pub style: Style,
+    /// CSS class (or space-separated classes) of this path
+    pub class: Option<String>,
+    /// attributes visualife has no field for, such as ``data-*`` ones, written as they are
+    pub attributes: Vec<(String, String)>,
+    /// when set, the path data is written in its shortest form, with numbers rounded to this many
+    /// decimal places; see [`Path::to_compact_d()`]
+    pub compact: Option<u32>,
+}
+
+impl Path {
+    pub fn new(id: &str) -> Self {
+        Path { id: id.to_string(), d: vec![], style: Style::new(), class: None, attributes: vec![], compact: None }
+    }
+
+    /// Creates a path from SVG path data, as given in the ``d`` attribute of a ``<path>`` element.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::shapes::{Path, PathCommand};
+    /// let p = Path::from_str("p", "M0,0 10,10 h-5").unwrap();
+    /// assert_eq!(p.d, vec![PathCommand::MoveTo(0.0, 0.0), PathCommand::LineTo(10.0, 10.0), PathCommand::HorizontalBy(-5.0)]);
+    /// assert_eq!(Path::from_str("p", "M 0 0 L 5").unwrap_err().offset(), 9);
+    /// ```
+    pub fn from_str(id: &str, path: &str) -> Result<Self, PathParseError> {
+        let mut p = Path::new(id);
+        p.d = parse_path_data(path)?;
+
+        Ok(p)
+    }
+
+    /// Closed path made of straight lines through the given points
+    pub fn polygon(id: &str, points: &[Point]) -> Self {
+        Polyline { points: points.to_vec(), closed: true }.to_path(id)
+    }
+
+    pub fn line_to(self, x: f32, y: f32) -> Self { self.add_command(PathCommand::LineTo(x, y)) }
+    pub fn move_to(self, x: f32, y: f32) -> Self { self.add_command(PathCommand::MoveTo(x, y)) }
+    pub fn horizontal_to(self, x: f32) -> Self { self.add_command(PathCommand::HorizontalTo(x)) }
+    pub fn vertical_to(self, y: f32) -> Self { self.add_command(PathCommand::VerticalTo(y)) }
+    pub fn curve_to(self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) -> Self {
+        self.add_command(PathCommand::CurveTo(x1, y1, x2, y2, x, y))
+    }
+    pub fn smooth_curve_to(self, x2: f32, y2: f32, x: f32, y: f32) -> Self {
+        self.add_command(PathCommand::SmoothCurveTo(x2, y2, x, y))
+    }
+    pub fn quadratic_bezier_curve_to(self, x1: f32, y1: f32, x: f32, y: f32) -> Self {
+        self.add_command(PathCommand::QuadraticBezierCurveTo(x1, y1, x, y))
+    }
+    pub fn smooth_quadratic_bezier_curve_to(self, x: f32, y: f32) -> Self {
+        self.add_command(PathCommand::SmoothQuadraticBezierCurveTo(x, y))
+    }
+    #[allow(clippy::too_many_arguments)]
+    pub fn elliptical_arc_to(self, rx: f32, ry: f32, x_axis_rotation: f32, large_arc_flag: bool,
+                             sweep_flag: bool, x: f32, y: f32) -> Self {
+        self.add_command(PathCommand::EllipticalArcTo(rx, ry, x_axis_rotation, large_arc_flag, sweep_flag, x, y))
+    }
+    pub fn close(self) -> Self { self.add_command(PathCommand::Close) }
+
+    pub fn add_command(mut self, command: PathCommand) -> Self {
+        self.d.push(command);
+        self
+    }
+}
+
+impl ToSvg for Path {
+    fn id(&self) -> Option<&str> { Some(&self.id) }
+
+    fn tag(&self) -> Option<&str> { Some("path") }
+
+    fn class(&self) -> Option<&str> { self.class.as_deref() }
+
+    /// Tight bounding box of the curves of this path; control points that lie outside the curves don't count
+    fn bbox(&self) -> Option<BBox> {
+        BBox::from_points(segments::subpaths(self).iter()
+            .flat_map(|sp| sp.segments.iter().flat_map(|s| s.extremes()))
+            .map(segments::to_point))
+    }
+
+    fn style(&self) -> Option<&Style> { Some(&self.style) }
+
+    fn style_mut(&mut self) -> Option<&mut Style> { Some(&mut self.style) }
+
+    fn to_svg(&self) -> String {
+        let d_str = match self.compact {
+            Some(decimals) => self.to_compact_d(Some(decimals)),
+            None => self.d.iter().map(|command| command.to_string()).collect::<Vec<_>>().join(" "),
+        };
+        let mut svg_string = format!(r#"<path id="{}" d="{}""#, escape(&self.id, true), d_str);
+        push_class(&self.class, &mut svg_string);
+        push_attributes(&self.attributes, &mut svg_string);
+        close_element(&self.style, &mut svg_string);
+        svg_string
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_path_commands() {
+        let path_data = "M 100 100 L 300 100 L 200 300 z";
+        let expected = vec![
+            PathCommand::MoveTo(100.0, 100.0),
+            PathCommand::LineTo(300.0, 100.0),
+            PathCommand::LineTo(200.0, 300.0),
+            PathCommand::Close,
+        ];
+
+        let result = parse_path_data(path_data).unwrap();
+        assert_eq!(result, expected);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,337 @@
+//! Conversion of path data to a canonical form made of ``M``, ``L``, ``C`` and ``Z`` commands only.
+use std::f64::consts::PI;
+use crate::shapes::{Path, PathCommand};
+
+impl Path {
+    /// Returns a copy of this path where every relative command is replaced by its absolute counterpart.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::shapes::{Path, PathCommand};
+    /// let p = Path::new("p").move_to(10.0, 10.0).add_command(PathCommand::LineBy(5.0, 0.0))
+    ///     .add_command(PathCommand::VerticalBy(5.0));
+    /// assert_eq!(p.to_absolute().d, vec![PathCommand::MoveTo(10.0, 10.0),
+    ///     PathCommand::LineTo(15.0, 10.0), PathCommand::VerticalTo(15.0)]);
+    /// ```
+    pub fn to_absolute(&self) -> Path {
+        let mut d = Vec::with_capacity(self.d.len());
+        let (mut x, mut y) = (0.0, 0.0);
+        let (mut sx, mut sy) = (0.0, 0.0);
+        for command in &self.d {
+            let abs = match *command {
+                PathCommand::MoveBy(dx, dy) => PathCommand::MoveTo(x + dx, y + dy),
+                PathCommand::LineBy(dx, dy) => PathCommand::LineTo(x + dx, y + dy),
+                PathCommand::HorizontalBy(dx) => PathCommand::HorizontalTo(x + dx),
+                PathCommand::VerticalBy(dy) => PathCommand::VerticalTo(y + dy),
+                PathCommand::CurveBy(x1, y1, x2, y2, dx, dy) =>
+                    PathCommand::CurveTo(x + x1, y + y1, x + x2, y + y2, x + dx, y + dy),
+                PathCommand::SmoothCurveBy(x2, y2, dx, dy) => PathCommand::SmoothCurveTo(x + x2, y + y2, x + dx, y + dy),
+                PathCommand::QuadraticBezierCurveBy(x1, y1, dx, dy) =>
+                    PathCommand::QuadraticBezierCurveTo(x + x1, y + y1, x + dx, y + dy),
+                PathCommand::SmoothQuadraticBezierCurveBy(dx, dy) => PathCommand::SmoothQuadraticBezierCurveTo(x + dx, y + dy),
+                PathCommand::EllipticalArcBy(rx, ry, rot, large, sweep, dx, dy) =>
+                    PathCommand::EllipticalArcTo(rx, ry, rot, large, sweep, x + dx, y + dy),
+                ref other => other.clone(),
+            };
+            // ---------- track the current point and the start of the current sub-path
+            match abs {
+                PathCommand::MoveTo(nx, ny) => { (x, y) = (nx, ny); (sx, sy) = (nx, ny); }
+                PathCommand::HorizontalTo(nx) => x = nx,
+                PathCommand::VerticalTo(ny) => y = ny,
+                PathCommand::Close => (x, y) = (sx, sy),
+                _ => if let Some(end) = end_point(&abs) { (x, y) = end },
+            }
+            d.push(abs);
+        }
+
+        self.with_commands(d)
+    }
+
+    /// Returns an absolute copy of this path with shorthand commands expanded.
+    ///
+    /// ``H`` and ``V`` become ``L``, ``S`` becomes ``C`` and ``T`` becomes ``Q``, with the implicit
+    /// control points of the smooth curves computed explicitly.
+    pub fn expand_shorthands(&self) -> Path {
+        let abs = self.to_absolute();
+        let mut d = Vec::with_capacity(abs.d.len());
+        let (mut x, mut y) = (0.0, 0.0);
+        let (mut sx, mut sy) = (0.0, 0.0);
+        // ---------- second control point of the previous cubic and control point of the previous quadratic
+        let mut last_cubic: Option<(f32, f32)> = None;
+        let mut last_quad: Option<(f32, f32)> = None;
+        for command in abs.d {
+            let expanded = match command {
+                PathCommand::HorizontalTo(nx) => PathCommand::LineTo(nx, y),
+                PathCommand::VerticalTo(ny) => PathCommand::LineTo(x, ny),
+                PathCommand::SmoothCurveTo(x2, y2, nx, ny) => {
+                    let (x1, y1) = last_cubic.map_or((x, y), |(cx, cy)| (2.0 * x - cx, 2.0 * y - cy));
+                    PathCommand::CurveTo(x1, y1, x2, y2, nx, ny)
+                }
+                PathCommand::SmoothQuadraticBezierCurveTo(nx, ny) => {
+                    let (x1, y1) = last_quad.map_or((x, y), |(cx, cy)| (2.0 * x - cx, 2.0 * y - cy));
+                    PathCommand::QuadraticBezierCurveTo(x1, y1, nx, ny)
+                }
+                other => other,
+            };
+            (last_cubic, last_quad) = match expanded {
+                PathCommand::CurveTo(_, _, x2, y2, _, _) => (Some((x2, y2)), None),
+                PathCommand::QuadraticBezierCurveTo(x1, y1, _, _) => (None, Some((x1, y1))),
+                _ => (None, None),
+            };
+            match expanded {
+                PathCommand::MoveTo(nx, ny) => { (x, y) = (nx, ny); (sx, sy) = (nx, ny); }
+                PathCommand::Close => (x, y) = (sx, sy),
+                _ => if let Some(end) = end_point(&expanded) { (x, y) = end },
+            }
+            d.push(expanded);
+        }
+
+        self.with_commands(d)
+    }
+
+    /// Returns an absolute copy of this path where every elliptical arc is replaced by cubic Béziers.
+    ///
+    /// An arc is split into pieces spanning at most 90 degrees each, which keeps the approximation
+    /// error below 0.03% of the radius. Out-of-range radii are scaled up as required by the SVG
+    /// specification; an arc with a zero radius becomes a straight line.
+    pub fn arcs_to_cubics(&self) -> Path {
+        let abs = self.to_absolute();
+        let mut d = Vec::with_capacity(abs.d.len());
+        let (mut x, mut y) = (0.0, 0.0);
+        let (mut sx, mut sy) = (0.0, 0.0);
+        for command in abs.d {
+            match command {
+                PathCommand::EllipticalArcTo(rx, ry, rot, large, sweep, nx, ny) => {
+                    d.extend(arc_to_cubics(x, y, rx, ry, rot, large, sweep, nx, ny));
+                    (x, y) = (nx, ny);
+                    continue;
+                }
+                PathCommand::MoveTo(nx, ny) => { (x, y) = (nx, ny); (sx, sy) = (nx, ny); }
+                PathCommand::HorizontalTo(nx) => x = nx,
+                PathCommand::VerticalTo(ny) => y = ny,
+                PathCommand::Close => (x, y) = (sx, sy),
+                ref other => if let Some(end) = end_point(other) { (x, y) = end },
+            }
+            d.push(command);
+        }
+
+        self.with_commands(d)
+    }
+
+    /// Returns the canonical form of this path: absolute ``M``, ``L``, ``C`` and ``Z`` commands only.
+    ///
+    /// Shorthands are expanded, arcs are approximated with cubics and quadratic Béziers are
+    /// elevated to the equivalent cubics. Geometric operations on paths work on this form.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::shapes::{Path, PathCommand};
+    /// let p = Path::new("p").move_to(0.0, 0.0).horizontal_to(30.0)
+    ///     .quadratic_bezier_curve_to(30.0, 30.0, 0.0, 30.0).close();
+    /// assert_eq!(p.normalize().d, vec![PathCommand::MoveTo(0.0, 0.0), PathCommand::LineTo(30.0, 0.0),
+    ///     PathCommand::CurveTo(30.0, 20.0, 20.0, 30.0, 0.0, 30.0), PathCommand::Close]);
+    /// ```
+    pub fn normalize(&self) -> Path {
+        let p = self.expand_shorthands().arcs_to_cubics();
+        let mut d = Vec::with_capacity(p.d.len());
+        let (mut x, mut y) = (0.0, 0.0);
+        let (mut sx, mut sy) = (0.0, 0.0);
+        for command in p.d {
+            let command = match command {
+                PathCommand::QuadraticBezierCurveTo(qx, qy, nx, ny) => PathCommand::CurveTo(
+                    x + 2.0 / 3.0 * (qx - x), y + 2.0 / 3.0 * (qy - y),
+                    nx + 2.0 / 3.0 * (qx - nx), ny + 2.0 / 3.0 * (qy - ny), nx, ny),
+                other => other,
+            };
+            match command {
+                PathCommand::MoveTo(nx, ny) => { (x, y) = (nx, ny); (sx, sy) = (nx, ny); }
+                PathCommand::Close => (x, y) = (sx, sy),
+                ref other => if let Some(end) = end_point(other) { (x, y) = end },
+            }
+            d.push(command);
+        }
+
+        self.with_commands(d)
+    }
+
+    /// Creates a path with the same id and style as this one, but with different commands
+    pub(crate) fn with_commands(&self, d: Vec<PathCommand>) -> Path {
+        Path { id: self.id.clone(), d, style: self.style.clone(), class: self.class.clone(), attributes: self.attributes.clone(),
+            compact: self.compact }
+    }
+}
+
+/// End point of an absolute command that has both of its end coordinates
+pub(crate) fn end_point(command: &PathCommand) -> Option<(f32, f32)> {
+    match *command {
+        PathCommand::MoveTo(x, y) | PathCommand::LineTo(x, y)
+        | PathCommand::CurveTo(_, _, _, _, x, y) | PathCommand::SmoothCurveTo(_, _, x, y)
+        | PathCommand::QuadraticBezierCurveTo(_, _, x, y) | PathCommand::SmoothQuadraticBezierCurveTo(x, y)
+        | PathCommand::EllipticalArcTo(_, _, _, _, _, x, y) => Some((x, y)),
+        _ => None,
+    }
+}
+
+/// Center parametrization of an elliptical arc
+pub(crate) struct ArcCenter {
+    pub cx: f64,
+    pub cy: f64,
+    pub rx: f64,
+    pub ry: f64,
+    /// rotation of the ellipse's x axis, in radians
+    pub phi: f64,
+    /// start angle in radians
+    pub theta: f64,
+    /// signed angular extent in radians
+    pub delta: f64,
+}
+
+impl ArcCenter {
+    /// Point of the ellipse at parametric angle `t`
+    pub fn point(&self, t: f64) -> (f64, f64) {
+        let (sin_phi, cos_phi) = self.phi.sin_cos();
+        let (ex, ey) = (self.rx * t.cos(), self.ry * t.sin());
+        (self.cx + cos_phi * ex - sin_phi * ey, self.cy + sin_phi * ex + cos_phi * ey)
+    }
+
+    /// Derivative of [`point()`](ArcCenter::point) over `t`
+    pub fn derivative(&self, t: f64) -> (f64, f64) {
+        let (sin_phi, cos_phi) = self.phi.sin_cos();
+        let (ex, ey) = (-self.rx * t.sin(), self.ry * t.cos());
+        (cos_phi * ex - sin_phi * ey, sin_phi * ex + cos_phi * ey)
+    }
+}
+
+/// Converts an arc from the endpoint parametrization used by SVG to the center parametrization,
+/// following section B.2.4 of the SVG 2 specification.
+///
+/// Returns `None` for degenerate arcs, which are drawn as straight lines (or not at all).
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn arc_center(x1: f32, y1: f32, rx: f32, ry: f32, rotation_deg: f32, large_arc: bool, sweep: bool,
+                         x2: f32, y2: f32) -> Option<ArcCenter> {
+    let (x1, y1, x2, y2) = (x1 as f64, y1 as f64, x2 as f64, y2 as f64);
+    let (mut rx, mut ry) = ((rx as f64).abs(), (ry as f64).abs());
+    if (x1 == x2 && y1 == y2) || rx == 0.0 || ry == 0.0 { return None; }
+
+    let phi = (rotation_deg as f64).to_radians();
+    let (sin_phi, cos_phi) = phi.sin_cos();
+    let (dx, dy) = ((x1 - x2) / 2.0, (y1 - y2) / 2.0);
+    let x1p = cos_phi * dx + sin_phi * dy;
+    let y1p = -sin_phi * dx + cos_phi * dy;
+
+    // ---------- scale radii up if they are too small to reach the end point
+    let lambda = (x1p * x1p) / (rx * rx) + (y1p * y1p) / (ry * ry);
+    if lambda > 1.0 {
+        rx *= lambda.sqrt();
+        ry *= lambda.sqrt();
+    }
+
+    let num = rx * rx * ry * ry - rx * rx * y1p * y1p - ry * ry * x1p * x1p;
+    let den = rx * rx * y1p * y1p + ry * ry * x1p * x1p;
+    let mut coef = (num / den).max(0.0).sqrt();
+    if large_arc == sweep { coef = -coef; }
+    let cxp = coef * rx * y1p / ry;
+    let cyp = -coef * ry * x1p / rx;
+
+    let cx = cos_phi * cxp - sin_phi * cyp + (x1 + x2) / 2.0;
+    let cy = sin_phi * cxp + cos_phi * cyp + (y1 + y2) / 2.0;
+
+    let angle = |ux: f64, uy: f64, vx: f64, vy: f64| (ux * vy - uy * vx).atan2(ux * vx + uy * vy);
+    let theta = angle(1.0, 0.0, (x1p - cxp) / rx, (y1p - cyp) / ry);
+    let mut delta = angle((x1p - cxp) / rx, (y1p - cyp) / ry, (-x1p - cxp) / rx, (-y1p - cyp) / ry);
+    if !sweep && delta > 0.0 { delta -= 2.0 * PI; }
+    if sweep && delta < 0.0 { delta += 2.0 * PI; }
+
+    Some(ArcCenter { cx, cy, rx, ry, phi, theta, delta })
+}
+
+/// Approximates an elliptical arc with cubic Bézier curves, each spanning at most 90 degrees
+#[allow(clippy::too_many_arguments)]
+fn arc_to_cubics(x1: f32, y1: f32, rx: f32, ry: f32, rotation_deg: f32, large_arc: bool, sweep: bool,
+                 x2: f32, y2: f32) -> Vec<PathCommand> {
+    let arc = match arc_center(x1, y1, rx, ry, rotation_deg, large_arc, sweep, x2, y2) {
+        Some(arc) => arc,
+        None if x1 == x2 && y1 == y2 => return vec![],
+        None => return vec![PathCommand::LineTo(x2, y2)],
+    };
+
+    let n = (arc.delta.abs() / (PI / 2.0) - 1e-9).ceil().max(1.0) as usize;
+    let step = arc.delta / n as f64;
+    let k = 4.0 / 3.0 * (step / 4.0).tan();
+    let mut out = Vec::with_capacity(n);
+    for i in 0..n {
+        let (t0, t1) = (arc.theta + step * i as f64, arc.theta + step * (i + 1) as f64);
+        let (p0, d0) = (arc.point(t0), arc.derivative(t0));
+        let (p1, d1) = (arc.point(t1), arc.derivative(t1));
+        // ---------- the last point is copied exactly, so the path ends where the arc was supposed to end
+        let end = if i + 1 == n { (x2, y2) } else { (p1.0 as f32, p1.1 as f32) };
+        out.push(PathCommand::CurveTo((p0.0 + k * d0.0) as f32, (p0.1 + k * d0.1) as f32,
+                                      (p1.0 - k * d1.0) as f32, (p1.1 - k * d1.1) as f32, end.0, end.1));
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shapes::{Path, PathCommand};
+
+    fn assert_close(a: &[PathCommand], b: &[PathCommand]) {
+        let fmt = |d: &[PathCommand]| format!("{:.3?}", d).replace("-0.000", "0.000");
+        assert_eq!(fmt(a), fmt(b));
+    }
+
+    #[test]
+    fn relative_to_absolute() {
+        let p = Path::new("p").add_command(PathCommand::MoveBy(10.0, 10.0))
+            .add_command(PathCommand::CurveBy(0.0, 5.0, 5.0, 5.0, 5.0, 0.0))
+            .add_command(PathCommand::HorizontalBy(5.0))
+            .close()
+            .add_command(PathCommand::MoveBy(1.0, 1.0))
+            .add_command(PathCommand::EllipticalArcBy(2.0, 2.0, 0.0, false, true, 4.0, 0.0));
+        assert_eq!(p.to_absolute().d, vec![PathCommand::MoveTo(10.0, 10.0),
+            PathCommand::CurveTo(10.0, 15.0, 15.0, 15.0, 15.0, 10.0), PathCommand::HorizontalTo(20.0),
+            PathCommand::Close, PathCommand::MoveTo(11.0, 11.0),
+            PathCommand::EllipticalArcTo(2.0, 2.0, 0.0, false, true, 15.0, 11.0)]);
+    }
+
+    #[test]
+    fn shorthands() {
+        let p = Path::new("p").move_to(0.0, 0.0).curve_to(0.0, 10.0, 10.0, 10.0, 10.0, 0.0)
+            .smooth_curve_to(20.0, -10.0, 20.0, 0.0)
+            .quadratic_bezier_curve_to(25.0, 5.0, 30.0, 0.0)
+            .smooth_quadratic_bezier_curve_to(40.0, 0.0)
+            .vertical_to(5.0)
+            .smooth_curve_to(0.0, 5.0, 0.0, 0.0);
+        assert_eq!(p.expand_shorthands().d, vec![PathCommand::MoveTo(0.0, 0.0),
+            PathCommand::CurveTo(0.0, 10.0, 10.0, 10.0, 10.0, 0.0),
+            PathCommand::CurveTo(10.0, -10.0, 20.0, -10.0, 20.0, 0.0),
+            PathCommand::QuadraticBezierCurveTo(25.0, 5.0, 30.0, 0.0),
+            PathCommand::QuadraticBezierCurveTo(35.0, -5.0, 40.0, 0.0),
+            PathCommand::LineTo(40.0, 5.0),
+            // --- no previous cubic: the first control point is the current point
+            PathCommand::CurveTo(40.0, 5.0, 0.0, 5.0, 0.0, 0.0)]);
+    }
+
+    #[test]
+    fn arcs() {
+        // --- a half circle becomes two quarter circles
+        let p = Path::new("p").move_to(0.0, 0.0).elliptical_arc_to(10.0, 10.0, 0.0, false, true, 20.0, 0.0);
+        let k = 10.0 * 0.552_284_8;
+        assert_close(&p.arcs_to_cubics().d, &[PathCommand::MoveTo(0.0, 0.0),
+            PathCommand::CurveTo(0.0, -k, 10.0 - k, -10.0, 10.0, -10.0),
+            PathCommand::CurveTo(10.0 + k, -10.0, 20.0, -k, 20.0, 0.0)]);
+
+        // --- too small radii are scaled up, zero radii give a line
+        let p = Path::new("p").move_to(0.0, 0.0).elliptical_arc_to(1.0, 1.0, 0.0, false, false, 20.0, 0.0)
+            .elliptical_arc_to(0.0, 5.0, 0.0, false, false, 30.0, 0.0);
+        let d = p.arcs_to_cubics().d;
+        assert_close(&d[1..3], &[PathCommand::CurveTo(0.0, k, 10.0 - k, 10.0, 10.0, 10.0),
+            PathCommand::CurveTo(10.0 + k, 10.0, 20.0, k, 20.0, 0.0)]);
+        assert_eq!(d[3], PathCommand::LineTo(30.0, 0.0));
+
+        let canonical = Path::from_str("c", "M 0 0 h 10 a 5 5 0 0 1 0 10 t -10 0 z").unwrap().normalize();
+        assert!(canonical.d.iter().all(|c| matches!(c, PathCommand::MoveTo(..) | PathCommand::LineTo(..)
+            | PathCommand::CurveTo(..) | PathCommand::Close)));
+    }
+}
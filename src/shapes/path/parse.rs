@@ -0,0 +1,243 @@
+//! Parser of the SVG path data, as given in the ``d`` attribute of a ``<path>``.
+use std::fmt;
+use std::str::FromStr;
+use crate::shapes::PathCommand;
+
+/// Error returned when path data doesn't follow the SVG path grammar.
+///
+/// Every variant carries the byte offset in the parsed string where the problem was found.
+#[derive(Debug, Clone, PartialEq)]
+pub enum PathParseError {
+    /// The path data doesn't begin with a ``M`` or ``m`` command
+    MissingMoveTo { offset: usize },
+    /// A character that is neither a command, a number nor a separator
+    UnexpectedCharacter { offset: usize, found: char },
+    /// A command that lacks some of its numeric arguments
+    ExpectedNumber { offset: usize },
+    /// An arc whose large-arc or sweep flag is not ``0`` or ``1``
+    ExpectedFlag { offset: usize },
+}
+
+impl PathParseError {
+    /// Byte offset in the path data where the error was found
+    pub fn offset(&self) -> usize {
+        match self {
+            PathParseError::MissingMoveTo { offset } | PathParseError::UnexpectedCharacter { offset, .. }
+            | PathParseError::ExpectedNumber { offset } | PathParseError::ExpectedFlag { offset } => *offset,
+        }
+    }
+}
+
+impl fmt::Display for PathParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathParseError::MissingMoveTo { offset } => write!(f, "path data must start with a moveto command (at byte {})", offset),
+            PathParseError::UnexpectedCharacter { offset, found } => write!(f, "unexpected character '{}' at byte {}", found, offset),
+            PathParseError::ExpectedNumber { offset } => write!(f, "expected a number at byte {}", offset),
+            PathParseError::ExpectedFlag { offset } => write!(f, "expected an arc flag (0 or 1) at byte {}", offset),
+        }
+    }
+}
+
+impl std::error::Error for PathParseError {}
+
+/// Kind of the arguments of path commands
+#[derive(Clone, Copy)]
+enum Arg { Number, Flag }
+
+const ARC_ARGS: [Arg; 7] = [Arg::Number, Arg::Number, Arg::Number, Arg::Flag, Arg::Flag, Arg::Number, Arg::Number];
+
+/// Parses path data into commands, following the grammar of the SVG 2 specification.
+///
+/// Commands may be followed by several groups of arguments, in which case the command is repeated;
+/// groups repeated after a moveto are linetos. Numbers may use exponents and need no separator when the
+/// boundary is unambiguous, as in ``-1-2`` or ``0.5.5``; arc flags need no separator at all. Empty data
+/// (or made of whitespace only) gives no commands.
+pub(crate) fn parse_path_data(data: &str) -> Result<Vec<PathCommand>, PathParseError> {
+    let mut parser = Parser { data, bytes: data.as_bytes(), pos: 0 };
+    let mut commands = vec![];
+    parser.skip_wsp();
+    if parser.at_end() { return Ok(commands); }
+    let mut command = parser.bytes[parser.pos];
+    if command != b'M' && command != b'm' { return Err(PathParseError::MissingMoveTo { offset: parser.pos }); }
+    parser.pos += 1;
+
+    loop {
+        if command == b'Z' || command == b'z' {
+            commands.push(PathCommand::Close);
+        } else {
+            parser.skip_wsp();
+            let mut implicit = command;
+            loop {
+                commands.push(parser.arguments(implicit)?);
+                // ---------- further argument groups repeat the command; a moveto continues with linetos
+                implicit = match implicit { b'M' => b'L', b'm' => b'l', c => c };
+                let before = parser.pos;
+                let had_comma = parser.skip_comma_wsp();
+                if !parser.starts_number() {
+                    if had_comma { return Err(PathParseError::ExpectedNumber { offset: parser.pos }); }
+                    parser.pos = before;
+                    break;
+                }
+            }
+        }
+        parser.skip_wsp();
+        if parser.at_end() { break; }
+        let c = parser.bytes[parser.pos];
+        if !b"MmLlHhVvCcSsQqTtAaZz".contains(&c) { return Err(parser.unexpected()); }
+        command = c;
+        parser.pos += 1;
+    }
+
+    Ok(commands)
+}
+
+struct Parser<'a> {
+    data: &'a str,
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn at_end(&self) -> bool { self.pos >= self.bytes.len() }
+
+    fn peek(&self) -> Option<u8> { self.bytes.get(self.pos).copied() }
+
+    fn skip_wsp(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r' | b'\x0C')) { self.pos += 1; }
+    }
+
+    /// Skips whitespace with at most one comma; tells whether a comma was found
+    fn skip_comma_wsp(&mut self) -> bool {
+        self.skip_wsp();
+        let comma = self.peek() == Some(b',');
+        if comma {
+            self.pos += 1;
+            self.skip_wsp();
+        }
+
+        comma
+    }
+
+    fn starts_number(&self) -> bool { matches!(self.peek(), Some(b'0'..=b'9' | b'+' | b'-' | b'.')) }
+
+    fn unexpected(&self) -> PathParseError {
+        match self.data[self.pos..].chars().next() {
+            Some(found) => PathParseError::UnexpectedCharacter { offset: self.pos, found },
+            None => PathParseError::ExpectedNumber { offset: self.pos },
+        }
+    }
+
+    /// Parses one group of arguments of the given command
+    fn arguments(&mut self, command: u8) -> Result<PathCommand, PathParseError> {
+        let n = match command.to_ascii_uppercase() {
+            b'M' | b'L' | b'T' => 2,
+            b'H' | b'V' => 1,
+            b'S' | b'Q' => 4,
+            b'C' => 6,
+            _ => 7,
+        };
+        let kinds: &[Arg] = if command.eq_ignore_ascii_case(&b'A') { &ARC_ARGS } else { &[Arg::Number; 6][..n] };
+        let mut v = [0.0f32; 7];
+        for (i, kind) in kinds.iter().enumerate() {
+            if i > 0 { self.skip_comma_wsp(); }
+            v[i] = match kind {
+                Arg::Number => self.number()?,
+                Arg::Flag => self.flag()?,
+            };
+        }
+
+        Ok(match command {
+            b'M' => PathCommand::MoveTo(v[0], v[1]),
+            b'm' => PathCommand::MoveBy(v[0], v[1]),
+            b'L' => PathCommand::LineTo(v[0], v[1]),
+            b'l' => PathCommand::LineBy(v[0], v[1]),
+            b'H' => PathCommand::HorizontalTo(v[0]),
+            b'h' => PathCommand::HorizontalBy(v[0]),
+            b'V' => PathCommand::VerticalTo(v[0]),
+            b'v' => PathCommand::VerticalBy(v[0]),
+            b'C' => PathCommand::CurveTo(v[0], v[1], v[2], v[3], v[4], v[5]),
+            b'c' => PathCommand::CurveBy(v[0], v[1], v[2], v[3], v[4], v[5]),
+            b'S' => PathCommand::SmoothCurveTo(v[0], v[1], v[2], v[3]),
+            b's' => PathCommand::SmoothCurveBy(v[0], v[1], v[2], v[3]),
+            b'Q' => PathCommand::QuadraticBezierCurveTo(v[0], v[1], v[2], v[3]),
+            b'q' => PathCommand::QuadraticBezierCurveBy(v[0], v[1], v[2], v[3]),
+            b'T' => PathCommand::SmoothQuadraticBezierCurveTo(v[0], v[1]),
+            b't' => PathCommand::SmoothQuadraticBezierCurveBy(v[0], v[1]),
+            b'A' => PathCommand::EllipticalArcTo(v[0], v[1], v[2], v[3] != 0.0, v[4] != 0.0, v[5], v[6]),
+            _ => PathCommand::EllipticalArcBy(v[0], v[1], v[2], v[3] != 0.0, v[4] != 0.0, v[5], v[6]),
+        })
+    }
+
+    /// Parses a number: an optional sign, digits with an optional fraction, and an optional exponent
+    fn number(&mut self) -> Result<f32, PathParseError> {
+        let start = self.pos;
+        let error = PathParseError::ExpectedNumber { offset: start };
+        if matches!(self.peek(), Some(b'+' | b'-')) { self.pos += 1; }
+        let int_digits = self.digits();
+        let mut frac_digits = 0;
+        if self.peek() == Some(b'.') {
+            self.pos += 1;
+            frac_digits = self.digits();
+        }
+        if int_digits + frac_digits == 0 {
+            self.pos = start;
+            return Err(error);
+        }
+        // ---------- an exponent counts only when digits follow, so that "e" alone is left alone
+        if matches!(self.peek(), Some(b'e' | b'E')) {
+            let mark = self.pos;
+            self.pos += 1;
+            if matches!(self.peek(), Some(b'+' | b'-')) { self.pos += 1; }
+            if self.digits() == 0 { self.pos = mark; }
+        }
+
+        f32::from_str(&self.data[start..self.pos]).map_err(|_| error)
+    }
+
+    fn digits(&mut self) -> usize {
+        let start = self.pos;
+        while matches!(self.peek(), Some(b'0'..=b'9')) { self.pos += 1; }
+        self.pos - start
+    }
+
+    fn flag(&mut self) -> Result<f32, PathParseError> {
+        match self.peek() {
+            Some(b'0') => { self.pos += 1; Ok(0.0) }
+            Some(b'1') => { self.pos += 1; Ok(1.0) }
+            _ => Err(PathParseError::ExpectedFlag { offset: self.pos }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shapes::path::parse::{parse_path_data, PathParseError};
+    use crate::shapes::PathCommand;
+
+    #[test]
+    fn compact_syntax() {
+        assert_eq!(parse_path_data("M0 0 10 10,20 20").unwrap(), vec![PathCommand::MoveTo(0.0, 0.0),
+            PathCommand::LineTo(10.0, 10.0), PathCommand::LineTo(20.0, 20.0)]);
+        assert_eq!(parse_path_data("m1e-3-2E+1.5.5").unwrap(), vec![PathCommand::MoveBy(0.001, -20.0),
+            PathCommand::LineBy(0.5, 0.5)]);
+        assert_eq!(parse_path_data("M0,0a25,25 -30 0110,10t5-5").unwrap(), vec![PathCommand::MoveTo(0.0, 0.0),
+            PathCommand::EllipticalArcBy(25.0, 25.0, -30.0, false, true, 10.0, 10.0),
+            PathCommand::SmoothQuadraticBezierCurveBy(5.0, -5.0)]);
+        assert_eq!(parse_path_data("M 1 2ZL3 4z").unwrap(), vec![PathCommand::MoveTo(1.0, 2.0), PathCommand::Close,
+            PathCommand::LineTo(3.0, 4.0), PathCommand::Close]);
+        assert_eq!(parse_path_data("  \n").unwrap(), vec![]);
+    }
+
+    #[test]
+    fn errors_carry_offsets() {
+        assert_eq!(parse_path_data("L 0 0"), Err(PathParseError::MissingMoveTo { offset: 0 }));
+        assert_eq!(parse_path_data("M 10"), Err(PathParseError::ExpectedNumber { offset: 4 }));
+        assert_eq!(parse_path_data("M 0 0 L 1 2,"), Err(PathParseError::ExpectedNumber { offset: 12 }));
+        assert_eq!(parse_path_data("M 0 0 X"), Err(PathParseError::UnexpectedCharacter { offset: 6, found: 'X' }));
+        assert_eq!(parse_path_data("M 0 0 A 5 5 0 2 0 1 1"), Err(PathParseError::ExpectedFlag { offset: 14 }));
+        assert_eq!(parse_path_data("M 0 0 L ."), Err(PathParseError::ExpectedNumber { offset: 8 }));
+        assert_eq!(parse_path_data("M 0 0 ł").unwrap_err().offset(), 6);
+        assert_eq!(parse_path_data("M,0 0").unwrap_err().offset(), 1);
+    }
+}
@@ -0,0 +1,401 @@
+//! Geometric queries: containment of points, distances and intersections.
+use crate::shapes::path::flatten::distance_to_segment;
+use crate::shapes::path::measure::PathMeasure;
+use crate::geometry::Point;
+use crate::shapes::path::segments::{dist, from_point, lerp, subpaths, to_point, Pt, Segment};
+use crate::shapes::{FillRule, Path};
+
+/// Number of samples per curve when looking for the nearest point
+const NEAREST_SAMPLES: usize = 32;
+
+/// Maximum depth of subdivision when intersecting curves
+const MAX_INTERSECTION_DEPTH: u32 = 48;
+
+/// Position on a path
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathLocation {
+    /// index of the segment, counted over the line and curve commands of [`Path::normalize()`];
+    /// a ``Z`` adds a closing line to the count only when its sub-path doesn't already end at its start
+    pub segment: usize,
+    /// parameter of the curve within its segment, from 0 to 1
+    pub t: f32,
+    /// distance from the start of the path, as used by [`Path::point_at_length()`]
+    pub length: f32,
+}
+
+/// A point where two paths cross
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Intersection {
+    pub point: Point,
+    /// where the point lies on the path whose method was called
+    pub this: PathLocation,
+    /// where the point lies on the other path
+    pub other: PathLocation,
+}
+
+impl Path {
+    /// Tells whether a point is inside the area of this path filled with the given rule.
+    ///
+    /// Open sub-paths are closed with a straight line, as they are when filled.
+    /// Curves are tested exactly, not through their flattening.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::geometry::Point;
+    /// use visualife::shapes::{FillRule, Path};
+    /// let donut = Path::from_str("d", "M 0 0 H 10 V 10 H 0 Z M 3 3 H 7 V 7 H 3 Z").unwrap();
+    /// assert!(donut.contains(Point::new(5.0, 5.0), FillRule::NonZero));
+    /// assert!(!donut.contains(Point::new(5.0, 5.0), FillRule::EvenOdd));
+    /// assert!(donut.contains(Point::new(1.0, 5.0), FillRule::EvenOdd));
+    /// ```
+    pub fn contains(&self, point: Point, fill_rule: FillRule) -> bool {
+        let p = from_point(point);
+        let mut winding = 0;
+        for sp in subpaths(self) {
+            for seg in &sp.segments { winding += segment_winding(seg, p); }
+            if let (false, Some(last)) = (sp.closed, sp.segments.last()) {
+                winding += segment_winding(&Segment::Line(last.end(), sp.start), p);
+            }
+        }
+
+        fill_rule.is_inside(winding)
+    }
+
+    /// Distance from a point to this path, together with the nearest point of the path.
+    ///
+    /// Returns `None` for a path that has no segments.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::geometry::Point;
+    /// use visualife::shapes::Path;
+    /// let p = Path::from_str("p", "M 0 0 H 10 V 10").unwrap();
+    /// assert_eq!(p.distance_to(Point::new(5.0, 3.0)), Some((3.0, Point::new(5.0, 0.0))));
+    /// ```
+    pub fn distance_to(&self, point: Point) -> Option<(f32, Point)> {
+        let p = from_point(point);
+        let mut best: Option<(f64, Pt)> = None;
+        for (seg, _) in PathMeasure::new(self).segments() {
+            let t = nearest_t(seg, p);
+            let q = seg.point(t);
+            let d = dist(p, q);
+            if best.is_none_or(|(bd, _)| d < bd) { best = Some((d, q)); }
+        }
+
+        best.map(|(d, q)| (d as f32, to_point(q)))
+    }
+
+    /// Points where this path crosses `other`.
+    ///
+    /// Curves are intersected by recursive subdivision; points are found within a tolerance
+    /// of a millionth of the size of the paths. Overlapping collinear parts are not reported.
+    /// A crossing at a joint between segments is reported once, but a path that passes twice through
+    /// the same point of `other` crosses it twice there.
+    /// Intersections are sorted by their distance along this path.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::shapes::Path;
+    /// let circle = Path::from_str("c", "M 10 0 A 10 10 0 0 1 -10 0 A 10 10 0 0 1 10 0 Z").unwrap();
+    /// let line = Path::from_str("l", "M -20 0 H 20").unwrap();
+    /// let crossings = circle.intersections(&line);
+    /// assert_eq!(crossings.len(), 2);
+    /// assert!((crossings[1].point.x + 10.0).abs() < 1e-3 && crossings[1].other.length > 9.99);
+    /// ```
+    pub fn intersections(&self, other: &Path) -> Vec<Intersection> {
+        let (ma, mb) = (PathMeasure::new(self), PathMeasure::new(other));
+        let (sa, sb) = (ma.segments(), mb.segments());
+        let scale = bounds(sa.iter().chain(sb.iter()).map(|s| &s.0)).map_or(1.0, |b| (b[2] - b[0]).max(b[3] - b[1]).max(1e-9));
+        let eps = scale * 1e-6;
+
+        let (ja, jb) = (Joints::new(&ma, eps), Joints::new(&mb, eps));
+        // ---------- every hit, with where it lies along both paths
+        let mut found: Vec<(f64, f64, usize, f64, usize, f64, Pt)> = vec![];
+        for (i, (a, _)) in sa.iter().enumerate() {
+            for (j, (b, _)) in sb.iter().enumerate() {
+                let mut hits = vec![];
+                intersect_segments(a, (0.0, 1.0), b, (0.0, 1.0), eps, 0, &mut hits);
+                for (ta, tb, p) in hits {
+                    found.push((ja.along(i, ta), jb.along(j, tb), i, ta, j, tb, p));
+                }
+            }
+        }
+        // ---------- a crossing at a joint between segments, or where subdivision splits a segment, is found twice:
+        // ---------- once at the end of a piece and once at the start of the next one, so at the same place along both paths
+        found.sort_by(|x, y| x.0.total_cmp(&y.0));
+        let tolerance = 10.0 * eps;
+        let mut kept: Vec<(f64, f64, usize, f64, usize, f64, Pt)> = vec![];
+        for hit in found {
+            let seen = kept.iter().rev().take_while(|k| hit.0 - k.0 < tolerance)
+                .any(|k| (hit.1 - k.1).abs() < tolerance && dist(hit.6, k.6) < tolerance);
+            if !seen { kept.push(hit); }
+        }
+
+        let location = |measure: &PathMeasure, i: usize, t: f64| {
+            let seg = measure.segments()[i].0;
+            PathLocation { segment: i, t: t as f32, length: (measure.start_of(i) + seg.length_between(0.0, t)) as f32 }
+        };
+        let mut out: Vec<Intersection> = kept.into_iter().map(|(_, _, i, ta, j, tb, p)| Intersection {
+            point: to_point(p),
+            this: location(&ma, i, ta),
+            other: location(&mb, j, tb),
+        }).collect();
+        out.sort_by(|x, y| x.this.length.total_cmp(&y.this.length));
+
+        out
+    }
+}
+
+/// Where the closed sub-paths of a path start and end, so that the two ends of such a sub-path,
+/// which are the same point, are given the same distance along the path
+struct Joints<'a> {
+    measure: &'a PathMeasure,
+    /// distances along the path of the end and of the start of every closed sub-path
+    closed: Vec<(f64, f64)>,
+    eps: f64,
+}
+
+impl<'a> Joints<'a> {
+    fn new(measure: &'a PathMeasure, eps: f64) -> Self {
+        let segments = measure.segments();
+        let mut closed = vec![];
+        let mut first = 0;
+        for k in 0..segments.len() {
+            // ---------- a sub-path ends where the next segment doesn't start at the end of this one
+            let last = k + 1 == segments.len() || dist(segments[k].0.end(), segments[k + 1].0.start()) > eps;
+            if !last { continue; }
+            if dist(segments[k].0.end(), segments[first].0.start()) <= eps {
+                closed.push((segments[k].1, measure.start_of(first)));
+            }
+            first = k + 1;
+        }
+        Joints { measure, closed, eps }
+    }
+
+    /// Distance along the path of the point at `t` of the `i`-th segment
+    fn along(&self, i: usize, t: f64) -> f64 {
+        let length = self.measure.start_of(i) + self.measure.segments()[i].0.length_between(0.0, t);
+        match self.closed.iter().find(|(end, _)| (end - length).abs() < 10.0 * self.eps) {
+            Some(&(_, start)) => start,
+            None => length,
+        }
+    }
+}
+
+/// Contribution of a segment to the winding number at `p`, counted along a ray towards +x
+fn segment_winding(seg: &Segment, p: Pt) -> i32 {
+    match *seg {
+        Segment::Line(a, b) => line_winding(a, b, p),
+        Segment::Cubic(p0, p1, p2, p3) => {
+            // ---------- quick rejection: the curve lies within the bounding box of its control points
+            let ys = [p0[1], p1[1], p2[1], p3[1]];
+            let xs = [p0[0], p1[0], p2[0], p3[0]];
+            if ys.iter().all(|&y| y > p[1]) || ys.iter().all(|&y| y < p[1]) || xs.iter().all(|&x| x <= p[0]) { return 0; }
+            // ---------- split the curve into parts monotone in y
+            let mut ts = vec![0.0];
+            ts.extend(y_extrema(p0[1], p1[1], p2[1], p3[1]));
+            ts.push(1.0);
+            let mut w = 0;
+            for k in 0..ts.len() - 1 {
+                let (t0, t1) = (ts[k], ts[k + 1]);
+                let (y0, y1) = (seg.point(t0)[1], seg.point(t1)[1]);
+                let upward = y0 <= p[1] && p[1] < y1;
+                let downward = y1 <= p[1] && p[1] < y0;
+                if !upward && !downward { continue; }
+                // ---------- bisection for the parameter where the monotone part reaches the height of p
+                let (mut lo, mut hi) = (t0, t1);
+                for _ in 0..60 {
+                    let mid = (lo + hi) / 2.0;
+                    if (seg.point(mid)[1] < p[1]) == upward { lo = mid; } else { hi = mid; }
+                }
+                if seg.point((lo + hi) / 2.0)[0] > p[0] { w += if upward { 1 } else { -1 }; }
+            }
+
+            w
+        }
+    }
+}
+
+fn line_winding(a: Pt, b: Pt, p: Pt) -> i32 {
+    let upward = a[1] <= p[1] && p[1] < b[1];
+    let downward = b[1] <= p[1] && p[1] < a[1];
+    if !upward && !downward { return 0; }
+    let x = a[0] + (p[1] - a[1]) / (b[1] - a[1]) * (b[0] - a[0]);
+    if x <= p[0] { 0 } else if upward { 1 } else { -1 }
+}
+
+/// Parameters in (0, 1) where a cubic with the given coordinates has a zero derivative
+fn y_extrema(a: f64, b: f64, c: f64, d: f64) -> Vec<f64> {
+    // ---------- the derivative divided by 3 is q2 t^2 + q1 t + q0
+    let q2 = -a + 3.0 * b - 3.0 * c + d;
+    let q1 = 2.0 * (a - 2.0 * b + c);
+    let q0 = b - a;
+    let mut roots = vec![];
+    if q2.abs() < 1e-12 {
+        if q1.abs() > 1e-12 { roots.push(-q0 / q1); }
+    } else {
+        let disc = q1 * q1 - 4.0 * q2 * q0;
+        if disc >= 0.0 {
+            let sq = disc.sqrt();
+            roots.push((-q1 - sq) / (2.0 * q2));
+            roots.push((-q1 + sq) / (2.0 * q2));
+        }
+    }
+    roots.retain(|&t| t > 0.0 && t < 1.0);
+    roots.sort_by(|x, y| x.total_cmp(y));
+
+    roots
+}
+
+/// Parameter of the point of a segment nearest to `p`
+fn nearest_t(seg: &Segment, p: Pt) -> f64 {
+    match *seg {
+        Segment::Line(a, b) => {
+            let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+            let len2 = dx * dx + dy * dy;
+            if len2 == 0.0 { 0.0 } else { (((p[0] - a[0]) * dx + (p[1] - a[1]) * dy) / len2).clamp(0.0, 1.0) }
+        }
+        Segment::Cubic(..) => {
+            let d2 = |t: f64| { let q = seg.point(t); (q[0] - p[0]).powi(2) + (q[1] - p[1]).powi(2) };
+            let best = (0..=NEAREST_SAMPLES).min_by(|&i, &j| {
+                d2(i as f64 / NEAREST_SAMPLES as f64).total_cmp(&d2(j as f64 / NEAREST_SAMPLES as f64))
+            }).unwrap();
+            // ---------- golden-section search around the best sample
+            let step = 1.0 / NEAREST_SAMPLES as f64;
+            let (mut lo, mut hi) = (((best as f64 - 1.0) * step).max(0.0), ((best as f64 + 1.0) * step).min(1.0));
+            let ratio = (5f64.sqrt() - 1.0) / 2.0;
+            for _ in 0..60 {
+                let (m1, m2) = (hi - ratio * (hi - lo), lo + ratio * (hi - lo));
+                if d2(m1) < d2(m2) { hi = m2; } else { lo = m1; }
+            }
+
+            (lo + hi) / 2.0
+        }
+    }
+}
+
+fn control_points(seg: &Segment) -> Vec<Pt> {
+    match *seg {
+        Segment::Line(a, b) => vec![a, b],
+        Segment::Cubic(a, b, c, d) => vec![a, b, c, d],
+    }
+}
+
+/// Bounding box `[min_x, min_y, max_x, max_y]` of the control points of the segments
+fn bounds<'a>(segments: impl Iterator<Item = &'a Segment>) -> Option<[f64; 4]> {
+    segments.flat_map(control_points).fold(None, |b: Option<[f64; 4]>, p| Some(match b {
+        None => [p[0], p[1], p[0], p[1]],
+        Some(b) => [b[0].min(p[0]), b[1].min(p[1]), b[2].max(p[0]), b[3].max(p[1])],
+    }))
+}
+
+/// Largest distance of a control point from the chord of a segment
+fn flatness(seg: &Segment) -> f64 {
+    match *seg {
+        Segment::Line(..) => 0.0,
+        Segment::Cubic(a, b, c, d) => distance_to_segment(b, a, d).max(distance_to_segment(c, a, d)),
+    }
+}
+
+/// Collects the parameters of the crossings of two segments, given as parts `ra`, `rb` of their curves
+fn intersect_segments(a: &Segment, ra: (f64, f64), b: &Segment, rb: (f64, f64), eps: f64, depth: u32,
+                      out: &mut Vec<(f64, f64, Pt)>) {
+    let (ba, bb) = (bounds(std::iter::once(a)).unwrap(), bounds(std::iter::once(b)).unwrap());
+    if ba[0] > bb[2] + eps || bb[0] > ba[2] + eps || ba[1] > bb[3] + eps || bb[1] > ba[3] + eps { return; }
+
+    let (fa, fb) = (flatness(a), flatness(b));
+    if (fa <= eps && fb <= eps) || depth >= MAX_INTERSECTION_DEPTH {
+        if let Some((u, v)) = chord_intersection(a.start(), a.end(), b.start(), b.end(), eps) {
+            let p = lerp(a.start(), a.end(), u);
+            out.push((ra.0 + u * (ra.1 - ra.0), rb.0 + v * (rb.1 - rb.0), p));
+        }
+        return;
+    }
+    // ---------- split the less flat of the two segments
+    if fa >= fb {
+        let (a1, a2) = a.split(0.5);
+        let mid = (ra.0 + ra.1) / 2.0;
+        intersect_segments(&a1, (ra.0, mid), b, rb, eps, depth + 1, out);
+        intersect_segments(&a2, (mid, ra.1), b, rb, eps, depth + 1, out);
+    } else {
+        let (b1, b2) = b.split(0.5);
+        let mid = (rb.0 + rb.1) / 2.0;
+        intersect_segments(a, ra, &b1, (rb.0, mid), eps, depth + 1, out);
+        intersect_segments(a, ra, &b2, (mid, rb.1), eps, depth + 1, out);
+    }
+}
+
+/// Parameters of the crossing of the chords `p1 p2` and `q1 q2`; parallel chords don't cross
+fn chord_intersection(p1: Pt, p2: Pt, q1: Pt, q2: Pt, eps: f64) -> Option<(f64, f64)> {
+    let r = [p2[0] - p1[0], p2[1] - p1[1]];
+    let s = [q2[0] - q1[0], q2[1] - q1[1]];
+    let denom = r[0] * s[1] - r[1] * s[0];
+    if denom.abs() <= 1e-12 * (r[0].hypot(r[1]) * s[0].hypot(s[1])).max(1e-300) { return None; }
+    let qp = [q1[0] - p1[0], q1[1] - p1[1]];
+    let u = (qp[0] * s[1] - qp[1] * s[0]) / denom;
+    let v = (qp[0] * r[1] - qp[1] * r[0]) / denom;
+    let (tol_u, tol_v) = (eps / r[0].hypot(r[1]).max(eps), eps / s[0].hypot(s[1]).max(eps));
+    if u < -tol_u || u > 1.0 + tol_u || v < -tol_v || v > 1.0 + tol_v { return None; }
+
+    Some((u.clamp(0.0, 1.0), v.clamp(0.0, 1.0)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::Point;
+    use crate::shapes::{FillRule, Path};
+
+    #[test]
+    fn contains_curved_shapes() {
+        let blob = Path::from_str("b", "M 0 0 C 0 -20 40 -20 40 0 C 40 20 0 20 0 0 Z").unwrap();
+        assert!(blob.contains(Point::new(20.0, 0.0), FillRule::NonZero));
+        assert!(blob.contains(Point::new(20.0, 14.0), FillRule::EvenOdd));
+        assert!(!blob.contains(Point::new(20.0, 16.0), FillRule::NonZero));
+        assert!(!blob.contains(Point::new(-1.0, 0.0), FillRule::NonZero));
+        // ---------- an open path is filled as if closed
+        let open = Path::from_str("o", "M 0 0 H 10 V 10").unwrap();
+        assert!(open.contains(Point::new(8.0, 2.0), FillRule::NonZero));
+        assert!(!open.contains(Point::new(2.0, 8.0), FillRule::NonZero));
+    }
+
+    #[test]
+    fn nearest_point_on_curve() {
+        let circle = Path::from_str("c", "M 10 0 A 10 10 0 0 1 -10 0 A 10 10 0 0 1 10 0 Z").unwrap();
+        let (d, Point { x, y }) = circle.distance_to(Point::new(30.0, 40.0)).unwrap();
+        assert!((d - 40.0).abs() < 1e-2);
+        assert!((x - 6.0).abs() < 1e-2 && (y - 8.0).abs() < 1e-2);
+        assert_eq!(Path::new("empty").distance_to(Point::ORIGIN), None);
+    }
+
+    #[test]
+    fn crossing_curves() {
+        let wave = Path::from_str("w", "M 0 0 C 10 20 20 -20 30 0").unwrap();
+        let axis = Path::from_str("a", "M -5 0 H 35").unwrap();
+        let hits = wave.intersections(&axis);
+        assert_eq!(hits.len(), 3);
+        for h in &hits {
+            assert!(h.point.y.abs() < 1e-4);
+            let on_axis = axis.point_at_length(h.other.length).unwrap();
+            assert!((on_axis.x - h.point.x).abs() < 1e-3);
+        }
+        assert!((hits[1].point.x - 15.0).abs() < 1e-3 && (hits[1].this.t - 0.5).abs() < 1e-4);
+        // ---------- the closing line is a segment, unless the sub-path is already closed by its last command
+        let vertical = Path::from_str("v", "M 5 -5 V 15").unwrap();
+        let square = Path::from_str("s", "M 0 0 H 10 V 10 H 0 Z").unwrap();
+        assert_eq!(square.intersections(&vertical)[1].this.segment, 2);
+        let across = Path::from_str("x", "M -5 5 H 15").unwrap();
+        assert_eq!(square.intersections(&across)[1].this.segment, 3);
+        let drawn_back = Path::from_str("b", "M 0 0 H 10 V 10 H 0 V 0 Z M 20 0 H 30").unwrap();
+        let hits = drawn_back.intersections(&Path::from_str("h", "M 25 -5 V 5").unwrap());
+        assert_eq!(hits[0].this.segment, 4);
+        let far = Path::from_str("f", "M 0 100 H 30").unwrap();
+        assert!(wave.intersections(&far).is_empty());
+        // ---------- a crossing at a joint is found once, but a path passing twice through a point crosses twice there
+        let corner = square.intersections(&Path::from_str("d", "M -5 -5 L 5 5").unwrap());
+        assert_eq!(corner.len(), 1);
+        let bow = Path::from_str("b", "M 0 0 L 10 10 L 10 0 L 0 10 Z").unwrap();
+        let twice = bow.intersections(&vertical);
+        assert_eq!(twice.iter().map(|h| h.this.segment).collect::<Vec<_>>(), [0, 2]);
+        assert!(twice.iter().all(|h| (h.point.x - 5.0).abs() < 1e-4 && (h.point.y - 5.0).abs() < 1e-4));
+    }
+}
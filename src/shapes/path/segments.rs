@@ -0,0 +1,256 @@
+//! Geometry of the segments of a canonical (``M``, ``L``, ``C``, ``Z``) path.
+//!
+//! Computations are done in double precision; coordinates are converted from and to `f32`
+//! only at the boundary of the public [`Path`] API.
+use crate::geometry::Point;
+use crate::shapes::{Path, PathCommand};
+
+pub(crate) type Pt = [f64; 2];
+
+/// Newton steps tried by [`Segment::t_at_length()`] before it falls back on bisection
+const NEWTON_STEPS: usize = 8;
+/// Bisection steps of [`Segment::t_at_length()`]; the bracket left by Newton's method is usually small already
+const BISECTION_STEPS: usize = 30;
+
+/// Gauss-Legendre nodes and weights on [-1, 1]
+const GAUSS_NODES: [(f64, f64); 8] = [
+    (-0.9602898564975363, 0.1012285362903763), (-0.7966664774136267, 0.2223810344533745),
+    (-0.525532409916329, 0.3137066458778873), (-0.1834346424956498, 0.362683783378362),
+    (0.1834346424956498, 0.362683783378362), (0.525532409916329, 0.3137066458778873),
+    (0.7966664774136267, 0.2223810344533745), (0.9602898564975363, 0.1012285362903763)];
+
+pub(crate) fn from_point(p: Point) -> Pt { [p.x as f64, p.y as f64] }
+
+pub(crate) fn to_point(p: Pt) -> Point { Point::new(p[0] as f32, p[1] as f32) }
+
+pub(crate) fn lerp(a: Pt, b: Pt, t: f64) -> Pt { [a[0] + (b[0] - a[0]) * t, a[1] + (b[1] - a[1]) * t] }
+
+pub(crate) fn sub(a: Pt, b: Pt) -> Pt { [a[0] - b[0], a[1] - b[1]] }
+
+pub(crate) fn norm(v: Pt) -> f64 { v[0].hypot(v[1]) }
+
+pub(crate) fn dist(a: Pt, b: Pt) -> f64 { norm(sub(a, b)) }
+
+/// A straight line or a cubic Bézier curve
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Segment {
+    Line(Pt, Pt),
+    Cubic(Pt, Pt, Pt, Pt),
+}
+
+impl Segment {
+    pub fn start(&self) -> Pt {
+        match *self { Segment::Line(p0, _) | Segment::Cubic(p0, ..) => p0 }
+    }
+
+    pub fn end(&self) -> Pt {
+        match *self { Segment::Line(_, p1) | Segment::Cubic(_, _, _, p1) => p1 }
+    }
+
+    pub fn point(&self, t: f64) -> Pt {
+        match *self {
+            Segment::Line(p0, p1) => lerp(p0, p1, t),
+            Segment::Cubic(p0, p1, p2, p3) => {
+                let mt = 1.0 - t;
+                let (a, b, c, d) = (mt * mt * mt, 3.0 * mt * mt * t, 3.0 * mt * t * t, t * t * t);
+                [a * p0[0] + b * p1[0] + c * p2[0] + d * p3[0], a * p0[1] + b * p1[1] + c * p2[1] + d * p3[1]]
+            }
+        }
+    }
+
+    pub fn derivative(&self, t: f64) -> Pt {
+        match *self {
+            Segment::Line(p0, p1) => sub(p1, p0),
+            Segment::Cubic(p0, p1, p2, p3) => {
+                let mt = 1.0 - t;
+                let (a, b, c) = (3.0 * mt * mt, 6.0 * mt * t, 3.0 * t * t);
+                [a * (p1[0] - p0[0]) + b * (p2[0] - p1[0]) + c * (p3[0] - p2[0]),
+                 a * (p1[1] - p0[1]) + b * (p2[1] - p1[1]) + c * (p3[1] - p2[1])]
+            }
+        }
+    }
+
+    /// Unit tangent at `t`; falls back on nearby points where the derivative vanishes, e.g. at a cusp
+    pub fn tangent(&self, t: f64) -> Pt {
+        let mut d = self.derivative(t);
+        if norm(d) < 1e-9 {
+            let (t0, t1) = ((t - 1e-3).max(0.0), (t + 1e-3).min(1.0));
+            d = sub(self.point(t1), self.point(t0));
+        }
+        if norm(d) < 1e-12 { d = sub(self.end(), self.start()); }
+        let n = norm(d);
+        if n < 1e-12 { [1.0, 0.0] } else { [d[0] / n, d[1] / n] }
+    }
+
+    /// Length of the part of this segment between parameters `t0` and `t1`
+    pub fn length_between(&self, t0: f64, t1: f64) -> f64 {
+        match *self {
+            Segment::Line(p0, p1) => dist(p0, p1) * (t1 - t0),
+            Segment::Cubic(..) => self.integrate_speed(t0, t1, 0),
+        }
+    }
+
+    pub fn length(&self) -> f64 { self.length_between(0.0, 1.0) }
+
+    /// The end points of this segment and the points where it turns back along the x or y axis;
+    /// their bounding box is the tight bounding box of the segment
+    pub fn extremes(&self) -> Vec<Pt> {
+        let mut out = vec![self.start(), self.end()];
+        if let Segment::Cubic(p0, p1, p2, p3) = *self {
+            for axis in 0..2 {
+                // ---------- roots of the derivative, a quadratic a t^2 + b t + c
+                let a = -p0[axis] + 3.0 * p1[axis] - 3.0 * p2[axis] + p3[axis];
+                let b = 2.0 * (p0[axis] - 2.0 * p1[axis] + p2[axis]);
+                let c = p1[axis] - p0[axis];
+                let roots = if a.abs() < 1e-12 {
+                    if b.abs() < 1e-12 { vec![] } else { vec![-c / b] }
+                } else {
+                    let disc = b * b - 4.0 * a * c;
+                    if disc < 0.0 { vec![] } else { vec![(-b + disc.sqrt()) / (2.0 * a), (-b - disc.sqrt()) / (2.0 * a)] }
+                };
+                out.extend(roots.into_iter().filter(|t| *t > 0.0 && *t < 1.0).map(|t| self.point(t)));
+            }
+        }
+
+        out
+    }
+
+    /// Adaptive Gauss-Legendre quadrature of the speed ``|B'(t)|``
+    fn integrate_speed(&self, t0: f64, t1: f64, depth: u32) -> f64 {
+        let gauss = |a: f64, b: f64| {
+            let (half, mid) = (0.5 * (b - a), 0.5 * (a + b));
+            GAUSS_NODES.iter().map(|&(x, w)| w * norm(self.derivative(mid + half * x))).sum::<f64>() * half
+        };
+        let whole = gauss(t0, t1);
+        let mid = 0.5 * (t0 + t1);
+        let halves = gauss(t0, mid) + gauss(mid, t1);
+        if depth >= 12 || (whole - halves).abs() <= 1e-9 * halves.max(1.0) {
+            halves
+        } else {
+            self.integrate_speed(t0, mid, depth + 1) + self.integrate_speed(mid, t1, depth + 1)
+        }
+    }
+
+    /// Parameter at which the length of this segment measured from its start reaches `s`
+    pub fn t_at_length(&self, s: f64) -> f64 { self.t_at_length_within(s, self.length()) }
+
+    /// [`t_at_length()`](Segment::t_at_length) for a segment whose length `total` is already known
+    pub fn t_at_length_within(&self, s: f64, total: f64) -> f64 {
+        if total <= 0.0 || s <= 0.0 { return 0.0; }
+        if s >= total { return 1.0; }
+        if let Segment::Line(..) = self { return s / total; }
+
+        // ---------- Newton's method; the length is updated by integrating only over each step
+        let tolerance = 1e-9 * total.max(1.0);
+        let (mut lo, mut hi) = (0.0, 1.0);
+        let mut t = s / total;
+        let mut length = self.length_between(0.0, t);
+        for _ in 0..NEWTON_STEPS {
+            let f = length - s;
+            if f.abs() < tolerance { return t; }
+            if f > 0.0 { hi = t; } else { lo = t; }
+            let speed = norm(self.derivative(t));
+            let next = t - f / speed;
+            if !(speed > 1e-12 && next > lo && next < hi) { break; }
+            length += self.length_between(t, next);
+            t = next;
+        }
+        // ---------- bisection where Newton's method leaves the bracket or doesn't converge
+        for _ in 0..BISECTION_STEPS {
+            let mid = 0.5 * (lo + hi);
+            let f = self.length_between(0.0, mid) - s;
+            if f.abs() < tolerance { return mid; }
+            if f > 0.0 { hi = mid; } else { lo = mid; }
+        }
+        0.5 * (lo + hi)
+    }
+
+    /// Splits this segment at parameter `t` with de Casteljau's algorithm
+    pub fn split(&self, t: f64) -> (Segment, Segment) {
+        match *self {
+            Segment::Line(p0, p1) => {
+                let m = lerp(p0, p1, t);
+                (Segment::Line(p0, m), Segment::Line(m, p1))
+            }
+            Segment::Cubic(p0, p1, p2, p3) => {
+                let (a, b, c) = (lerp(p0, p1, t), lerp(p1, p2, t), lerp(p2, p3, t));
+                let (d, e) = (lerp(a, b, t), lerp(b, c, t));
+                let m = lerp(d, e, t);
+                (Segment::Cubic(p0, a, d, m), Segment::Cubic(m, e, c, p3))
+            }
+        }
+    }
+}
+
+/// A continuous run of segments, started by a move command
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SubPath {
+    pub start: Pt,
+    pub segments: Vec<Segment>,
+    /// true if the sub-path ends with a ``Z`` command
+    pub closed: bool,
+}
+
+/// Splits a path into sub-paths of lines and cubic Bézier curves.
+///
+/// The path is normalized first; a closed sub-path gets an explicit closing line
+/// unless it already ends at its start point.
+pub(crate) fn subpaths(path: &Path) -> Vec<SubPath> {
+    let mut out: Vec<SubPath> = vec![];
+    let mut current = [0.0, 0.0];
+    let open_subpath = |out: &mut Vec<SubPath>, at: Pt| {
+        out.push(SubPath { start: at, segments: vec![], closed: false });
+    };
+    for command in path.normalize().d {
+        match command {
+            PathCommand::MoveTo(x, y) => {
+                current = [x as f64, y as f64];
+                open_subpath(&mut out, current);
+            }
+            PathCommand::LineTo(x, y) => {
+                if out.last().is_none_or(|s| s.closed) { open_subpath(&mut out, current); }
+                let p = [x as f64, y as f64];
+                out.last_mut().unwrap().segments.push(Segment::Line(current, p));
+                current = p;
+            }
+            PathCommand::CurveTo(x1, y1, x2, y2, x, y) => {
+                if out.last().is_none_or(|s| s.closed) { open_subpath(&mut out, current); }
+                let p = [x as f64, y as f64];
+                out.last_mut().unwrap().segments.push(
+                    Segment::Cubic(current, [x1 as f64, y1 as f64], [x2 as f64, y2 as f64], p));
+                current = p;
+            }
+            PathCommand::Close => {
+                if let Some(sp) = out.last_mut().filter(|s| !s.closed) {
+                    if current != sp.start { sp.segments.push(Segment::Line(current, sp.start)); }
+                    sp.closed = true;
+                    current = sp.start;
+                }
+            }
+            _ => unreachable!("a normalized path contains only M, L, C and Z commands"),
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shapes::path::segments::Segment;
+
+    #[test]
+    fn cubic_length() {
+        // --- a straight cubic with uneven control points has the length of its chord
+        let s = Segment::Cubic([0.0, 0.0], [1.0, 0.0], [9.0, 0.0], [10.0, 0.0]);
+        assert!((s.length() - 10.0).abs() < 1e-9);
+        let t = s.t_at_length(5.0);
+        assert!((s.point(t)[0] - 5.0).abs() < 1e-7);
+        // --- a curved cubic with a cusp-like turn
+        let c = Segment::Cubic([0.0, 0.0], [100.0, 50.0], [-50.0, 50.0], [50.0, 0.0]);
+        let total = c.length();
+        for k in 1..10 {
+            let target = total * k as f64 / 10.0;
+            assert!((c.length_between(0.0, c.t_at_length_within(target, total)) - target).abs() < 1e-6);
+        }
+    }
+}
@@ -0,0 +1,175 @@
+//! Reduction of the number of points of polylines.
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use crate::geometry::Point;
+use crate::shapes::path::flatten::distance_to_segment;
+use crate::shapes::path::segments::from_point;
+use crate::shapes::{Path, Polyline};
+
+impl Polyline {
+    /// Straight-line path through the points of this polyline
+    pub fn to_path(&self, id: &str) -> Path {
+        let mut p = Path::new(id);
+        for (i, &Point { x, y }) in self.points.iter().enumerate() {
+            p = if i == 0 { p.move_to(x, y) } else { p.line_to(x, y) };
+        }
+        if self.closed && !self.points.is_empty() { p = p.close(); }
+
+        p
+    }
+
+    /// Simplifies this polyline with the Ramer–Douglas–Peucker algorithm.
+    ///
+    /// Points are dropped as long as the polyline doesn't move farther than `epsilon` from any
+    /// of the original points. The first and the last point are always kept.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::geometry::Point;
+    /// use visualife::shapes::Polyline;
+    /// let points = [(0.0, 0.0), (1.0, 0.1), (2.0, -0.1), (3.0, 5.0), (4.0, 0.0)].map(Point::from).to_vec();
+    /// let zigzag = Polyline { points, closed: false };
+    /// let kept = [(0.0, 0.0), (2.0, -0.1), (3.0, 5.0), (4.0, 0.0)].map(Point::from);
+    /// assert_eq!(zigzag.simplify_rdp(0.5).points, kept);
+    /// ```
+    pub fn simplify_rdp(&self, epsilon: f32) -> Polyline {
+        let mut points: Vec<[f64; 2]> = self.points.iter().copied().map(from_point).collect();
+        // ---------- a closed polyline is simplified as an open one that returns to its first point
+        if self.closed && !points.is_empty() { points.push(points[0]); }
+        let n = points.len();
+        if n < 3 { return self.clone(); }
+
+        let mut keep = vec![false; n];
+        keep[0] = true;
+        keep[n - 1] = true;
+        let mut stack = vec![(0, n - 1)];
+        while let Some((first, last)) = stack.pop() {
+            let (mut worst, mut worst_dist) = (first, 0.0);
+            for i in first + 1..last {
+                let d = distance_to_segment(points[i], points[first], points[last]);
+                if d > worst_dist { (worst, worst_dist) = (i, d); }
+            }
+            if worst_dist > epsilon as f64 {
+                keep[worst] = true;
+                stack.push((first, worst));
+                stack.push((worst, last));
+            }
+        }
+        if self.closed { keep[n - 1] = false; }
+
+        self.keeping(&keep)
+    }
+
+    /// Simplifies this polyline with the Visvalingam–Whyatt algorithm.
+    ///
+    /// The point forming the smallest triangle with its neighbours is removed repeatedly, until
+    /// every remaining triangle has an area of at least `min_area`. This tends to keep the overall
+    /// shape better than [`Polyline::simplify_rdp()`] for noisy data.
+    /// Open polylines keep their end points; closed ones keep at least three points.
+    pub fn simplify_visvalingam(&self, min_area: f32) -> Polyline {
+        let points = &self.points;
+        let n = points.len();
+        let min_points = if self.closed { 3 } else { 2 };
+        if n <= min_points { return self.clone(); }
+
+        let mut prev: Vec<usize> = (0..n).map(|i| (i + n - 1) % n).collect();
+        let mut next: Vec<usize> = (0..n).map(|i| (i + 1) % n).collect();
+        let removable = |i: usize| self.closed || (i != 0 && i != n - 1);
+        let area = |p: &[usize], nx: &[usize], i: usize| triangle_area(points[p[i]], points[i], points[nx[i]]);
+        let mut heap = BinaryHeap::new();
+        for i in (0..n).filter(|&i| removable(i)) {
+            heap.push(Candidate { area: area(&prev, &next, i), index: i });
+        }
+
+        let mut removed = vec![false; n];
+        let mut remaining = n;
+        while let Some(Candidate { area: a, index }) = heap.pop() {
+            if removed[index] || remaining <= min_points { continue; }
+            // ---------- skip entries made stale by removal of a neighbour
+            if a != area(&prev, &next, index) { continue; }
+            if a >= min_area as f64 { break; }
+            removed[index] = true;
+            remaining -= 1;
+            let (p, nx) = (prev[index], next[index]);
+            next[p] = nx;
+            prev[nx] = p;
+            for j in [p, nx] {
+                if removable(j) { heap.push(Candidate { area: area(&prev, &next, j), index: j }); }
+            }
+        }
+        let keep: Vec<bool> = removed.iter().map(|r| !r).collect();
+
+        self.keeping(&keep)
+    }
+
+    fn keeping(&self, keep: &[bool]) -> Polyline {
+        let points = self.points.iter().zip(keep).filter(|(_, &k)| k).map(|(p, _)| *p).collect();
+        Polyline { points, closed: self.closed }
+    }
+}
+
+fn triangle_area(a: Point, b: Point, c: Point) -> f64 {
+    let ([ax, ay], [bx, by], [cx, cy]) = (from_point(a), from_point(b), from_point(c));
+    ((bx - ax) * (cy - ay) - (cx - ax) * (by - ay)).abs() / 2.0
+}
+
+/// Entry of the Visvalingam priority queue; the smallest area comes out first
+struct Candidate { area: f64, index: usize }
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool { self.cmp(other) == Ordering::Equal }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.area.total_cmp(&self.area).then_with(|| other.index.cmp(&self.index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::Point;
+    use crate::shapes::Polyline;
+
+    fn noisy_sine(n: usize) -> Polyline {
+        let points = (0..n).map(|i| {
+            let x = i as f32 * 0.01;
+            Point::new(x, x.sin() + if i % 2 == 0 { 0.001 } else { -0.001 })
+        }).collect();
+        Polyline { points, closed: false }
+    }
+
+    fn square() -> Polyline {
+        Polyline { points: [(0.0, 0.0), (5.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)].map(Point::from).to_vec(), closed: true }
+    }
+
+    #[test]
+    fn rdp_keeps_the_shape() {
+        let dense = noisy_sine(1000);
+        let simple = dense.simplify_rdp(0.01);
+        assert!(simple.points.len() < 50, "{} points left", simple.points.len());
+        assert_eq!(simple.points.first(), dense.points.first());
+        assert_eq!(simple.points.last(), dense.points.last());
+        let square = square();
+        assert_eq!(square.simplify_rdp(0.1).points.len(), 4);
+        assert!(square.simplify_rdp(0.1).closed);
+    }
+
+    #[test]
+    fn visvalingam_removes_small_triangles() {
+        let dense = noisy_sine(1000);
+        let simple = dense.simplify_visvalingam(0.001);
+        assert!(simple.points.len() < 60, "{} points left", simple.points.len());
+        assert_eq!(simple.points.first(), dense.points.first());
+        assert_eq!(simple.points.last(), dense.points.last());
+        let square = square();
+        assert_eq!(square.simplify_visvalingam(1.0).points, [(0.0, 0.0), (10.0, 0.0), (10.0, 10.0), (0.0, 10.0)].map(Point::from));
+        assert_eq!(square.simplify_visvalingam(1000.0).points.len(), 3);
+    }
+}
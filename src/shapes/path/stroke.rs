@@ -0,0 +1,284 @@
+//! Outlines of stroked paths and offset paths.
+use std::f64::consts::PI;
+use std::fmt;
+use crate::shapes::path::boolean::boolean_rings;
+use crate::shapes::path::segments::{from_point, Pt};
+use crate::shapes::{BooleanOp, FillRule, Path};
+
+/// Flattening tolerance used for outlines and offsets
+const STROKE_TOLERANCE: f32 = 0.01;
+
+/// Shape drawn where two segments of a stroke meet, as the SVG ``stroke-linejoin`` property
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LineJoin {
+    /// sharp corner; replaced by a bevel when the ratio of the miter length to the stroke width exceeds the limit
+    Miter(f32),
+    Round,
+    Bevel,
+}
+
+impl Default for LineJoin {
+    /// The SVG default: a miter with limit 4
+    fn default() -> Self { LineJoin::Miter(4.0) }
+}
+
+impl fmt::Display for LineJoin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LineJoin::Miter(_) => write!(f, "miter"),
+            LineJoin::Round => write!(f, "round"),
+            LineJoin::Bevel => write!(f, "bevel"),
+        }
+    }
+}
+
+/// Shape drawn at the ends of an open stroke, as the SVG ``stroke-linecap`` property
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum LineCap {
+    #[default]
+    Butt,
+    Round,
+    Square,
+}
+
+impl fmt::Display for LineCap {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LineCap::Butt => write!(f, "butt"),
+            LineCap::Round => write!(f, "round"),
+            LineCap::Square => write!(f, "square"),
+        }
+    }
+}
+
+impl Path {
+    /// Filled outline of this path stroked with the given width, joins, caps and dash pattern.
+    ///
+    /// The dash pattern is applied as by [`Path::dash()`].
+    /// Curves are flattened first, so the outline is made of straight lines only. The result is the
+    /// area a renderer would paint, merged into non-overlapping polygons; it keeps the id and the style of this path.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::shapes::{LineCap, LineJoin, Path};
+    /// let line = Path::from_str("l", "M 0 0 H 10").unwrap();
+    /// let outline = line.stroke_outline(2.0, LineJoin::Bevel, LineCap::Square, None);
+    /// // ---------- a 12 x 2 rectangle
+    /// assert_eq!(outline.d.len(), 5);
+    /// assert_eq!(outline.sample(5).iter().map(|p| p.x).fold(f32::MAX, f32::min), -1.0);
+    /// ```
+    pub fn stroke_outline(&self, width: f32, join: LineJoin, cap: LineCap, dash: Option<&[f32]>) -> Path {
+        let polylines = self.stroke_polylines(dash);
+        let pieces = stroke_pieces(&polylines, (width / 2.0).abs() as f64, join, cap);
+        self.with_rings(boolean_rings(&pieces, BooleanOp::Union, FillRule::NonZero))
+    }
+
+    /// Path enclosing the points within `distance` from the area of this path.
+    ///
+    /// Closed sub-paths grow by a positive `distance` and shrink by a negative one; corners are shaped by `join`.
+    /// Open sub-paths become their outline, as if stroked with the width of twice the distance, with
+    /// `cap` at their ends. Curves are flattened first, so the result is made of straight lines only.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::shapes::{LineCap, LineJoin, Path};
+    /// let square = Path::from_str("s", "M 0 0 H 10 V 10 H 0 Z").unwrap();
+    /// let inner = square.offset(-2.0, LineJoin::Miter(4.0), LineCap::Butt);
+    /// assert!(inner.d.contains(&visualife::shapes::PathCommand::LineTo(8.0, 8.0)));
+    /// assert_eq!(inner.length(), 24.0);
+    /// ```
+    pub fn offset(&self, distance: f32, join: LineJoin, cap: LineCap) -> Path {
+        let h = distance.abs() as f64;
+        let polylines = self.stroke_polylines(None);
+        let (closed, open): (Vec<_>, Vec<_>) = polylines.into_iter().partition(|(_, is_closed)| *is_closed);
+        // ---------- operand 0: the area of the closed sub-paths, operand 1: the band around their outlines
+        let mut rings: Vec<(usize, Vec<Pt>)> = closed.iter().map(|(points, _)| (0, points.clone())).collect();
+        rings.extend(stroke_pieces(&closed, h, join, cap).into_iter().map(|(_, ring)| (1, ring)));
+        let op = if distance >= 0.0 { BooleanOp::Union } else { BooleanOp::Difference };
+        let mut result: Vec<(usize, Vec<Pt>)> = boolean_rings(&rings, op, FillRule::NonZero)
+            .into_iter().map(|ring| (0, ring)).collect();
+        if open.is_empty() { return self.with_rings(result.into_iter().map(|(_, ring)| ring).collect()); }
+
+        result.extend(stroke_pieces(&open, h, join, cap));
+        self.with_rings(boolean_rings(&result, BooleanOp::Union, FillRule::NonZero))
+    }
+
+    /// Flattened sub-paths of this path, cut into dashes when a pattern is given
+    fn stroke_polylines(&self, dash: Option<&[f32]>) -> Vec<(Vec<Pt>, bool)> {
+        let source = match dash {
+            Some(pattern) => self.dash(pattern),
+            None => self.clone(),
+        };
+        let mut out = vec![];
+        for pl in source.flatten(STROKE_TOLERANCE) {
+            let mut points: Vec<Pt> = pl.points.iter().copied().map(from_point).collect();
+            points.dedup();
+            if pl.closed && points.len() > 1 && points.first() == points.last() { points.pop(); }
+            let closed = pl.closed && points.len() > 2;
+            out.push((points, closed));
+        }
+
+        out
+    }
+}
+
+/// Polygons whose union is the stroke of the given polylines with half-width `h`
+fn stroke_pieces(polylines: &[(Vec<Pt>, bool)], h: f64, join: LineJoin, cap: LineCap) -> Vec<(usize, Vec<Pt>)> {
+    let mut pieces: Vec<Vec<Pt>> = vec![];
+    if h == 0.0 { return vec![]; }
+    for (points, closed) in polylines {
+        let n = points.len();
+        if n == 0 { continue; }
+        if n == 1 || points.iter().all(|p| p == &points[0]) {
+            // ---------- a zero-length sub-path shows its caps only
+            let c = points[0];
+            match cap {
+                LineCap::Round => pieces.push(circle(c, h)),
+                LineCap::Square => pieces.push(vec![[c[0] - h, c[1] - h], [c[0] + h, c[1] - h], [c[0] + h, c[1] + h], [c[0] - h, c[1] + h]]),
+                LineCap::Butt => {}
+            }
+            continue;
+        }
+        let segments = if *closed { n } else { n - 1 };
+        for i in 0..segments {
+            let (a, b) = (points[i], points[(i + 1) % n]);
+            let nrm = normal(a, b);
+            pieces.push(vec![add(a, nrm, h), add(a, nrm, -h), add(b, nrm, -h), add(b, nrm, h)]);
+        }
+        let joints: Vec<usize> = if *closed { (0..n).collect() } else { (1..n - 1).collect() };
+        for i in joints {
+            let (a, v, b) = (points[(i + n - 1) % n], points[i], points[(i + 1) % n]);
+            if let Some(piece) = join_piece(a, v, b, h, join) { pieces.push(piece); }
+        }
+        if !closed {
+            for (end, next) in [(points[0], points[1]), (points[n - 1], points[n - 2])] {
+                match cap {
+                    LineCap::Round => pieces.push(circle(end, h)),
+                    LineCap::Square => {
+                        let nrm = normal(next, end);
+                        let u = [nrm[1], -nrm[0]];
+                        let tip = add(end, u, h);
+                        pieces.push(vec![add(end, nrm, h), add(end, nrm, -h), add(tip, nrm, -h), add(tip, nrm, h)]);
+                    }
+                    LineCap::Butt => {}
+                }
+            }
+        }
+    }
+
+    // ---------- the union with the non-zero rule needs all the pieces wound the same way
+    pieces.into_iter().map(|mut ring| {
+        if signed_area(&ring) < 0.0 { ring.reverse(); }
+        (0, ring)
+    }).collect()
+}
+
+/// Polygon filling the outer corner where the segments `av` and `vb` meet
+fn join_piece(a: Pt, v: Pt, b: Pt, h: f64, join: LineJoin) -> Option<Vec<Pt>> {
+    let (n1, n2) = (normal(a, v), normal(v, b));
+    let turn = n1[0] * n2[1] - n1[1] * n2[0];
+    let cos = n1[0] * n2[0] + n1[1] * n2[1];
+    if turn.abs() < 1e-12 && cos > 0.0 { return None; }
+    // ---------- the outer side of a left turn is on the right
+    let side = if turn > 0.0 { -h } else { h };
+    let (p1, p2) = (add(v, n1, side), add(v, n2, side));
+    match join {
+        LineJoin::Round => Some(circle(v, h)),
+        LineJoin::Bevel => Some(vec![v, p1, p2]),
+        LineJoin::Miter(limit) => {
+            // ---------- the miter length relative to the stroke width is 1 / cos(phi / 2), phi being the turning angle
+            let half_cos = ((1.0 + cos) / 2.0).max(0.0).sqrt();
+            if half_cos * limit as f64 >= 1.0 {
+                let bisector = [n1[0] + n2[0], n1[1] + n2[1]];
+                let len = bisector[0].hypot(bisector[1]);
+                let tip = add(v, [bisector[0] / len, bisector[1] / len], side / half_cos);
+                Some(vec![v, p1, tip, p2])
+            } else {
+                Some(vec![v, p1, p2])
+            }
+        }
+    }
+}
+
+/// Left-hand unit normal of the direction from `a` to `b`
+fn normal(a: Pt, b: Pt) -> Pt {
+    let (dx, dy) = (b[0] - a[0], b[1] - a[1]);
+    let len = dx.hypot(dy);
+    [-dy / len, dx / len]
+}
+
+fn add(p: Pt, v: Pt, scale: f64) -> Pt { [p[0] + v[0] * scale, p[1] + v[1] * scale] }
+
+/// Polygon inscribed in a circle, deviating from it by no more than the stroke tolerance
+fn circle(c: Pt, r: f64) -> Vec<Pt> {
+    let step = 2.0 * (1.0 - STROKE_TOLERANCE as f64 / r).clamp(-1.0, 1.0).acos();
+    let n = ((2.0 * PI / step.max(1e-3)).ceil() as usize).max(8);
+    (0..n).map(|i| {
+        let a = 2.0 * PI * i as f64 / n as f64;
+        [c[0] + r * a.cos(), c[1] + r * a.sin()]
+    }).collect()
+}
+
+fn signed_area(ring: &[Pt]) -> f64 {
+    (0..ring.len()).map(|i| {
+        let (a, b) = (ring[i], ring[(i + 1) % ring.len()]);
+        a[0] * b[1] - b[0] * a[1]
+    }).sum::<f64>() / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shapes::{LineCap, LineJoin, Path};
+
+    fn area(p: &Path) -> f32 {
+        p.flatten(0.01).iter().map(|pl| {
+            let n = pl.points.len();
+            (0..n).map(|i| {
+                let (a, b) = (pl.points[i], pl.points[(i + 1) % n]);
+                a.to_vector().cross(b.to_vector())
+            }).sum::<f32>() / 2.0
+        }).sum()
+    }
+
+    #[test]
+    fn joins_and_caps() {
+        // ---------- an L-shaped polyline with legs of 10, stroked with width 2
+        let l = Path::from_str("l", "M 0 10 V 0 H 10").unwrap();
+        let bevel = area(&l.stroke_outline(2.0, LineJoin::Bevel, LineCap::Butt, None));
+        let miter = area(&l.stroke_outline(2.0, LineJoin::Miter(4.0), LineCap::Butt, None));
+        let round = area(&l.stroke_outline(2.0, LineJoin::Round, LineCap::Butt, None));
+        assert!((miter.abs() - 40.0).abs() < 1e-3);
+        assert!((bevel.abs() - 39.5).abs() < 1e-3);
+        assert!(bevel.abs() < round.abs() && round.abs() < miter.abs());
+        // ---------- a sharp corner exceeds the miter limit and is beveled
+        let sharp = Path::from_str("s", "M 0 0 L 10 1 L 0 2").unwrap();
+        assert_eq!(area(&sharp.stroke_outline(1.0, LineJoin::Miter(4.0), LineCap::Butt, None)),
+                   area(&sharp.stroke_outline(1.0, LineJoin::Bevel, LineCap::Butt, None)));
+
+        let line = Path::from_str("l", "M 0 0 H 10").unwrap();
+        assert!((area(&line.stroke_outline(2.0, LineJoin::Bevel, LineCap::Square, None)).abs() - 24.0).abs() < 1e-3);
+        let round = area(&line.stroke_outline(2.0, LineJoin::Bevel, LineCap::Round, None)).abs();
+        assert!((round - (20.0 + std::f32::consts::PI)).abs() < 0.05);
+    }
+
+    #[test]
+    fn dashes() {
+        let line = Path::from_str("l", "M 0 0 H 10").unwrap();
+        let dashed = line.stroke_outline(1.0, LineJoin::Bevel, LineCap::Butt, Some(&[2.0, 1.0]));
+        // ---------- dashes at 0-2, 3-5, 6-8 and 9-10
+        assert_eq!(dashed.d.iter().filter(|c| c.to_string().starts_with('M')).count(), 4);
+        assert!((area(&dashed).abs() - 7.0).abs() < 1e-3);
+        let solid = line.stroke_outline(1.0, LineJoin::Bevel, LineCap::Butt, Some(&[0.0]));
+        assert!((area(&solid).abs() - 10.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn offsets() {
+        let square = Path::from_str("s", "M 0 0 H 10 V 10 H 0 Z").unwrap();
+        assert!((area(&square.offset(1.0, LineJoin::Miter(4.0), LineCap::Butt)).abs() - 144.0).abs() < 1e-3);
+        let rounded = area(&square.offset(1.0, LineJoin::Round, LineCap::Butt)).abs();
+        assert!((rounded - (140.0 + std::f32::consts::PI)).abs() < 0.05);
+        assert!((area(&square.offset(-1.0, LineJoin::Round, LineCap::Butt)).abs() - 64.0).abs() < 1e-3);
+        assert!(square.offset(-6.0, LineJoin::Round, LineCap::Butt).d.is_empty());
+    }
+}
@@ -0,0 +1,122 @@
+//! Transformation of path geometry.
+use crate::geometry::Point;
+use crate::shapes::{Path, PathCommand, Transform};
+
+impl Path {
+    /// Returns a copy of this path with `t` applied to its geometry.
+    ///
+    /// The result is an absolute path. ``H`` and ``V`` commands become ``L`` since they generally
+    /// don't stay axis-aligned. Elliptical arcs get new radii and axis rotation, so they remain
+    /// exact under non-uniform scaling and skew; the sweep flag is flipped by a mirroring transform.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::shapes::{Path, PathCommand, Transform};
+    /// let p = Path::from_str("p", "M 0 0 h 10 a 5 5 0 0 1 0 10").unwrap();
+    /// let q = p.transform(&Transform::scale(2.0, 1.0).then(&Transform::translate(1.0, 1.0)));
+    /// assert_eq!(q.d, vec![PathCommand::MoveTo(1.0, 1.0), PathCommand::LineTo(21.0, 1.0),
+    ///     PathCommand::EllipticalArcTo(10.0, 5.0, 0.0, false, true, 21.0, 11.0)]);
+    /// ```
+    pub fn transform(&self, t: &Transform) -> Path {
+        let at = |x: f32, y: f32| t.apply(Point::new(x, y)).into();
+        let mut d = Vec::with_capacity(self.d.len());
+        let (mut x, mut y) = (0.0, 0.0);
+        let (mut sx, mut sy) = (0.0, 0.0);
+        for command in self.to_absolute().d {
+            let transformed = match command {
+                PathCommand::MoveTo(nx, ny) => {
+                    (sx, sy) = (nx, ny);
+                    let (px, py) = at(nx, ny);
+                    PathCommand::MoveTo(px, py)
+                }
+                PathCommand::LineTo(nx, ny) => { let (px, py) = at(nx, ny); PathCommand::LineTo(px, py) }
+                PathCommand::HorizontalTo(nx) => { let (px, py) = at(nx, y); PathCommand::LineTo(px, py) }
+                PathCommand::VerticalTo(ny) => { let (px, py) = at(x, ny); PathCommand::LineTo(px, py) }
+                PathCommand::CurveTo(x1, y1, x2, y2, nx, ny) => {
+                    let ((ax, ay), (bx, by), (px, py)) = (at(x1, y1), at(x2, y2), at(nx, ny));
+                    PathCommand::CurveTo(ax, ay, bx, by, px, py)
+                }
+                PathCommand::SmoothCurveTo(x2, y2, nx, ny) => {
+                    let ((bx, by), (px, py)) = (at(x2, y2), at(nx, ny));
+                    PathCommand::SmoothCurveTo(bx, by, px, py)
+                }
+                PathCommand::QuadraticBezierCurveTo(x1, y1, nx, ny) => {
+                    let ((ax, ay), (px, py)) = (at(x1, y1), at(nx, ny));
+                    PathCommand::QuadraticBezierCurveTo(ax, ay, px, py)
+                }
+                PathCommand::SmoothQuadraticBezierCurveTo(nx, ny) => {
+                    let (px, py) = at(nx, ny);
+                    PathCommand::SmoothQuadraticBezierCurveTo(px, py)
+                }
+                PathCommand::EllipticalArcTo(rx, ry, rot, large, sweep, nx, ny) => {
+                    let (px, py) = at(nx, ny);
+                    let (rx, ry, rot) = transform_ellipse(t, rx.abs(), ry.abs(), rot);
+                    PathCommand::EllipticalArcTo(rx, ry, rot, large, sweep != (t.determinant() < 0.0), px, py)
+                }
+                PathCommand::Close => PathCommand::Close,
+                _ => unreachable!("to_absolute() leaves only absolute commands"),
+            };
+            // ---------- track the current point in the untransformed coordinates
+            match command {
+                PathCommand::HorizontalTo(nx) => x = nx,
+                PathCommand::VerticalTo(ny) => y = ny,
+                PathCommand::Close => (x, y) = (sx, sy),
+                PathCommand::MoveTo(nx, ny) | PathCommand::LineTo(nx, ny)
+                | PathCommand::CurveTo(_, _, _, _, nx, ny) | PathCommand::SmoothCurveTo(_, _, nx, ny)
+                | PathCommand::QuadraticBezierCurveTo(_, _, nx, ny) | PathCommand::SmoothQuadraticBezierCurveTo(nx, ny)
+                | PathCommand::EllipticalArcTo(_, _, _, _, _, nx, ny) => (x, y) = (nx, ny),
+                _ => {}
+            }
+            d.push(transformed);
+        }
+
+        self.with_commands(d)
+    }
+}
+
+/// Radii and axis rotation (in degrees) of an ellipse after the linear part of `t` is applied to it.
+///
+/// The ellipse is the image of the unit circle under `M = T * R(rot) * diag(rx, ry)`; its semi-axes are the
+/// singular values of `M`, found from the eigen-decomposition of the symmetric matrix `M * M^T`.
+fn transform_ellipse(t: &Transform, rx: f32, ry: f32, rot: f32) -> (f32, f32, f32) {
+    let (sin, cos) = (rot as f64).to_radians().sin_cos();
+    let (ta, tb, tc, td) = (t.a as f64, t.b as f64, t.c as f64, t.d as f64);
+    let (rx, ry) = (rx as f64, ry as f64);
+    // ---------- columns of M: images of the two semi-axes of the ellipse
+    let (m00, m10) = (rx * (ta * cos + tc * sin), rx * (tb * cos + td * sin));
+    let (m01, m11) = (ry * (-ta * sin + tc * cos), ry * (-tb * sin + td * cos));
+    let s00 = m00 * m00 + m01 * m01;
+    let s11 = m10 * m10 + m11 * m11;
+    let s01 = m00 * m10 + m01 * m11;
+    let mean = (s00 + s11) / 2.0;
+    let spread = (((s00 - s11) / 2.0).powi(2) + s01 * s01).sqrt();
+    let major = (mean + spread).max(0.0).sqrt();
+    let minor = (mean - spread).max(0.0).sqrt();
+    let angle = if spread == 0.0 { 0.0 } else { 0.5 * (2.0 * s01).atan2(s00 - s11) };
+
+    (major as f32, minor as f32, angle.to_degrees() as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::shapes::path::flatten::distance_to_segment;
+    use crate::geometry::{Degrees, Point};
+    use crate::shapes::{Path, Transform};
+
+    #[test]
+    fn arcs_under_skew_and_mirror() {
+        let p = Path::from_str("p", "M 0 0 A 30 10 25 1 0 40 20 Q 50 30 60 20 T 80 0 Z").unwrap();
+        for t in [Transform::skew_x(Degrees(30.0)).then(&Transform::scale(1.5, 0.5)),
+                  Transform::scale(-1.0, 1.0).then(&Transform::rotate_around(Degrees(40.0), Point::new(10.0, 10.0)))] {
+            // ---------- every point of the transformed path must lie on the transformed original
+            let reference: Vec<[f64; 2]> = p.flatten(0.001)[0].points.iter()
+                .map(|&p| { let q = t.apply(p); [q.x as f64, q.y as f64] }).collect();
+            for Point { x, y } in p.transform(&t).sample(100) {
+                let d = (0..reference.len())
+                    .map(|i| distance_to_segment([x as f64, y as f64], reference[i], reference[(i + 1) % reference.len()]))
+                    .fold(f64::MAX, f64::min);
+                assert!(d < 0.01, "({}, {}) is {} away from the transformed path", x, y, d);
+            }
+        }
+    }
+}
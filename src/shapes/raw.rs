@@ -0,0 +1,79 @@
+use crate::draw_svg::{escape, push_attributes};
+use crate::geometry::BBox;
+use crate::ToSvg;
+
+/// An element that visualife has no type for, kept as it was read from an SVG document.
+///
+/// Raw elements hold elements such as ``<text>`` or ``<defs>``, and also the circles, rectangles, paths and groups
+/// whose geometry can't be read, or can't be moved into the coordinates of the drawing without changing how they look.
+/// Their attributes are written back unchanged, in the order they were read.
+///
+/// # Example
+/// ```
+/// use visualife::shapes::{RawElement, RawText};
+/// use visualife::ToSvg;
+/// let mut text = RawElement::new("text", vec![("id".to_string(), "t".to_string()), ("x".to_string(), "5".to_string())]);
+/// text.children.push(Box::new(RawText("a < b".to_string())));
+/// assert_eq!(text.to_svg(), r#"<text id="t" x="5">a &lt; b</text>"#);
+/// assert_eq!(text.id(), Some("t"));
+/// ```
+pub struct RawElement {
+    /// name of the element, with its namespace prefix if it has one
+    pub tag: String,
+    pub attributes: Vec<(String, String)>,
+    /// nested elements and text, in document order
+    pub children: Vec<Box<dyn ToSvg>>,
+}
+
+impl RawElement {
+    pub fn new(tag: &str, attributes: Vec<(String, String)>) -> Self {
+        RawElement { tag: tag.to_string(), attributes, children: vec![] }
+    }
+
+    /// Value of an attribute of this element
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+}
+
+impl ToSvg for RawElement {
+    fn id(&self) -> Option<&str> { self.attribute("id") }
+
+    fn tag(&self) -> Option<&str> { Some(&self.tag) }
+
+    fn class(&self) -> Option<&str> { self.attribute("class") }
+
+    fn children(&self) -> &[Box<dyn ToSvg>] { &self.children }
+
+    fn children_mut(&mut self) -> &mut [Box<dyn ToSvg>] { &mut self.children }
+
+    fn remove_child(&mut self, index: usize) -> Option<Box<dyn ToSvg>> {
+        (index < self.children.len()).then(|| self.children.remove(index))
+    }
+
+    /// Raw elements don't know their geometry
+    fn bbox(&self) -> Option<BBox> { None }
+
+    fn to_svg(&self) -> String {
+        let mut svg_string = format!("<{}", self.tag);
+        push_attributes(&self.attributes, &mut svg_string);
+        if self.children.is_empty() {
+            svg_string.push_str(" />");
+            return svg_string;
+        }
+        // ---------- children are written next to each other, since whitespace may matter in text
+        svg_string.push('>');
+        for child in &self.children {
+            svg_string.push_str(&child.to_svg());
+        }
+        svg_string.push_str(&format!("</{}>", self.tag));
+        svg_string
+    }
+}
+
+/// Character data of an SVG document, such as the content of a ``<text>`` or a ``<style>`` element
+pub struct RawText(pub String);
+
+impl ToSvg for RawText {
+    fn to_svg(&self) -> String { escape(&self.0, false) }
+}
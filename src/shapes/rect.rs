@@ -1,5 +1,7 @@
 use std::fmt;
-use crate::draw_svg::{close_element, ToSvg};
+use crate::draw_svg::{close_element, escape, push_attributes, push_class, ToSvg};
+use crate::geometry::{BBox, Degrees, Point};
+use crate::shapes::Transform;
 use crate::style::Style;
 
 
@@ -13,18 +15,25 @@ pub struct Rect {
     pub height: f32,
     pub rx: Option<f32>,
     pub ry: Option<f32>,
-    pub angle: f32,
+    /// rotation around the center of this rectangle; positive angles turn clockwise on screen
+    pub angle: Degrees,
     pub style: Style,
+    /// CSS class (or space-separated classes) of this rectangle
+    pub class: Option<String>,
+    /// attributes visualife has no field for, such as ``data-*`` ones, written as they are
+    pub attributes: Vec<(String, String)>,
 }
 
 impl Rect {
     pub fn new(id: &str, x: f32, y: f32, width: f32, height: f32) -> Self {
-        Rect { id: id.to_string(), x, y, width, height, rx: None, ry: None, angle: 0.0, style: Style::new(), }
+        Rect { id: id.to_string(), x, y, width, height, rx: None, ry: None, angle: Degrees(0.0), style: Style::new(), class: None, attributes: vec![] }
     }
 
     pub fn set_rx(&mut self, rx: f32) { self.rx = Some(rx); }
 
     pub fn set_ry(&mut self, ry: f32) { self.ry = Some(ry); }
+
+    pub fn center(&self) -> Point { Point::new(self.x + self.width / 2.0, self.y + self.height / 2.0) }
 }
 
 impl fmt::Display for Rect {
@@ -34,10 +43,28 @@ impl fmt::Display for Rect {
 }
 
 impl ToSvg for Rect {
+    fn id(&self) -> Option<&str> { Some(&self.id) }
+
+    fn tag(&self) -> Option<&str> { Some("rect") }
+
+    fn class(&self) -> Option<&str> { self.class.as_deref() }
+
+    fn style(&self) -> Option<&Style> { Some(&self.style) }
+
+    fn style_mut(&mut self) -> Option<&mut Style> { Some(&mut self.style) }
+
+    /// Box of the rectangle as drawn, i.e. after its rotation; rounded corners are not taken into account
+    fn bbox(&self) -> Option<BBox> {
+        let bbox = BBox::new(Point::new(self.x, self.y), Point::new(self.x + self.width, self.y + self.height));
+        if self.angle == Degrees(0.0) { return Some(bbox); }
+        let rotation = Transform::rotate_around(self.angle, self.center());
+        BBox::from_points(bbox.corners().map(|p| rotation.apply(p)))
+    }
+
     fn to_svg(&self) -> String {
         let mut svg_string = format!(
             r#"<rect id="{}" x="{}" y="{}" width="{}" height="{}""#,
-            self.id, self.x, self.y, self.width, self.height
+            escape(&self.id, true), self.x, self.y, self.width, self.height
         );
 
         if let Some(rx) = self.rx {
@@ -49,12 +76,12 @@ impl ToSvg for Rect {
         }
 
 
-        if self.angle != 0.0 {
-            svg_string.push_str(&format!(
-                r#" transform="rotate({} {} {})""#,
-                self.angle, self.x + self.width / 2.0, self.y + self.height / 2.0
-            ));
+        if self.angle != Degrees(0.0) {
+            let c = self.center();
+            svg_string.push_str(&format!(r#" transform="rotate({} {} {})""#, self.angle, c.x, c.y));
         }
+        push_class(&self.class, &mut svg_string);
+        push_attributes(&self.attributes, &mut svg_string);
         close_element(&self.style, &mut svg_string);
 
         svg_string
@@ -0,0 +1,221 @@
+use std::fmt;
+use std::str::FromStr;
+use crate::geometry::{Degrees, Point, Radians, Vector};
+
+/// A 2D affine transformation.
+///
+/// The transformation maps a point `(x, y)` to `(a*x + c*y + e, b*x + d*y + f)`, using the same
+/// coefficients as the SVG ``matrix(a b c d e f)`` transform function. Angles may be given in
+/// [`Degrees`](crate::geometry::Degrees), as in SVG, or in [`Radians`].
+///
+/// # Example
+/// ```
+/// use visualife::geometry::{Degrees, Point};
+/// use visualife::shapes::Transform;
+/// let t = Transform::rotate(Degrees(90.0)).then(&Transform::translate(10.0, 0.0));
+/// let p = t.apply(Point::new(1.0, 0.0));
+/// assert!((p.x - 10.0).abs() < 1e-6 && (p.y - 1.0).abs() < 1e-6);
+/// assert_eq!(Transform::scale(2.0, 3.0).to_string(), "matrix(2 0 0 3 0 0)");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Transform {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub e: f32,
+    pub f: f32,
+}
+
+impl Transform {
+    /// Transformation that leaves every point in place
+    pub const IDENTITY: Transform = Transform { a: 1.0, b: 0.0, c: 0.0, d: 1.0, e: 0.0, f: 0.0 };
+
+    pub fn matrix(a: f32, b: f32, c: f32, d: f32, e: f32, f: f32) -> Transform { Transform { a, b, c, d, e, f } }
+
+    pub fn translate(tx: f32, ty: f32) -> Transform { Transform::matrix(1.0, 0.0, 0.0, 1.0, tx, ty) }
+
+    pub fn scale(sx: f32, sy: f32) -> Transform { Transform::matrix(sx, 0.0, 0.0, sy, 0.0, 0.0) }
+
+    /// Rotation by `angle` around the origin; positive angles turn clockwise on screen
+    pub fn rotate(angle: impl Into<Radians>) -> Transform {
+        let (sin, cos) = angle.into().sin_cos();
+        Transform::matrix(cos, sin, -sin, cos, 0.0, 0.0)
+    }
+
+    /// Rotation by `angle` around the point `center`, as SVG ``rotate(angle cx cy)``
+    pub fn rotate_around(angle: impl Into<Radians>, center: Point) -> Transform {
+        Transform::translate(-center.x, -center.y).then(&Transform::rotate(angle)).then(&Transform::translate(center.x, center.y))
+    }
+
+    pub fn skew_x(angle: impl Into<Radians>) -> Transform { Transform::matrix(1.0, 0.0, angle.into().tan(), 1.0, 0.0, 0.0) }
+
+    pub fn skew_y(angle: impl Into<Radians>) -> Transform { Transform::matrix(1.0, angle.into().tan(), 0.0, 1.0, 0.0, 0.0) }
+
+    /// Transformation that applies `self` first and then `next`
+    pub fn then(&self, next: &Transform) -> Transform {
+        let (s, n) = (self, next);
+        Transform {
+            a: n.a * s.a + n.c * s.b,
+            b: n.b * s.a + n.d * s.b,
+            c: n.a * s.c + n.c * s.d,
+            d: n.b * s.c + n.d * s.d,
+            e: n.a * s.e + n.c * s.f + n.e,
+            f: n.b * s.e + n.d * s.f + n.f,
+        }
+    }
+
+    pub fn determinant(&self) -> f32 { self.a * self.d - self.b * self.c }
+
+    /// The inverse transformation, or `None` when this one is singular
+    pub fn inverse(&self) -> Option<Transform> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() { return None; }
+        Some(Transform {
+            a: self.d / det,
+            b: -self.b / det,
+            c: -self.c / det,
+            d: self.a / det,
+            e: (self.c * self.f - self.d * self.e) / det,
+            f: (self.b * self.e - self.a * self.f) / det,
+        })
+    }
+
+    /// Transforms a point
+    pub fn apply(&self, p: Point) -> Point {
+        Point::new(self.a * p.x + self.c * p.y + self.e, self.b * p.x + self.d * p.y + self.f)
+    }
+
+    /// Transforms a vector, i.e. ignores the translation
+    pub fn apply_vector(&self, v: Vector) -> Vector {
+        Vector::new(self.a * v.x + self.c * v.y, self.b * v.x + self.d * v.y)
+    }
+}
+
+impl Default for Transform {
+    fn default() -> Self { Transform::IDENTITY }
+}
+
+impl fmt::Display for Transform {
+    /// Formats this transformation as a value of the SVG ``transform`` attribute
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "matrix({} {} {} {} {} {})", self.a, self.b, self.c, self.d, self.e, self.f)
+    }
+}
+
+/// Error returned when a ``transform`` attribute can't be parsed.
+///
+/// Every variant carries the byte offset in the parsed string where the problem was found.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TransformParseError {
+    /// A name that is not one of the SVG transform functions
+    UnknownFunction { offset: usize, name: String },
+    /// A function whose arguments are not numbers, or not as many as it takes
+    InvalidArguments { offset: usize },
+    /// A function that lacks its parentheses
+    ExpectedParenthesis { offset: usize },
+}
+
+impl TransformParseError {
+    /// Byte offset in the parsed string where the error was found
+    pub fn offset(&self) -> usize {
+        match self {
+            TransformParseError::UnknownFunction { offset, .. } | TransformParseError::InvalidArguments { offset }
+            | TransformParseError::ExpectedParenthesis { offset } => *offset,
+        }
+    }
+}
+
+impl fmt::Display for TransformParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransformParseError::UnknownFunction { offset, name } => write!(f, "unknown transform function \"{}\" at byte {}", name, offset),
+            TransformParseError::InvalidArguments { offset } => write!(f, "invalid transform arguments at byte {}", offset),
+            TransformParseError::ExpectedParenthesis { offset } => write!(f, "expected a parenthesis at byte {}", offset),
+        }
+    }
+}
+
+impl std::error::Error for TransformParseError {}
+
+impl FromStr for Transform {
+    type Err = TransformParseError;
+
+    /// Parses the value of an SVG ``transform`` attribute, such as ``"translate(10 5) rotate(30)"``.
+    ///
+    /// Functions are applied from the last one, as in SVG; angles are in degrees.
+    ///
+    /// # Example
+    /// ```
+    /// use std::str::FromStr;
+    /// use visualife::geometry::Point;
+    /// use visualife::shapes::Transform;
+    /// let t = Transform::from_str("translate(10,0) scale(2)").unwrap();
+    /// assert_eq!(t.apply(Point::new(1.0, 1.0)), Point::new(12.0, 2.0));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut t = Transform::IDENTITY;
+        let mut rest = s;
+        loop {
+            rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+            if rest.is_empty() { return Ok(t); }
+            let offset = s.len() - rest.len();
+            let (name, after) = rest.split_at(rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len()));
+            let after = after.trim_start();
+            let open = s.len() - after.len();
+            let Some(after) = after.strip_prefix('(') else { return Err(TransformParseError::ExpectedParenthesis { offset: open }); };
+            let Some(close) = after.find(')') else { return Err(TransformParseError::ExpectedParenthesis { offset: s.len() }); };
+            let args: Result<Vec<f32>, _> = after[..close].split(|c: char| c.is_whitespace() || c == ',')
+                .filter(|a| !a.is_empty()).map(str::parse::<f32>).collect();
+            let invalid = TransformParseError::InvalidArguments { offset: open + 1 };
+            let args = args.map_err(|_| invalid.clone())?;
+            let f = match (name, args.as_slice()) {
+                ("matrix", &[a, b, c, d, e, f]) => Transform::matrix(a, b, c, d, e, f),
+                ("translate", &[tx]) => Transform::translate(tx, 0.0),
+                ("translate", &[tx, ty]) => Transform::translate(tx, ty),
+                ("scale", &[s]) => Transform::scale(s, s),
+                ("scale", &[sx, sy]) => Transform::scale(sx, sy),
+                ("rotate", &[a]) => Transform::rotate(Degrees(a)),
+                ("rotate", &[a, cx, cy]) => Transform::rotate_around(Degrees(a), Point::new(cx, cy)),
+                ("skewX", &[a]) => Transform::skew_x(Degrees(a)),
+                ("skewY", &[a]) => Transform::skew_y(Degrees(a)),
+                ("matrix" | "translate" | "scale" | "rotate" | "skewX" | "skewY", _) => return Err(invalid),
+                _ => return Err(TransformParseError::UnknownFunction { offset, name: name.to_string() }),
+            };
+            // ---------- the functions on the right act first
+            t = f.then(&t);
+            rest = &after[close + 1..];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::{Degrees, Point, Radians};
+    use crate::shapes::Transform;
+
+    #[test]
+    fn compose_and_invert() {
+        let t = Transform::rotate_around(Degrees(30.0), Point::new(5.0, 7.0)).then(&Transform::skew_x(Radians(0.35)))
+            .then(&Transform::scale(2.0, -1.0));
+        let back = t.inverse().unwrap().apply(t.apply(Point::new(3.0, -4.0)));
+        assert!(back.distance(Point::new(3.0, -4.0)) < 1e-4);
+        let center = Point::new(5.0, 5.0);
+        assert_eq!(Transform::rotate_around(Degrees(90.0), center).apply(center), center);
+        assert!(Transform::scale(0.0, 1.0).inverse().is_none());
+    }
+
+    #[test]
+    fn parse_transform_lists() {
+        use std::str::FromStr;
+        use crate::shapes::TransformParseError;
+        let t = Transform::from_str(" rotate(90 5 5),translate(1) skewX(0) matrix(1 0 0 1 0 0)").unwrap();
+        let expected = Transform::translate(1.0, 0.0).then(&Transform::rotate_around(Degrees(90.0), Point::new(5.0, 5.0)));
+        assert!(t.apply(Point::new(2.0, 3.0)).distance(expected.apply(Point::new(2.0, 3.0))) < 1e-5);
+        assert_eq!(Transform::from_str("").unwrap(), Transform::IDENTITY);
+        assert_eq!(Transform::from_str("scale(1 2 3)"), Err(TransformParseError::InvalidArguments { offset: 6 }));
+        assert_eq!(Transform::from_str("scale(1) turn(3)").unwrap_err().offset(), 9);
+        assert_eq!(Transform::from_str("scale 2"), Err(TransformParseError::ExpectedParenthesis { offset: 6 }));
+        assert_eq!(Transform::from_str("scale(2"), Err(TransformParseError::ExpectedParenthesis { offset: 7 }));
+    }
+}
@@ -0,0 +1,261 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use crate::geometry::{BBox, Point};
+use crate::ToSvg;
+
+/// Maximum number of entries in a node of the tree
+const NODE_CAPACITY: usize = 16;
+
+/// Spatial index of the elements of a drawing, for hit testing and region queries.
+///
+/// The index is an R-tree over the bounding boxes of the elements, bulk-loaded with the
+/// Sort-Tile-Recursive algorithm; a query visits only the branches whose boxes can hold an answer.
+/// Elements are matched by their bounding boxes, so a point in a corner of a circle's box hits the circle.
+///
+/// The index is a snapshot: it has to be built again when the drawing changes.
+///
+/// # Example
+/// ```
+/// use visualife::geometry::{BBox, Point};
+/// use visualife::shapes::{Circle, Rect};
+/// use visualife::{SpatialIndex, SvgDrawing};
+/// let mut drawing = SvgDrawing::new(100.0, 100.0);
+/// drawing.add_element(Box::new(Circle::new("c", 20.0, 20.0, 10.0)));
+/// drawing.add_element(Box::new(Rect::new("r", 15.0, 15.0, 50.0, 10.0)));
+/// let index = SpatialIndex::new(&drawing);
+/// assert_eq!(index.elements_at(Point::new(18.0, 18.0)), vec!["c", "r"]);
+/// assert_eq!(index.elements_in(&BBox::new(Point::new(40.0, 0.0), Point::new(90.0, 20.0))), vec!["r"]);
+/// assert_eq!(index.nearest(Point::new(90.0, 90.0), 1), vec!["r"]);
+/// ```
+#[derive(Debug, Clone)]
+pub struct SpatialIndex {
+    /// indexed elements, in the order of the leaves of the tree
+    entries: Vec<Entry>,
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    id: String,
+    bbox: BBox,
+    /// position of the element in the drawing, so results come in drawing order
+    order: usize,
+}
+
+#[derive(Debug, Clone)]
+struct Node {
+    bbox: BBox,
+    /// the range of entries held by a leaf node, or the range of child nodes
+    first: usize,
+    len: usize,
+    leaf: bool,
+}
+
+impl SpatialIndex {
+    /// Indexes the elements of a drawing or a group.
+    ///
+    /// Elements that contain other elements, such as groups, are not indexed themselves; their descendants are.
+    /// Elements that have no id or draw nothing are left out, and so are hidden layers with everything on them.
+    pub fn new(root: &dyn ToSvg) -> SpatialIndex {
+        let mut elements = vec![];
+        collect(&mut root.nested(), &mut elements);
+        SpatialIndex::from_boxes(elements)
+    }
+
+    /// Indexes arbitrary boxes, each identified by a string, e.g. labels that are yet to be placed
+    pub fn from_boxes(boxes: Vec<(String, BBox)>) -> SpatialIndex {
+        let mut entries: Vec<Entry> = boxes.into_iter().enumerate()
+            .map(|(order, (id, bbox))| Entry { id, bbox, order }).collect();
+        let mut nodes: Vec<Node> = vec![];
+
+        // ---------- leaves hold tiles of entries that are close to each other
+        sort_tile(&mut entries, |e| e.bbox.center());
+        for (k, chunk) in entries.chunks(NODE_CAPACITY).enumerate() {
+            let bbox = chunk.iter().skip(1).fold(chunk[0].bbox, |b, e| b.union(&e.bbox));
+            nodes.push(Node { bbox, first: k * NODE_CAPACITY, len: chunk.len(), leaf: true });
+        }
+        // ---------- every upper level tiles the nodes of the level below
+        let mut level = 0..nodes.len();
+        while level.len() > 1 {
+            sort_tile(&mut nodes[level.clone()], |n| n.bbox.center());
+            let start = nodes.len();
+            for first in level.clone().step_by(NODE_CAPACITY) {
+                let len = NODE_CAPACITY.min(level.end - first);
+                let bbox = nodes[first + 1..first + len].iter().fold(nodes[first].bbox, |b, n| b.union(&n.bbox));
+                nodes.push(Node { bbox, first, len, leaf: false });
+            }
+            level = start..nodes.len();
+        }
+
+        SpatialIndex { entries, nodes }
+    }
+
+    /// Number of indexed elements
+    pub fn len(&self) -> usize { self.entries.len() }
+
+    pub fn is_empty(&self) -> bool { self.entries.is_empty() }
+
+    /// Ids of the elements whose bounding boxes contain `p`, in drawing order; the topmost element comes last
+    pub fn elements_at(&self, p: Point) -> Vec<&str> {
+        self.query(|b| b.contains(p))
+    }
+
+    /// Ids of the elements whose bounding boxes overlap or touch `rect`, in drawing order
+    pub fn elements_in(&self, rect: &BBox) -> Vec<&str> {
+        self.query(|b| b.intersects(rect))
+    }
+
+    /// Ids of the `k` elements nearest to `p`, the nearest first.
+    ///
+    /// The distance to an element is measured to its bounding box, so it is zero for every element whose box
+    /// contains `p`; elements at equal distances are given in drawing order.
+    pub fn nearest(&self, p: Point, k: usize) -> Vec<&str> {
+        let mut out = vec![];
+        let Some(root) = self.nodes.last() else { return out; };
+        // ---------- best-first search: nodes and entries are visited by their distance from p
+        let mut heap = BinaryHeap::from([Candidate { distance: root.bbox.distance_to(p), order: 0, item: Item::Node(self.nodes.len() - 1) }]);
+        while let Some(Candidate { item, .. }) = heap.pop() {
+            if out.len() == k { break; }
+            match item {
+                Item::Entry(i) => out.push(self.entries[i].id.as_str()),
+                Item::Node(n) => {
+                    let node = &self.nodes[n];
+                    for i in node.first..node.first + node.len {
+                        heap.push(if node.leaf {
+                            let e = &self.entries[i];
+                            Candidate { distance: e.bbox.distance_to(p), order: e.order, item: Item::Entry(i) }
+                        } else {
+                            Candidate { distance: self.nodes[i].bbox.distance_to(p), order: 0, item: Item::Node(i) }
+                        });
+                    }
+                }
+            }
+        }
+        out
+    }
+
+    /// Ids of the entries whose boxes pass the `test`; a node is visited only when its box passes it as well
+    fn query(&self, test: impl Fn(&BBox) -> bool) -> Vec<&str> {
+        let mut found: Vec<&Entry> = vec![];
+        let mut stack: Vec<usize> = self.nodes.len().checked_sub(1).into_iter().collect();
+        while let Some(n) = stack.pop() {
+            let node = &self.nodes[n];
+            if !test(&node.bbox) { continue; }
+            let range = node.first..node.first + node.len;
+            if node.leaf {
+                found.extend(self.entries[range].iter().filter(|e| test(&e.bbox)));
+            } else {
+                stack.extend(range);
+            }
+        }
+        found.sort_by_key(|e| e.order);
+        found.into_iter().map(|e| e.id.as_str()).collect()
+    }
+}
+
+/// Collects the ids and boxes of the leaf elements, depth first
+fn collect(elements: &mut dyn Iterator<Item = &dyn ToSvg>, out: &mut Vec<(String, BBox)>) {
+    for el in elements.filter(|el| el.is_visible()) {
+        let mut nested = el.nested().peekable();
+        if nested.peek().is_some() {
+            collect(&mut nested, out);
+        } else if let (Some(id), Some(bbox)) = (el.id(), el.bbox()) {
+            out.push((id.to_string(), bbox));
+        }
+    }
+}
+
+/// Orders items so that every run of [`NODE_CAPACITY`] items covers a compact tile of the plane:
+/// the items are cut into vertical slices by x, then each slice is sorted by y
+fn sort_tile<T>(items: &mut [T], center: impl Fn(&T) -> Point) {
+    let pages = items.len().div_ceil(NODE_CAPACITY);
+    let slice_len = (pages as f64).sqrt().ceil() as usize * NODE_CAPACITY;
+    items.sort_by(|a, b| center(a).x.total_cmp(&center(b).x));
+    for slice in items.chunks_mut(slice_len.max(1)) {
+        slice.sort_by(|a, b| center(a).y.total_cmp(&center(b).y));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Item { Node(usize), Entry(usize) }
+
+/// Element of the priority queue of [`SpatialIndex::nearest()`]
+#[derive(Debug, Clone, Copy)]
+struct Candidate {
+    distance: f32,
+    /// drawing order of an entry, to break ties
+    order: usize,
+    item: Item,
+}
+
+impl Ord for Candidate {
+    /// Reversed, so the max-heap pops the nearest candidate first; at equal distances
+    /// nodes go before entries, so no entry is given before a node that may hold an earlier one
+    fn cmp(&self, other: &Self) -> Ordering {
+        let is_entry = |c: &Candidate| matches!(c.item, Item::Entry(_));
+        other.distance.total_cmp(&self.distance)
+            .then_with(|| is_entry(other).cmp(&is_entry(self)))
+            .then_with(|| other.order.cmp(&self.order))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool { self.cmp(other) == Ordering::Equal }
+}
+
+impl Eq for Candidate {}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::{BBox, Point};
+    use crate::shapes::{Circle, Group};
+    use crate::{SpatialIndex, SvgDrawing};
+
+    #[test]
+    fn queries_match_brute_force() {
+        // ---------- a few thousand boxes of various sizes, spread by the R2 low-discrepancy sequence
+        let boxes: Vec<(String, BBox)> = (1..=3000).map(|i| {
+            let p = Point::new((i as f32 * 0.754_877_7).fract() * 1000.0, (i as f32 * 0.569_840_3).fract() * 1000.0);
+            let size = 1.0 + (i % 7) as f32 * 3.0;
+            (format!("b{}", i), BBox::new(p, Point::new(p.x + size, p.y + size / 2.0)))
+        }).collect();
+        let index = SpatialIndex::from_boxes(boxes.clone());
+        assert_eq!(index.len(), 3000);
+
+        for probe in [Point::new(500.0, 500.0), Point::new(3.0, 997.0), Point::new(-50.0, 20.0), Point::new(333.3, 666.6)] {
+            let rect = BBox::new(probe, Point::new(probe.x + 40.0, probe.y + 25.0));
+            let expected: Vec<&str> = boxes.iter().filter(|(_, b)| b.intersects(&rect)).map(|(id, _)| id.as_str()).collect();
+            assert_eq!(index.elements_in(&rect), expected);
+            let expected: Vec<&str> = boxes.iter().filter(|(_, b)| b.contains(probe)).map(|(id, _)| id.as_str()).collect();
+            assert_eq!(index.elements_at(probe), expected);
+
+            let mut by_distance: Vec<(f32, usize)> = boxes.iter().enumerate().map(|(i, (_, b))| (b.distance_to(probe), i)).collect();
+            by_distance.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+            let expected: Vec<&str> = by_distance[..10].iter().map(|&(_, i)| boxes[i].0.as_str()).collect();
+            assert_eq!(index.nearest(probe, 10), expected);
+        }
+        assert_eq!(index.nearest(Point::new(0.0, 0.0), 5000).len(), 3000);
+    }
+
+    #[test]
+    fn nested_elements() {
+        let mut drawing = SvgDrawing::new(100.0, 100.0);
+        let mut outer = Group::new("outer");
+        let mut inner = Group::new("inner");
+        inner.add_element(Box::new(Circle::new("deep", 80.0, 80.0, 5.0)));
+        outer.add_element(Box::new(inner));
+        outer.add_element(Box::new(Circle::new("shallow", 10.0, 10.0, 5.0)));
+        drawing.add_element(Box::new(outer));
+        drawing.add_element(Box::new(Group::new("empty")));
+
+        let index = SpatialIndex::new(&drawing);
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.elements_at(Point::new(82.0, 78.0)), vec!["deep"]);
+        assert_eq!(index.nearest(Point::new(50.0, 50.0), 2), vec!["deep", "shallow"]);
+        assert!(SpatialIndex::new(&Group::new("g")).nearest(Point::new(0.0, 0.0), 3).is_empty());
+    }
+}
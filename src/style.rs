@@ -1,16 +1,125 @@
+use std::fmt;
+use std::str::FromStr;
+use crate::colors::{Color, ColorParseError};
+use crate::ToSvg;
 
-pub fn rgb_to_hex(r: u16, g: u16, b: u16) -> String {
-    format!("#{:02X}{:02X}{:02X}", r, g, b)
+/// Value of the ``fill`` or ``stroke`` property
+#[derive(Debug, Clone, PartialEq)]
+pub enum Paint {
+    /// ``none``: nothing is painted
+    None,
+    Color(Color),
+    /// Any other paint, kept verbatim, such as ``url(#gradient)`` or ``currentColor``
+    Other(String),
 }
 
-#[derive(Debug, Clone)]
+impl Paint {
+    /// Returns the colour of this paint, if it's a plain colour
+    pub fn color(&self) -> Option<Color> {
+        match self {
+            Paint::Color(c) => Some(*c),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Paint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Paint::None => write!(f, "none"),
+            Paint::Color(c) => write!(f, "{}", c),
+            Paint::Other(s) => write!(f, "{}", s),
+        }
+    }
+}
+
+impl FromStr for Paint {
+    type Err = ColorParseError;
+
+    /// Parses ``none``, a colour, a ``url()`` reference or one of the ``currentColor``, ``inherit``,
+    /// ``context-fill`` and ``context-stroke`` keywords
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let lowercase = s.to_ascii_lowercase();
+        match lowercase.as_str() {
+            "none" => Ok(Paint::None),
+            "currentcolor" | "inherit" | "context-fill" | "context-stroke" => Ok(Paint::Other(s.to_string())),
+            _ if lowercase.starts_with("url(") => Ok(Paint::Other(s.to_string())),
+            _ => Ok(Paint::Color(Color::from_str(s)?)),
+        }
+    }
+}
+
+impl From<Color> for Paint {
+    fn from(c: Color) -> Self { Paint::Color(c) }
+}
+
+impl TryFrom<&str> for Paint {
+    type Error = ColorParseError;
+
+    /// Parses a paint, as [`Paint::from_str()`] does
+    fn try_from(s: &str) -> Result<Self, Self::Error> { Paint::from_str(s) }
+}
+
+impl TryFrom<String> for Paint {
+    type Error = ColorParseError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> { Paint::from_str(&s) }
+}
+
+/// Error returned when a CSS declaration list can't be turned into a [`Style`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum StyleParseError {
+    /// A declaration that has no ``:`` separating the property from its value
+    MissingColon(String),
+    /// A declaration such as ``fill:`` that has no value
+    EmptyValue(String),
+    /// A property that is not supported by [`Style`]
+    UnknownProperty(String),
+    /// A numeric property whose value is not a number
+    InvalidNumber { property: String, value: String },
+    /// A ``fill`` or ``stroke`` whose value is not a valid paint
+    InvalidColor { property: String, error: ColorParseError },
+}
+
+impl fmt::Display for StyleParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StyleParseError::MissingColon(decl) => write!(f, "missing ':' in declaration \"{}\"", decl),
+            StyleParseError::EmptyValue(prop) => write!(f, "empty value for property \"{}\"", prop),
+            StyleParseError::UnknownProperty(prop) => write!(f, "unknown style property \"{}\"", prop),
+            StyleParseError::InvalidNumber { property, value } =>
+                write!(f, "invalid numeric value \"{}\" for property \"{}\"", value, property),
+            StyleParseError::InvalidColor { property, error } =>
+                write!(f, "invalid colour for property \"{}\": {}", property, error),
+        }
+    }
+}
+
+impl std::error::Error for StyleParseError {}
+
+/// Presentation properties of an SVG element, written as its ``style`` attribute.
+///
+/// A style can be parsed from CSS declarations:
+/// ```
+/// use std::str::FromStr;
+/// use visualife::colors::Color;
+/// use visualife::style::{Paint, Style};
+/// let style = Style::from_str("fill:#f00; stroke-width:2").unwrap();
+/// assert_eq!(style.fill, Some(Paint::Color(Color::rgb(255, 0, 0))));
+/// assert_eq!(style.stroke_width, Some(2.0));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
 pub struct Style {
-    pub fill: Option<String>,
-    pub stroke: Option<String>,
+    pub fill: Option<Paint>,
+    pub stroke: Option<Paint>,
     pub stroke_width: Option<f32>,
     pub opacity: Option<f32>,
     pub fill_opacity: Option<f32>,
     pub stroke_opacity: Option<f32>,
+    /// declarations of the properties that have no field of their own, such as ``stroke-linecap``,
+    /// written back as they are; see [`Style::parse_keeping_unknown()`]
+    pub other: Vec<(String, String)>,
 }
 
 impl Style {
@@ -22,15 +131,52 @@ impl Style {
             opacity: None,
             fill_opacity: None,
             stroke_opacity: None,
+            other: vec![],
         }
     }
 
-    pub fn set_fill(&mut self, fill: &str) {
-        self.fill = Some(fill.to_string());
+    /// Parses a list of CSS declarations as [`Style::from_str()`] does, except that properties
+    /// without a field of their own are kept in [`other`](Style::other) rather than rejected.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::style::Style;
+    /// let style = Style::parse_keeping_unknown("stroke-linecap: round; fill:red").unwrap();
+    /// assert_eq!(style.other, vec![("stroke-linecap".to_string(), "round".to_string())]);
+    /// assert_eq!(style.to_string(), "fill:#F00;stroke-linecap:round;");
+    /// ```
+    pub fn parse_keeping_unknown(s: &str) -> Result<Style, StyleParseError> { parse(s, true) }
+
+    /// Sets the fill paint, given either as a [`Paint`] or as a [`Color`]
+    pub fn set_fill<P: Into<Paint>>(&mut self, fill: P) {
+        self.fill = Some(fill.into());
     }
 
-    pub fn set_stroke(&mut self, stroke: &str) {
-        self.stroke = Some(stroke.to_string());
+    /// Sets the stroke paint, given either as a [`Paint`] or as a [`Color`]
+    pub fn set_stroke<P: Into<Paint>>(&mut self, stroke: P) {
+        self.stroke = Some(stroke.into());
+    }
+
+    /// Sets the fill paint parsed from a string, as the ``fill`` property of [`Style::from_str()`].
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::colors::Color;
+    /// use visualife::style::{Paint, Style};
+    /// let mut style = Style::new();
+    /// style.try_set_fill("red").unwrap();
+    /// assert_eq!(style.fill, Some(Paint::Color(Color::rgb(255, 0, 0))));
+    /// assert!(style.try_set_fill("rde").is_err());
+    /// ```
+    pub fn try_set_fill(&mut self, fill: &str) -> Result<(), StyleParseError> {
+        self.fill = Some(parse_paint("fill", fill)?);
+        Ok(())
+    }
+
+    /// Sets the stroke paint parsed from a string, as the ``stroke`` property of [`Style::from_str()`]
+    pub fn try_set_stroke(&mut self, stroke: &str) -> Result<(), StyleParseError> {
+        self.stroke = Some(parse_paint("stroke", stroke)?);
+        Ok(())
     }
 
     pub fn set_stroke_width(&mut self, stroke_width: f32) {
@@ -56,35 +202,218 @@ impl Style {
             && self.opacity.is_none()
             && self.fill_opacity.is_none()
             && self.stroke_opacity.is_none()
+            && self.other.is_empty()
     }
 
-    pub fn to_string(&self) -> String {
-        let mut style_string = String::new();
+    /// Replaces every colour of this style, i.e. its fill and stroke colours, with `f(colour)`
+    pub fn map_colors<F: Fn(Color) -> Color>(&mut self, f: F) {
+        for paint in [&mut self.fill, &mut self.stroke].into_iter().flatten() {
+            if let Paint::Color(c) = paint { *c = f(*c); }
+        }
+    }
+
+    /// Overlays `other` on top of this style.
+    ///
+    /// Every property set in `other` replaces the respective property of `self`; properties
+    /// that are not set in `other` are taken from `self`.
+    ///
+    /// # Example
+    /// ```
+    /// use std::str::FromStr;
+    /// use visualife::style::Style;
+    /// let base = Style::from_str("fill:red;stroke:black").unwrap();
+    /// let merged = base.merge(&Style::from_str("fill:blue").unwrap());
+    /// assert_eq!(merged.to_string(), "fill:#00F;stroke:#000;");
+    /// ```
+    pub fn merge(&self, other: &Style) -> Style {
+        Style {
+            fill: other.fill.clone().or_else(|| self.fill.clone()),
+            stroke: other.stroke.clone().or_else(|| self.stroke.clone()),
+            stroke_width: other.stroke_width.or(self.stroke_width),
+            opacity: other.opacity.or(self.opacity),
+            fill_opacity: other.fill_opacity.or(self.fill_opacity),
+            stroke_opacity: other.stroke_opacity.or(self.stroke_opacity),
+            other: self.other.iter().filter(|(name, _)| other.other.iter().all(|(n, _)| n != name))
+                .chain(other.other.iter()).cloned().collect(),
+        }
+    }
 
+    /// Computes the style a child element inherits from this style.
+    ///
+    /// Unlike the other properties, ``opacity`` is not inherited in SVG: a group's opacity
+    /// applies to the group as a whole. The effective opacity of a leaf is therefore the product
+    /// of the opacities of all its ancestors and its own.
+    pub(crate) fn cascade(&self, child: &Style) -> Style {
+        let mut out = self.merge(child);
+        out.opacity = match (self.opacity, child.opacity) {
+            (Some(a), Some(b)) => Some(a * b),
+            (a, b) => a.or(b),
+        };
+        out
+    }
+}
+
+impl fmt::Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         if let Some(ref fill) = self.fill {
-            style_string.push_str(&format!("fill:{};", fill));
+            write!(f, "fill:{};", fill)?;
         }
 
         if let Some(ref stroke) = self.stroke {
-            style_string.push_str(&format!("stroke:{};", stroke));
+            write!(f, "stroke:{};", stroke)?;
         }
 
         if let Some(stroke_width) = self.stroke_width {
-            style_string.push_str(&format!("stroke-width:{};", stroke_width));
+            write!(f, "stroke-width:{};", stroke_width)?;
         }
 
         if let Some(opacity) = self.opacity {
-            style_string.push_str(&format!("opacity:{};", opacity));
+            write!(f, "opacity:{};", opacity)?;
         }
 
         if let Some(fill_opacity) = self.fill_opacity {
-            style_string.push_str(&format!("fill-opacity:{};", fill_opacity));
+            write!(f, "fill-opacity:{};", fill_opacity)?;
         }
 
         if let Some(stroke_opacity) = self.stroke_opacity {
-            style_string.push_str(&format!("stroke-opacity:{};", stroke_opacity));
+            write!(f, "stroke-opacity:{};", stroke_opacity)?;
+        }
+
+        for (property, value) in &self.other {
+            write!(f, "{}:{};", property, value)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Style {
+    type Err = StyleParseError;
+
+    /// Parses a list of CSS declarations, such as ``"fill:#f00;stroke-width:2"``.
+    ///
+    /// Declarations are separated by ``;``; whitespace and empty declarations are ignored.
+    /// Lengths may carry a ``px`` unit and opacities may be given as percentages.
+    fn from_str(s: &str) -> Result<Self, Self::Err> { parse(s, false) }
+}
+
+/// Parses CSS declarations; properties unknown to [`Style`] are kept in its `other` declarations or rejected
+fn parse(s: &str, keep_unknown: bool) -> Result<Style, StyleParseError> {
+    let mut style = Style::new();
+    for declaration in s.split(';').map(str::trim).filter(|d| !d.is_empty()) {
+        let (property, value) = declaration.split_once(':')
+            .ok_or_else(|| StyleParseError::MissingColon(declaration.to_string()))?;
+        let name = property.trim();
+        let property = name.to_ascii_lowercase();
+        let value = value.trim();
+        if value.is_empty() {
+            return Err(StyleParseError::EmptyValue(property));
+        }
+        match property.as_str() {
+            "fill" => style.fill = Some(parse_paint(&property, value)?),
+            "stroke" => style.stroke = Some(parse_paint(&property, value)?),
+            "stroke-width" => style.stroke_width = Some(parse_length(&property, value)?),
+            "opacity" => style.opacity = Some(parse_opacity(&property, value)?),
+            "fill-opacity" => style.fill_opacity = Some(parse_opacity(&property, value)?),
+            "stroke-opacity" => style.stroke_opacity = Some(parse_opacity(&property, value)?),
+            _ if keep_unknown => {
+                style.other.retain(|(other, _)| other != name);
+                style.other.push((name.to_string(), value.to_string()));
+            }
+            _ => return Err(StyleParseError::UnknownProperty(property)),
+        }
+    }
+
+    Ok(style)
+}
+
+fn parse_paint(property: &str, value: &str) -> Result<Paint, StyleParseError> {
+    Paint::from_str(value).map_err(|error| StyleParseError::InvalidColor { property: property.to_string(), error })
+}
+
+fn parse_number(property: &str, value: &str) -> Result<f32, StyleParseError> {
+    value.trim().parse::<f32>().map_err(|_| StyleParseError::InvalidNumber {
+        property: property.to_string(), value: value.to_string() })
+}
+
+fn parse_length(property: &str, value: &str) -> Result<f32, StyleParseError> {
+    parse_number(property, value.strip_suffix("px").unwrap_or(value))
+}
+
+fn parse_opacity(property: &str, value: &str) -> Result<f32, StyleParseError> {
+    match value.strip_suffix('%') {
+        Some(percent) => Ok(parse_number(property, percent)? / 100.0),
+        None => parse_number(property, value),
+    }
+}
+
+/// Finds the effective style of the element identified by `id`.
+///
+/// The element is searched for among `elements` and, recursively, their children. The
+/// styles of all the groups on the way down to it are cascaded with `inherited` and the
+/// element's own style. Returns `None` if no element has the given id.
+pub(crate) fn computed_style(elements: &[Box<dyn ToSvg>], inherited: &Style, id: &str) -> Option<Style> {
+    for el in elements {
+        let style = match el.style() {
+            Some(own) => inherited.cascade(own),
+            None => inherited.clone(),
+        };
+        if el.id() == Some(id) {
+            return Some(style);
+        }
+        if let Some(found) = computed_style(el.children(), &style, id) {
+            return Some(found);
         }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use crate::colors::{Color, ColorParseError};
+    use crate::shapes::{Circle, Group};
+    use crate::style::{Paint, Style, StyleParseError};
+
+    #[test]
+    fn parse_style() {
+        let style = Style::from_str(" fill: #f00 ; stroke-width:2px;opacity:50%; ").unwrap();
+        assert_eq!(style.fill, Some(Paint::Color(Color::rgb(255, 0, 0))));
+        assert_eq!(style.stroke_width, Some(2.0));
+        assert_eq!(style.opacity, Some(0.5));
+        assert_eq!(style.to_string(), "fill:#F00;stroke-width:2;opacity:0.5;");
+        let style = Style::from_str("fill:url(#grad);stroke:none").unwrap();
+        assert_eq!(style.fill, Some(Paint::Other("url(#grad)".to_string())));
+        assert_eq!(style.stroke, Some(Paint::None));
+        assert!(Style::from_str("").unwrap().is_empty());
+
+        assert_eq!(Paint::try_from("currentColor"), Ok(Paint::Other("currentColor".to_string())));
+        assert_eq!(Paint::try_from("reddish"), Err(ColorParseError::UnknownName("reddish".to_string())));
+
+        assert_eq!(Style::from_str("fill #f00"), Err(StyleParseError::MissingColon("fill #f00".to_string())));
+        assert_eq!(Style::from_str("fill:"), Err(StyleParseError::EmptyValue("fill".to_string())));
+        assert_eq!(Style::from_str("font-size:2"), Err(StyleParseError::UnknownProperty("font-size".to_string())));
+        assert_eq!(Style::from_str("stroke-width:thick"),
+                   Err(StyleParseError::InvalidNumber { property: "stroke-width".to_string(), value: "thick".to_string() }));
+        assert_eq!(Style::from_str("fill:#12"), Err(StyleParseError::InvalidColor {
+            property: "fill".to_string(), error: ColorParseError::InvalidHex("#12".to_string()) }));
+    }
+
+    #[test]
+    fn computed_style_of_nested_leaf() {
+        let mut outer = Group::new("outer");
+        outer.style = Style::from_str("fill:red;stroke:black;opacity:0.5").unwrap();
+        let mut inner = Group::new("inner");
+        inner.style = Style::from_str("fill:blue;opacity:0.5").unwrap();
+        let mut leaf = Circle::new("leaf", 0.0, 0.0, 1.0);
+        leaf.style = Style::from_str("stroke-width:3").unwrap();
+        inner.add_element(Box::new(leaf));
+        outer.add_element(Box::new(inner));
 
-        style_string
+        let computed = outer.computed_style("leaf").unwrap();
+        assert_eq!(computed.to_string(), "fill:#00F;stroke:#000;stroke-width:3;opacity:0.25;");
+        assert_eq!(outer.computed_style("outer").unwrap(), outer.style);
+        assert!(outer.computed_style("missing").is_none());
     }
 }
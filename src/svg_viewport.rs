@@ -1,29 +1,450 @@
+use std::str::FromStr;
+use crate::colors::{Color, ColorVisionDeficiency};
+use crate::layer::{Layer, ANNOTATIONS_LAYER, BACKGROUND_LAYER, DATA_LAYER, OVERLAY_LAYER};
+use crate::style::{computed_style, Style};
+use crate::theme::{Theme, BACKGROUND_CLASS};
+use crate::select::{self, Descendants, ElementInfo, Selector, SelectorParseError};
+use crate::import::{import_svg, SvgImportError};
+use crate::draw_svg::{escape, push_attributes, push_class};
 use crate::ToSvg;
 
+/// An SVG document.
+///
+/// Elements of a drawing are placed on [`Layer`]s, which are drawn one over another by their order.
+/// A new drawing has four layers, from the bottom: [`BACKGROUND_LAYER`](crate::BACKGROUND_LAYER),
+/// [`DATA_LAYER`](crate::DATA_LAYER), [`ANNOTATIONS_LAYER`](crate::ANNOTATIONS_LAYER) and
+/// [`OVERLAY_LAYER`](crate::OVERLAY_LAYER); layers that hold no elements are left out of the document.
+///
+/// # Example
+/// ```
+/// use visualife::shapes::{Circle, Rect};
+/// use visualife::{SvgDrawing, ToSvg, ANNOTATIONS_LAYER, BACKGROUND_LAYER};
+/// let mut drawing = SvgDrawing::new(100.0, 100.0);
+/// drawing.add_to_layer(ANNOTATIONS_LAYER, Box::new(Circle::new("label", 50.0, 50.0, 5.0)));
+/// drawing.add_element(Box::new(Circle::new("point", 50.0, 50.0, 20.0)));
+/// drawing.add_to_layer(BACKGROUND_LAYER, Box::new(Rect::new("frame", 0.0, 0.0, 100.0, 100.0)));
+/// let svg = drawing.to_svg();
+/// let at = |id: &str| svg.find(&format!("id=\"{}\"", id)).unwrap();
+/// assert!(at("frame") < at("point") && at("point") < at("label"));
+/// ```
 pub struct SvgDrawing {
     width: f32,
     height: f32,
-    elements: Vec<Box<dyn ToSvg>>
+    /// [`Layer`]s of this drawing, sorted by their order
+    layers: Vec<Layer>,
+    theme: Option<Theme>,
+    dark_theme: Option<Theme>,
+    /// namespace declarations of an imported document, written back into its header
+    namespaces: Vec<(String, String)>,
+    /// style of the ``<svg>`` element, inherited by all the layers
+    style: Style,
+    class: Option<String>,
+    /// other attributes of the ``<svg>`` element of an imported document, written back into its header
+    attributes: Vec<(String, String)>,
 }
 
 impl SvgDrawing {
     pub fn new(width: f32, height: f32) -> Self {
-        SvgDrawing { width, height, elements: vec![] }
+        let layers = [BACKGROUND_LAYER, DATA_LAYER, ANNOTATIONS_LAYER, OVERLAY_LAYER].iter().enumerate()
+            .map(|(i, name)| Layer::new(name, 10 * i as i32)).collect();
+        SvgDrawing { width, height, layers, theme: None, dark_theme: None, namespaces: vec![], style: Style::new(), class: None,
+                     attributes: vec![] }
     }
 
+    /// Reads an SVG document.
+    ///
+    /// Circles, rectangles, paths and groups become [`Circle`](crate::shapes::Circle), [`Rect`](crate::shapes::Rect),
+    /// [`Path`](crate::shapes::Path) and [`Group`](crate::shapes::Group) elements, and Inkscape layers become
+    /// [`Layer`]s; elements outside of layers are put on the [`DATA_LAYER`](crate::DATA_LAYER). In a document
+    /// that has layers, every run of elements between them gets a layer of its own, named ``data``, ``data 2`` and so on,
+    /// so they are drawn in their place in the stack.
+    /// Transformations of the document's ``viewBox`` and of its groups are applied to the coordinates of the shapes.
+    /// Stroke widths are scaled along, so the shapes look the same, and ``<use>`` elements are moved back
+    /// so that they draw the elements they refer to where the document did.
+    /// The style and the class of the ``<svg>`` element are kept on the drawing, and cascade to all its layers.
+    /// Attributes and style properties visualife has no field for are kept on the shapes as they are.
+    /// Everything else is kept as [`RawElement`](crate::shapes::RawElement)s and written back as it was.
+    /// Elements without an id are given a new one.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::shapes::Circle;
+    /// use visualife::{SvgDrawing, ToSvg};
+    /// let svg = r#"<svg width="200" height="200" viewBox="0 0 100 100" xmlns="http://www.w3.org/2000/svg">
+    ///   <circle id="dot" cx="50" cy="50" r="10" fill="red"/>
+    ///   <text x="10" y="20">label</text>
+    /// </svg>"#;
+    /// let mut drawing = SvgDrawing::from_svg(svg).unwrap();
+    /// let dot = drawing.get_by_id_mut("dot").and_then(|el| el.downcast_mut::<Circle>()).unwrap();
+    /// assert_eq!((dot.cx, dot.radius), (100.0, 20.0));
+    /// dot.style.try_set_fill("blue").unwrap();
+    /// let svg = drawing.to_svg();
+    /// assert!(svg.contains(r#"<circle id="dot" cx="100" cy="100" r="20" style="fill:#00F;stroke-width:2;" />"#));
+    /// assert!(svg.contains(r#"<text x="10" y="20" transform="matrix(2 0 0 2 0 0)">label</text>"#));
+    /// ```
+    pub fn from_svg(svg: &str) -> Result<SvgDrawing, SvgImportError> { import_svg(svg) }
+
+    /// Declares a namespace prefix in the header of this document; the SVG and Inkscape namespaces are always declared
+    pub(crate) fn add_namespace(&mut self, prefix: &str, uri: &str) {
+        if prefix != "inkscape" && self.namespaces.iter().all(|(p, _)| p != prefix) {
+            self.namespaces.push((prefix.to_string(), uri.to_string()));
+        }
+    }
+
+    /// Keeps an attribute of the ``<svg>`` element of an imported document, written back into its header
+    pub(crate) fn add_attribute(&mut self, name: &str, value: &str) {
+        self.attributes.push((name.to_string(), value.to_string()));
+    }
+
+    /// Sets the CSS class of the ``<svg>`` element of this drawing
+    pub fn set_class(&mut self, class: &str) { self.class = Some(class.to_string()); }
+
     pub fn draw(&mut self) {
-        println!("{}", self.svg_header());
-        for element in &self.elements {
-            println!("{}", element.to_svg());
+        println!("{}", self.to_svg());
+    }
+
+    /// Adds an element on top of the [`DATA_LAYER`](crate::DATA_LAYER)
+    pub fn add_element(&mut self, el: Box<dyn ToSvg>) { self.add_to_layer(DATA_LAYER, el); }
+
+    /// Adds an element on top of the given layer; a layer that doesn't exist yet is created over all the others
+    pub fn add_to_layer(&mut self, layer: &str, el: Box<dyn ToSvg>) {
+        if self.layer(layer).is_none() {
+            let order = self.layers().last().map_or(0, |top| top.order() + 10);
+            self.add_layer(Layer::new(layer, order));
+        }
+        self.layer_mut(layer).unwrap().add_element(el);
+    }
+
+    /// Adds a layer to this drawing, in its place in the stack; returns the layer of the same name it replaces, if any.
+    ///
+    /// A layer whose id is taken by another layer gets a numeric suffix, such as ``layer-a-b-2``.
+    pub fn add_layer(&mut self, mut layer: Layer) -> Option<Layer> {
+        let old = self.remove_layer(&layer.name);
+        if self.layers().any(|l| l.id == layer.id) {
+            let id = (2..).map(|n| format!("{}-{}", layer.id, n)).find(|id| self.layers().all(|l| l.id != *id)).unwrap();
+            layer.id = id;
+        }
+        let at = self.layers().take_while(|l| l.order() <= layer.order()).count();
+        self.layers.insert(at, layer);
+        old
+    }
+
+    /// Removes a layer, together with all its elements
+    pub fn remove_layer(&mut self, name: &str) -> Option<Layer> {
+        let at = self.layers().position(|l| l.name == name)?;
+        Some(self.layers.remove(at))
+    }
+
+    /// Layers of this drawing, from the bottom one
+    pub fn layers(&self) -> impl Iterator<Item = &Layer> { self.layers.iter() }
+
+    pub fn layer(&self, name: &str) -> Option<&Layer> { self.layers().find(|l| l.name == name) }
+
+    pub fn layer_mut(&mut self, name: &str) -> Option<&mut Layer> { self.layers_mut().find(|l| l.name == name) }
+
+    fn layers_mut(&mut self) -> impl Iterator<Item = &mut Layer> { self.layers.iter_mut() }
+
+    /// Moves a layer in the stack; among layers of equal orders, the one moved last is drawn on top
+    pub fn set_layer_order(&mut self, name: &str, order: i32) {
+        if let Some(mut layer) = self.remove_layer(name) {
+            layer.set_order(order);
+            self.add_layer(layer);
+        }
+    }
+
+    /// Effective style of the element identified by `id`, cascaded through all its parent groups
+    pub fn computed_style(&self, id: &str) -> Option<Style> {
+        self.layers().find_map(|layer| {
+            let style = self.style.cascade(&layer.style);
+            if layer.id == id { Some(style) } else { computed_style(&layer.elements, &style, id) }
+        })
+    }
+
+    /// The first element with the given id, searched depth first.
+    ///
+    /// Use `downcast_ref()` of `dyn ToSvg` to get at its concrete type.
+    pub fn get_by_id(&self, id: &str) -> Option<&dyn ToSvg> { self.descendants().find(|el| el.id() == Some(id)) }
+
+    /// Mutable access to the first element with the given id, searched depth first.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::shapes::{Circle, Group};
+    /// use visualife::SvgDrawing;
+    /// let mut drawing = SvgDrawing::new(100.0, 100.0);
+    /// let mut group = Group::new("g");
+    /// group.add_element(Box::new(Circle::new("c", 50.0, 50.0, 10.0)));
+    /// drawing.add_element(Box::new(group));
+    /// let circle = drawing.get_by_id_mut("c").and_then(|el| el.downcast_mut::<Circle>()).unwrap();
+    /// circle.radius = 20.0;
+    /// assert_eq!(drawing.get_by_id("c").unwrap().bbox().unwrap().width(), 40.0);
+    /// ```
+    pub fn get_by_id_mut(&mut self, id: &str) -> Option<&mut dyn ToSvg> {
+        self.layers_mut().find_map(|layer| {
+            if layer.id == id { return Some(layer as &mut dyn ToSvg); }
+            select::find_slot(&mut layer.elements, id).map(|el| el.as_mut())
+        })
+    }
+
+    /// Removes the first element with the given id, searched depth first, from the layer or the element that holds it.
+    ///
+    /// Layers themselves are removed with [`remove_layer()`](SvgDrawing::remove_layer).
+    pub fn remove_by_id(&mut self, id: &str) -> Option<Box<dyn ToSvg>> {
+        self.layers_mut().find_map(|layer| select::remove(&mut layer.elements, id))
+    }
+
+    /// Puts `element` in place of the first element with the given id and returns the replaced one.
+    ///
+    /// When no element has that id, nothing changes and `element` is dropped; layers can't be replaced.
+    pub fn replace(&mut self, id: &str, element: Box<dyn ToSvg>) -> Option<Box<dyn ToSvg>> {
+        self.layers_mut().find_map(|layer| select::find_slot(&mut layer.elements, id)).map(|slot| std::mem::replace(slot, element))
+    }
+
+    /// All the elements nested in this drawing, depth first, every element before its children
+    pub fn descendants(&self) -> Descendants<'_> { Descendants::new(self) }
+
+    /// Elements nested in this drawing that match a CSS-like [`Selector`], in document order
+    pub fn select(&self, selector: &str) -> Result<Vec<&dyn ToSvg>, SelectorParseError> {
+        let selector = Selector::from_str(selector)?;
+        let mut found = vec![];
+        select::select(self, &selector, &mut vec![ElementInfo::of(self)], &mut found);
+        Ok(found)
+    }
+
+    /// Calls `f` on every element nested in this drawing that matches a CSS-like [`Selector`]; returns how many matched
+    pub fn select_mut(&mut self, selector: &str, mut f: impl FnMut(&mut dyn ToSvg)) -> Result<usize, SelectorParseError> {
+        let selector = Selector::from_str(selector)?;
+        let mut ancestors = vec![ElementInfo::of(self)];
+        Ok(self.layers.iter_mut().map(|layer| select::select_mut(layer, &selector, &mut ancestors, &mut f)).sum())
+    }
+
+    /// Sets the theme of this drawing.
+    ///
+    /// The theme is written into the document as a stylesheet, preceded by a rectangle
+    /// that fills the background.
+    pub fn set_theme(&mut self, theme: Theme) { self.theme = Some(theme); }
+
+    /// Sets the theme used when the viewer prefers a dark colour scheme.
+    ///
+    /// The theme is written into the stylesheet within a ``prefers-color-scheme`` media query,
+    /// so a single document adapts to the dark mode of a browser or an operating system.
+    /// Without a [`set_theme()`](SvgDrawing::set_theme), the background is left transparent in the light mode.
+    pub fn set_dark_theme(&mut self, theme: Theme) { self.dark_theme = Some(theme); }
+
+    pub fn theme(&self) -> Option<&Theme> { self.theme.as_ref() }
+
+    /// Replaces every colour used in this drawing, by its elements and its themes, with `f(colour)`
+    pub fn map_colors<F: Fn(Color) -> Color>(&mut self, f: F) {
+        self.visit_styles_mut(&mut |style| style.map_colors(&f));
+        for theme in [&mut self.theme, &mut self.dark_theme].into_iter().flatten() {
+            theme.map_colors(&f);
         }
-        println!("</svg>");
     }
 
-    pub fn add_element(&mut self, el: Box<dyn ToSvg>) {
-        self.elements.push(el);
+    /// Recolours this drawing as seen by a person with the given colour vision deficiency.
+    ///
+    /// The original colours are lost; [`to_svg_with_cvd()`](SvgDrawing::to_svg_with_cvd) renders
+    /// the simulation without changing the drawing.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::colors::ColorVisionDeficiency;
+    /// use visualife::shapes::Circle;
+    /// use visualife::{SvgDrawing, ToSvg};
+    /// let mut drawing = SvgDrawing::new(10.0, 10.0);
+    /// let mut circle = Circle::new("c", 5.0, 5.0, 4.0);
+    /// circle.style.try_set_fill("red").unwrap();
+    /// drawing.add_element(Box::new(circle));
+    /// drawing.simulate_cvd(ColorVisionDeficiency::Deuteranopia);
+    /// assert!(drawing.to_svg().contains("fill:#A39000;"));
+    /// ```
+    pub fn simulate_cvd(&mut self, deficiency: ColorVisionDeficiency) {
+        self.map_colors(|c| c.simulate_cvd(deficiency));
+    }
+
+    /// Renders this drawing as seen by a person with the given colour vision deficiency.
+    ///
+    /// The drawing is not changed: its content is wrapped in a group with a colour matrix filter,
+    /// so everything, including raw elements and images, is shown as [`Color::simulate_cvd()`] would recolour it.
+    ///
+    /// # Example
+    /// ```
+    /// use visualife::colors::ColorVisionDeficiency;
+    /// use visualife::shapes::Circle;
+    /// use visualife::{SvgDrawing, ToSvg};
+    /// let mut drawing = SvgDrawing::new(10.0, 10.0);
+    /// let mut circle = Circle::new("c", 5.0, 5.0, 4.0);
+    /// circle.style.try_set_fill("red").unwrap();
+    /// drawing.add_element(Box::new(circle));
+    /// let simulated = drawing.to_svg_with_cvd(ColorVisionDeficiency::Deuteranopia);
+    /// assert!(simulated.contains(r#"<g filter="url(#cvd-simulation)">"#));
+    /// assert!(drawing.to_svg().contains("fill:#F00;"));
+    /// ```
+    pub fn to_svg_with_cvd(&self, deficiency: ColorVisionDeficiency) -> String {
+        const FILTER_ID: &str = "cvd-simulation";
+        let mut svg_string = self.svg_header();
+        svg_string.push_str(&format!("\n<defs>{}</defs>\n<g filter=\"url(#{})\">\n", deficiency.svg_filter(FILTER_ID), FILTER_ID));
+        self.push_content(&mut svg_string);
+        svg_string.push_str("</g>\n</svg>");
+
+        svg_string
     }
 
     pub fn svg_header(&self) -> String {
-        format!(r#"<svg width="{}" height="{}" xmlns="http://www.w3.org/2000/svg">"#, self.width, self.height).to_string()
+        let mut header = format!(r#"<svg width="{}" height="{}" xmlns="http://www.w3.org/2000/svg" xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape""#, self.width, self.height);
+        for (prefix, uri) in &self.namespaces {
+            header.push_str(&format!(r#" xmlns:{}="{}""#, prefix, escape(uri, true)));
+        }
+        push_class(&self.class, &mut header);
+        push_attributes(&self.attributes, &mut header);
+        if !self.style.is_empty() {
+            header.push_str(&format!(r#" style="{}""#, escape(&self.style.to_string(), true)));
+        }
+        header.push('>');
+        header
+    }
+
+    /// Appends the stylesheet and the layers that hold any elements, each on its own line
+    fn push_content(&self, svg_string: &mut String) {
+        if let Some(stylesheet) = self.svg_stylesheet() {
+            svg_string.push_str(&stylesheet);
+            svg_string.push('\n');
+        }
+        for layer in self.layers().filter(|l| !l.elements.is_empty()) {
+            svg_string.push_str(&layer.to_svg());
+            svg_string.push('\n');
+        }
+    }
+
+    fn svg_stylesheet(&self) -> Option<String> {
+        if self.theme.is_none() && self.dark_theme.is_none() { return None; }
+        // ---------- the background stays transparent in a colour scheme no theme is set for
+        let mut rules = vec![format!(".{} {{ fill:none; }}", BACKGROUND_CLASS)];
+        if let Some(theme) = &self.theme { rules.push(theme.css()); }
+        if let Some(theme) = &self.dark_theme { rules.push(theme.dark_mode_css()); }
+
+        Some(format!("<style>\n{}\n</style>\n<rect class=\"{}\" width=\"100%\" height=\"100%\" />",
+                     rules.join("\n"), BACKGROUND_CLASS))
+    }
+}
+
+impl ToSvg for SvgDrawing {
+    fn tag(&self) -> Option<&str> { Some("svg") }
+
+    fn class(&self) -> Option<&str> { self.class.as_deref() }
+
+    fn style(&self) -> Option<&Style> { Some(&self.style) }
+
+    fn nested(&self) -> Box<dyn Iterator<Item = &dyn ToSvg> + '_> {
+        Box::new(self.layers.iter().map(|layer| layer as &dyn ToSvg))
+    }
+
+    fn style_mut(&mut self) -> Option<&mut Style> { Some(&mut self.style) }
+
+    fn visit_styles_mut(&mut self, f: &mut dyn FnMut(&mut Style)) {
+        f(&mut self.style);
+        for layer in self.layers.iter_mut() { layer.visit_styles_mut(f); }
+    }
+
+    fn to_svg(&self) -> String {
+        let mut svg_string = self.svg_header();
+        svg_string.push('\n');
+        self.push_content(&mut svg_string);
+        svg_string.push_str("</svg>");
+
+        svg_string
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::geometry::Point;
+    use crate::layer::{Layer, DATA_LAYER, OVERLAY_LAYER};
+    use crate::shapes::Circle;
+    use crate::theme::Theme;
+    use crate::{SpatialIndex, SvgDrawing, ToSvg};
+
+    #[test]
+    fn themed_drawing() {
+        let mut drawing = SvgDrawing::new(10.0, 10.0);
+        drawing.add_element(Box::new(Circle::new("c", 5.0, 5.0, 1.0)));
+        assert_eq!(drawing.to_svg().lines().count(), 5);
+
+        drawing.set_theme(Theme::light());
+        drawing.set_dark_theme(Theme::dark());
+        let svg = drawing.to_svg();
+        assert!(svg.contains("<style>\n.background { fill:none; }\nsvg { color:#222;"));
+        assert!(svg.contains("@media (prefers-color-scheme: dark)"));
+        assert!(svg.contains(r#"<rect class="background" width="100%" height="100%" />"#));
+        assert!(svg.ends_with("<g id=\"layer-data\" inkscape:groupmode=\"layer\" inkscape:label=\"data\">\n\t<circle id=\"c\" cx=\"5\" cy=\"5\" r=\"1\" />\n</g>\n</svg>"));
+
+        // ---------- with only a dark theme, the background is not painted in the light colour scheme
+        let mut drawing = SvgDrawing::new(10.0, 10.0);
+        drawing.set_dark_theme(Theme::dark());
+        let svg = drawing.to_svg();
+        assert!(svg.find(".background { fill:none; }").unwrap() < svg.find("@media").unwrap());
+    }
+
+    #[test]
+    fn layers_in_order() {
+        let mut drawing = SvgDrawing::new(10.0, 10.0);
+        drawing.add_to_layer(OVERLAY_LAYER, Box::new(Circle::new("top", 5.0, 5.0, 1.0)));
+        drawing.add_to_layer("my & \"custom\"", Box::new(Circle::new("new", 5.0, 5.0, 1.0)));
+        drawing.add_element(Box::new(Circle::new("d", 5.0, 5.0, 1.0)));
+        let names = |d: &SvgDrawing| d.layers().map(|l| l.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&drawing), vec!["background", "data", "annotations", "overlay", "my & \"custom\""]);
+
+        drawing.set_layer_order("my & \"custom\"", -5);
+        drawing.set_layer_order(DATA_LAYER, 100);
+        assert_eq!(names(&drawing), vec!["my & \"custom\"", "background", "annotations", "overlay", "data"]);
+        let mut replacement = Layer::new(OVERLAY_LAYER, 30);
+        replacement.add_element(Box::new(Circle::new("other", 5.0, 5.0, 1.0)));
+        let old = drawing.add_layer(replacement).unwrap();
+        assert_eq!(old.elements.len(), 1);
+        assert_eq!(names(&drawing), vec!["my & \"custom\"", "background", "annotations", "overlay", "data"]);
+
+        // ---------- a hidden layer is kept in the document; the style of a layer cascades to its elements
+        let custom = drawing.layer_mut("my & \"custom\"").unwrap();
+        custom.visible = false;
+        custom.style.try_set_fill("red").unwrap();
+        assert_eq!(drawing.computed_style("new").unwrap().to_string(), "fill:#F00;");
+        let svg = drawing.to_svg();
+        assert!(svg.starts_with(r#"<svg width="10" height="10" xmlns="http://www.w3.org/2000/svg" xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape">
+<g id="layer-my----custom-" inkscape:groupmode="layer" inkscape:label="my &amp; &quot;custom&quot;" style="display:none;fill:#F00;">"#));
+        assert!(svg.find("other").unwrap() < svg.find("\"d\"").unwrap());
+        assert!(!svg.contains("top") && !svg.contains("background"));
+
+        // ---------- elements of a hidden layer are still found, but they are not drawn
+        assert!(drawing.get_by_id("new").is_some());
+        let index = SpatialIndex::new(&drawing);
+        assert_eq!(index.elements_at(Point::new(5.0, 5.0)), vec!["other", "d"]);
+        drawing.layer_mut("my & \"custom\"").unwrap().add_element(Box::new(Circle::new("far", 50.0, 50.0, 1.0)));
+        assert_eq!(drawing.bbox().unwrap().max, Point::new(6.0, 6.0));
+
+        assert!(drawing.remove_layer(DATA_LAYER).is_some());
+        assert!(drawing.get_by_id("d").is_none());
+    }
+
+    #[test]
+    fn layer_ids_dont_shadow_elements() {
+        let mut drawing = SvgDrawing::new(10.0, 10.0);
+        drawing.add_element(Box::new(Circle::new("data", 5.0, 5.0, 1.0)));
+        assert!(drawing.get_by_id("data").unwrap().is::<Circle>());
+        assert!(drawing.get_by_id_mut("layer-data").unwrap().is::<Layer>());
+        assert_eq!(drawing.select("g > circle").unwrap().len(), 1);
+        assert_eq!(drawing.select_mut("#layer-data", |_| {}).unwrap(), 1);
+    }
+
+    #[test]
+    fn unique_layer_ids() {
+        let mut drawing = SvgDrawing::new(10.0, 10.0);
+        for name in ["a b", "a-b", "a.b"] { drawing.add_to_layer(name, Box::new(Circle::new(name, 5.0, 5.0, 1.0))); }
+        let ids: Vec<&str> = drawing.layers().map(|l| l.id.as_str()).filter(|id| id.starts_with("layer-a-")).collect();
+        assert_eq!(ids, ["layer-a-b", "layer-a-b-2", "layer-a-b-3"]);
+        drawing.get_by_id_mut("layer-a-b-2").unwrap().style_mut().unwrap().set_opacity(0.5);
+        assert_eq!(drawing.layer("a-b").unwrap().style.opacity, Some(0.5));
+        // ---------- a layer that replaces another one of the same name takes its id back
+        drawing.add_layer(Layer::new("a b", 0));
+        assert_eq!(drawing.layer("a b").unwrap().id, "layer-a-b");
     }
 }
@@ -0,0 +1,157 @@
+//! Colour and line themes for drawings and mindmaps.
+//!
+//! A [`Theme`] gathers the decisions that make a figure look consistent: its background and
+//! foreground colours, a categorical palette, the font and the styles of mindmap nodes and
+//! connectors. A theme can be set on an [`SvgDrawing`](crate::SvgDrawing), which writes it into
+//! the document as a CSS stylesheet, or on a [`Mindmap`](crate::mindmap::Mindmap), which applies
+//! its styles directly to the elements it renders.
+//!
+//! # Example
+//! ```
+//! use visualife::theme::Theme;
+//! use visualife::SvgDrawing;
+//! let mut drawing = SvgDrawing::new(100.0, 100.0);
+//! drawing.set_theme(Theme::light());
+//! // --- switch to the dark variant when the viewer prefers a dark colour scheme
+//! drawing.set_dark_theme(Theme::dark());
+//! ```
+use std::fmt::Write;
+use crate::colors::{Color, Palette};
+use crate::style::{Paint, Style};
+
+/// CSS class of the background rectangle a themed drawing starts with
+pub const BACKGROUND_CLASS: &str = "background";
+/// CSS class of the group holding mindmap nodes
+pub const MINDMAP_NODES_CLASS: &str = "mindmap-nodes";
+/// CSS class of the group holding mindmap connectors
+pub const MINDMAP_CONNECTORS_CLASS: &str = "mindmap-connectors";
+
+/// A set of colours, fonts and styles applied to a figure as a whole
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    pub name: String,
+    /// colour of the drawing's background
+    pub background: Color,
+    /// colour of text and outlines
+    pub foreground: Color,
+    /// categorical colours, e.g. for mindmap branches or plot series
+    pub palette: Palette,
+    pub font_family: String,
+    pub font_size: f32,
+    /// width of regular lines
+    pub stroke_width: f32,
+    /// width of secondary lines, such as grid lines or thin outlines
+    pub thin_stroke_width: f32,
+    /// style of mindmap nodes
+    pub node_style: Style,
+    /// style of mindmap connectors
+    pub connector_style: Style,
+}
+
+impl Theme {
+    /// Dark text and lines on a white background
+    pub fn light() -> Self {
+        Theme::build("light", 0xFFFFFF, 0x222222, Palette::tableau10(), "sans-serif", 12.0, 1.5, 0.5)
+    }
+
+    /// Light text and lines on a dark grey background
+    pub fn dark() -> Self {
+        // --- Tableau colours brightened, so they stand out against the dark background
+        let palette = [0x8CB4E1, 0xFFB062, 0xFF8A8C, 0x9FE0DA, 0x8ACF7E,
+            0xFFE27A, 0xD6A8CB, 0xFFC2CA, 0xC9A38A, 0xD9D1CB].map(Color::from_u32);
+        Theme::build("dark", 0x1E1E1E, 0xE0E0E0, Palette::new(&palette).unwrap(), "sans-serif", 12.0, 1.5, 0.5)
+    }
+
+    /// Black on white with thicker lines and a colour-blind safe palette, for printed figures
+    pub fn print() -> Self {
+        Theme::build("print", 0xFFFFFF, 0x000000, Palette::okabe_ito(), "serif", 10.0, 2.0, 1.0)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn build(name: &str, background: u32, foreground: u32, palette: Palette, font_family: &str,
+             font_size: f32, stroke_width: f32, thin_stroke_width: f32) -> Self {
+        let (background, foreground) = (Color::from_u32(background), Color::from_u32(foreground));
+        let mut node_style = Style::new();
+        node_style.set_fill(palette.color(0));
+        node_style.set_stroke(foreground);
+        node_style.set_stroke_width(thin_stroke_width);
+        let mut connector_style = Style::new();
+        connector_style.set_fill(foreground);
+        connector_style.set_fill_opacity(0.6);
+        connector_style.set_stroke(Paint::None);
+
+        Theme {
+            name: name.to_string(),
+            background,
+            foreground,
+            palette,
+            font_family: font_family.to_string(),
+            font_size,
+            stroke_width,
+            thin_stroke_width,
+            node_style,
+            connector_style,
+        }
+    }
+
+    /// Replaces every colour of this theme with `f(colour)`
+    pub fn map_colors<F: Fn(Color) -> Color>(&mut self, f: F) {
+        self.background = f(self.background);
+        self.foreground = f(self.foreground);
+        self.palette.map_colors(&f);
+        self.node_style.map_colors(&f);
+        self.connector_style.map_colors(&f);
+    }
+
+    /// CSS rules that apply this theme to a document.
+    ///
+    /// The rules style the document root, the background rectangle, text and the
+    /// [`MINDMAP_NODES_CLASS`] and [`MINDMAP_CONNECTORS_CLASS`] groups.
+    pub fn css(&self) -> String {
+        let mut css = String::new();
+        writeln!(css, "svg {{ color:{}; font-family:{}; font-size:{}px; }}",
+                 self.foreground, self.font_family, self.font_size).unwrap();
+        writeln!(css, ".{} {{ fill:{}; }}", BACKGROUND_CLASS, self.background).unwrap();
+        writeln!(css, "text {{ fill:{}; }}", self.foreground).unwrap();
+        writeln!(css, ".{} {{ {} }}", MINDMAP_NODES_CLASS, self.node_style).unwrap();
+        write!(css, ".{} {{ {} }}", MINDMAP_CONNECTORS_CLASS, self.connector_style).unwrap();
+
+        css
+    }
+
+    /// CSS rules that apply this theme only when the viewer prefers a dark colour scheme.
+    ///
+    /// The rules are wrapped in a ``prefers-color-scheme: dark`` media query, so a document
+    /// can carry both a light and a dark variant.
+    pub fn dark_mode_css(&self) -> String {
+        let mut css = String::from("@media (prefers-color-scheme: dark) {\n");
+        for rule in self.css().lines() {
+            writeln!(css, "  {}", rule).unwrap();
+        }
+        css.push('}');
+
+        css
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self { Theme::light() }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::theme::Theme;
+
+    #[test]
+    fn theme_css() {
+        let theme = Theme::light();
+        let css = theme.css();
+        assert!(css.contains(".background { fill:#FFF; }"));
+        assert!(css.contains(".mindmap-nodes { fill:#4E79A7;stroke:#222;stroke-width:0.5; }"));
+
+        let dark = Theme::dark().dark_mode_css();
+        assert!(dark.starts_with("@media (prefers-color-scheme: dark) {\n  svg {"));
+        assert!(dark.ends_with("}\n}"));
+        assert_eq!(theme.palette.color(12), theme.palette.colors()[2]);
+    }
+}
@@ -17,20 +17,19 @@ mod test_elements {
 
     #[test]
     fn test_path() {
-        let mut p = Path::from_str("p1", "M 100 100 L 300 100 L 200 300 Z");
-        assert_eq!(p.to_svg(), r#"<path id="p1" d="M 100 100 L 300 100 L 200 300 Z " />"#);
+        let mut p = Path::from_str("p1", "M 100 100 L 300 100 L 200 300 Z").unwrap();
+        assert_eq!(p.to_svg(), r#"<path id="p1" d="M 100 100 L 300 100 L 200 300 Z" />"#);
 
-        p.style.set_stroke("#000000");
-        assert_eq!(p.to_svg(), r#"<path id="p1" d="M 100 100 L 300 100 L 200 300 Z " style="stroke:#000000;" />"#);
+        p.style.try_set_stroke("#000000").unwrap();
+        assert_eq!(p.to_svg(), r#"<path id="p1" d="M 100 100 L 300 100 L 200 300 Z" style="stroke:#000;" />"#);
 
         let p = Path::new("p1").move_to(100.0, 100.0).line_to(300.0, 100.0).line_to(200.0, 300.0).close();
-        assert_eq!(p.to_svg(), r#"<path id="p1" d="M 100 100 L 300 100 L 200 300 Z " />"#);
+        assert_eq!(p.to_svg(), r#"<path id="p1" d="M 100 100 L 300 100 L 200 300 Z" />"#);
     }
 
     #[test]
     fn test_group() {
         use visualife::shapes::{Circle, Group};
-        use visualife::style::Style;
         use visualife::ToSvg;
         let mut g = Group::new("my_group");
         g.add_element(Box::new(Circle::new("my_circle", 100.0, 50.0, 10.0)));
@@ -43,4 +42,121 @@ mod test_elements {
         assert_eq!(svg, expected1);
         println!("{}", svg);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_bounding_boxes() {
+        use visualife::geometry::{BBox, Degrees, Point};
+        use visualife::shapes::Group;
+        let mut r = Rect::new("r", 0.0, 0.0, 10.0, 10.0);
+        assert_eq!(r.bbox(), Some(BBox::new(Point::new(0.0, 0.0), Point::new(10.0, 10.0))));
+        r.angle = Degrees(45.0);
+        let b = r.bbox().unwrap();
+        assert!((b.width() - 200f32.sqrt()).abs() < 1e-4 && b.center().distance(Point::new(5.0, 5.0)) < 1e-4);
+        // ---------- the box of a curve is tight, its control points stick out of it
+        let p = Path::from_str("p", "M 0 0 C 0 -40 40 -40 40 0").unwrap();
+        let b = p.bbox().unwrap();
+        assert!((b.min.y + 30.0).abs() < 1e-4 && b.max == Point::new(40.0, 0.0));
+        let mut g = Group::new("g");
+        assert_eq!(g.bbox(), None);
+        g.add_element(Box::new(Circle::new("c", 100.0, 100.0, 10.0)));
+        g.add_element(Box::new(p));
+        assert_eq!(g.bbox(), Some(BBox::new(Point::new(0.0, -30.0), Point::new(110.0, 110.0))));
+    }
+
+    #[test]
+    fn find_and_change_elements() {
+        use visualife::shapes::Group;
+        use visualife::SvgDrawing;
+        let mut drawing = SvgDrawing::new(100.0, 100.0);
+        let mut outer = Group::new("outer");
+        let mut inner = Group::new("inner");
+        inner.add_element(Box::new(Circle::new("c", 10.0, 10.0, 5.0)));
+        inner.add_element(Box::new(Rect::new("r", 0.0, 0.0, 5.0, 5.0)));
+        outer.add_element(Box::new(inner));
+        drawing.add_element(Box::new(outer));
+        drawing.add_element(Box::new(Path::from_str("p", "M 0 0 L 10 10").unwrap()));
+
+        let ids: Vec<&str> = drawing.descendants().filter_map(|el| el.id()).collect();
+        assert_eq!(ids, vec!["layer-background", "layer-data", "outer", "inner", "c", "r", "p", "layer-annotations", "layer-overlay"]);
+        assert!(drawing.get_by_id("r").unwrap().is::<Rect>());
+        assert!(drawing.get_by_id("missing").is_none());
+
+        drawing.get_by_id_mut("c").and_then(|el| el.downcast_mut::<Circle>()).unwrap().radius = 7.0;
+        assert!(drawing.to_svg().contains(r#"<circle id="c" cx="10" cy="10" r="7" />"#));
+
+        let old = drawing.replace("r", Box::new(Circle::new("r2", 0.0, 0.0, 1.0))).unwrap();
+        assert_eq!(old.downcast_ref::<Rect>().unwrap().width, 5.0);
+        assert!(drawing.replace("r", Box::new(Circle::new("x", 0.0, 0.0, 1.0))).is_none());
+
+        let removed = drawing.remove_by_id("r2").unwrap();
+        assert_eq!(removed.id(), Some("r2"));
+        assert!(drawing.remove_by_id("p").is_some());
+        let ids: Vec<&str> = drawing.descendants().filter_map(|el| el.id()).collect();
+        assert_eq!(ids, vec!["layer-background", "layer-data", "outer", "inner", "c", "layer-annotations", "layer-overlay"]);
+        assert!(drawing.remove_by_id("layer-data").is_none() && drawing.replace("layer-data", Box::new(Group::new("g"))).is_none());
+
+        // ---------- elements nested in any container can be removed, not only in groups
+        use visualife::shapes::RawElement;
+        let mut link = RawElement::new("a", vec![("href".to_string(), "#c".to_string())]);
+        link.children.push(Box::new(Circle::new("linked", 0.0, 0.0, 1.0)));
+        drawing.add_element(Box::new(link));
+        assert!(drawing.get_by_id("linked").is_some());
+        assert_eq!(drawing.remove_by_id("linked").unwrap().id(), Some("linked"));
+        assert!(drawing.get_by_id("linked").is_none());
+    }
+
+    #[test]
+    fn select_elements() {
+        use visualife::shapes::Group;
+        let mut g = Group::new("g");
+        g.class = Some("layer".to_string());
+        let mut shapes = Group::new("shapes");
+        let mut marked = Circle::new("c1", 0.0, 0.0, 1.0);
+        marked.class = Some("marked big".to_string());
+        shapes.add_element(Box::new(marked));
+        shapes.add_element(Box::new(Circle::new("c2", 0.0, 0.0, 1.0)));
+        g.add_element(Box::new(shapes));
+        g.add_element(Box::new(Circle::new("c3", 0.0, 0.0, 1.0)));
+
+        let ids = |found: Vec<&dyn ToSvg>| found.iter().filter_map(|el| el.id().map(str::to_string)).collect::<Vec<_>>();
+        assert_eq!(ids(g.select("circle").unwrap()), vec!["c1", "c2", "c3"]);
+        assert_eq!(ids(g.select("g.layer > circle").unwrap()), vec!["c3"]);
+        assert_eq!(ids(g.select("#shapes circle.marked, #c3").unwrap()), vec!["c1", "c3"]);
+        assert!(g.select("circle::before").is_err());
+
+        let n = g.select_mut(".marked", |el| el.style_mut().unwrap().try_set_fill("red").unwrap()).unwrap();
+        assert_eq!(n, 1);
+        assert!(g.to_svg().contains(r#"<circle id="c1" cx="0" cy="0" r="1" class="marked big" style="fill:#F00;" />"#));
+    }
+
+    #[test]
+    fn import_and_modify_svg() {
+        use visualife::SvgDrawing;
+        let svg = r##"<?xml version="1.0" encoding="UTF-8"?>
+<svg xmlns="http://www.w3.org/2000/svg" xmlns:inkscape="http://www.inkscape.org/namespaces/inkscape" width="100" height="100">
+  <defs><linearGradient id="grad"><stop offset="0" stop-color="red"/></linearGradient></defs>
+  <g id="layer1" inkscape:groupmode="layer" inkscape:label="Shapes">
+    <rect id="box" x="10" y="10" width="30" height="20" style="fill:url(#grad);stroke:#000"/>
+    <circle id="dot" cx="70" cy="70" r="10" fill="blue" data-value="3" style="stroke-linecap:round"/>
+    <text x="5" y="95">T &amp; <tspan font-weight="bold">C</tspan></text>
+  </g>
+</svg>"##;
+        let mut drawing = SvgDrawing::from_svg(svg).unwrap();
+        assert_eq!(drawing.layers().map(|l| l.name.as_str()).collect::<Vec<_>>(), vec!["data", "Shapes"]);
+        assert_eq!(drawing.layer("Shapes").unwrap().id, "layer1");
+        let rect = drawing.get_by_id_mut("box").and_then(|el| el.downcast_mut::<Rect>()).unwrap();
+        rect.width = 50.0;
+        // ---------- attributes and style properties visualife has no field for are kept on the circle
+        let dot = drawing.get_by_id_mut("dot").and_then(|el| el.downcast_mut::<Circle>()).unwrap();
+        dot.radius = 12.0;
+        assert_eq!(dot.style.other, vec![("stroke-linecap".to_string(), "round".to_string())]);
+
+        let out = drawing.to_svg();
+        assert!(out.contains(r#"<defs><linearGradient id="grad"><stop offset="0" stop-color="red" /></linearGradient></defs>"#));
+        assert!(out.contains(r#"<g id="layer1" inkscape:groupmode="layer" inkscape:label="Shapes">"#));
+        assert!(out.contains(r#"<rect id="box" x="10" y="10" width="50" height="20" style="fill:url(#grad);stroke:#000;" />"#));
+        assert!(out.contains(r#"<circle id="dot" cx="70" cy="70" r="12" data-value="3" style="fill:#00F;stroke-linecap:round;" />"#));
+        assert!(out.contains(r#"<text x="5" y="95">T &amp; <tspan font-weight="bold">C</tspan></text>"#));
+        assert!(SvgDrawing::from_svg(&out).unwrap().to_svg() == out);
+    }
+}
@@ -1,12 +1,14 @@
 #[cfg(test)]
 mod test_mindmap {
     use visualife::{mindmap, SvgDrawing, ToSvg};
+    use visualife::geometry::{Degrees, Point};
+    use visualife::theme::Theme;
 
     #[test]
     fn two_nodes() {
         let mut mndmp = mindmap::Mindmap::new("a_mindmap", 45.0);
-        mndmp.place_node("n1", "Node 1", 100.0, 100.0);
-        mndmp.place_node("n2", "Node 2", 180.0, 180.0);
+        mndmp.place_node("n1", "Node 1", Point::new(100.0, 100.0));
+        mndmp.place_node("n2", "Node 2", Point::new(180.0, 180.0));
         mndmp.connect_nodes("n1", "n2");
         let svg = mndmp.to_svg();
         assert_eq!(svg.lines().count(), 13);
@@ -15,25 +17,67 @@ mod test_mindmap {
     #[test]
     fn grow_nodes() {
         let mut mndmp = mindmap::Mindmap::new("a_mindmap", 50.0);
-        mndmp.place_node("n0", "Center node", 100.0, 100.0);
+        mndmp.place_node("n0", "Center node", Point::new(100.0, 100.0));
         for i in 1..=7 {
-            mndmp.grow_node(&format!("n:{i}"), &format!("Node {i}"), 360.0/7.0 * i as f32, "n0");
+            mndmp.grow_node(&format!("n:{i}"), &format!("Node {i}"), Degrees(360.0/7.0 * i as f32), "n0");
         }
         let svg = mndmp.to_svg();
-        assert_eq!(svg.lines().count(), 14);
+        assert_eq!(svg.lines().count(), 49);
     }
 
 
+    #[test]
+    fn grow_node_takes_degrees() {
+        let mut mndmp = mindmap::Mindmap::new("a_mindmap", 10.0);
+        mndmp.place_node("n0", "Center", Point::new(100.0, 100.0));
+        mndmp.grow_node("n1", "Right", Degrees(0.0), "n0");
+        mndmp.grow_node("n2", "Left", Degrees(180.0), "n0");
+        let b = mndmp.bbox().unwrap();
+        // ---------- children of radius 8 are placed 30 units left and right of the center
+        assert!((b.min.x - 62.0).abs() < 1e-3 && (b.max.x - 138.0).abs() < 1e-3);
+        assert!((b.center().y - 100.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn themed_mindmap() {
+        let mut mndmp = mindmap::Mindmap::new("a_mindmap", 45.0);
+        mndmp.place_node("n1", "Node 1", Point::new(100.0, 100.0));
+        mndmp.place_node("n2", "Node 2", Point::new(180.0, 180.0));
+        mndmp.connect_nodes("n1", "n2");
+        let svg = mndmp.to_svg();
+        assert!(svg.contains(r#"<g id="nodes-a_mindmap" class="mindmap-nodes">"#));
+
+        mndmp.set_theme(&Theme::dark());
+        let svg = mndmp.to_svg();
+        assert!(svg.contains(r#"<g id="nodes-a_mindmap" class="mindmap-nodes" style="fill:#8CB4E1;stroke:#E0E0E0;stroke-width:0.5;">"#));
+        assert!(svg.contains(r#"<g id="connectors-a_mindmap" class="mindmap-connectors" style="fill:#E0E0E0;stroke:none;fill-opacity:0.6;">"#));
+    }
+
     #[test]
     fn small_mindmap() {
         let mut mndmp = mindmap::Mindmap::new("a_mindmap", 50.0);
-        mndmp.place_node("n0", "Center", 250.0, 250.0);
+        mndmp.place_node("n0", "Center", Point::new(250.0, 250.0));
         for i in 1..=5 {
-            mndmp.grow_node(&format!("n:{i}"), &format!("Node {i}"), 360.0 / 7.0 * i as f32, "n0");
+            mndmp.grow_node(&format!("n:{i}"), &format!("Node {i}"), Degrees(360.0 / 7.0 * i as f32), "n0");
         }
 
         let mut drawing = SvgDrawing::new(500.0, 500.0);
         drawing.add_element(Box::new(mndmp));
         drawing.draw();
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn highlight_node() {
+        let mut mndmp = mindmap::Mindmap::new("m", 20.0);
+        mndmp.place_node("root", "Root", Point::new(100.0, 100.0));
+        mndmp.grow_node("leaf", "Leaf", Degrees(0.0), "root");
+        let plain = mndmp.to_svg();
+        let mut drawing = SvgDrawing::new(200.0, 200.0);
+        drawing.add_element(Box::new(mndmp.to_group()));
+        assert_eq!(drawing.get_by_id("m").unwrap().to_svg(), plain);
+
+        let n = drawing.select_mut("g.mindmap-nodes > #leaf", |node| node.style_mut().unwrap().try_set_fill("orange").unwrap()).unwrap();
+        assert_eq!(n, 1);
+        assert!(drawing.to_svg().contains(r#"<circle id="leaf" cx="160" cy="100" r="16" style="fill:#FFA500;" />"#));
+    }
+}